@@ -0,0 +1,76 @@
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this
+// file, You can obtain one at http://mozilla.org/MPL/2.0/.
+//
+// Copyright (c) DUSK NETWORK. All rights reserved.
+
+//! Compares the multi-scalar multiplications of the commitments and of the
+//! setup of the public parameters with the ones of `dusk-bls12_381`.
+
+use criterion::{criterion_group, criterion_main, BenchmarkId, Criterion};
+use dusk_plonk::bls12_381::{
+    multiscalar_mul::msm_variable_base, BlsScalar, G1Affine, G1Projective,
+};
+use dusk_plonk::commitment_scheme::kzg10::msm::{pippenger, FixedBaseTable};
+use rayon::prelude::*;
+
+fn random_inputs(len: usize) -> (Vec<G1Affine>, Vec<BlsScalar>) {
+    let mut rng = rand::thread_rng();
+    let scalars: Vec<BlsScalar> = (0..len).map(|_| BlsScalar::random(&mut rng)).collect();
+    let points: Vec<G1Projective> = scalars
+        .par_iter()
+        .map(|scalar| G1Affine::generator() * scalar)
+        .collect();
+    let mut normalised = vec![G1Affine::identity(); len];
+    G1Projective::batch_normalize(&points, &mut normalised);
+    (normalised, scalars)
+}
+
+fn variable_base(c: &mut Criterion) {
+    let mut group = c.benchmark_group("variable base msm");
+    group.sample_size(10);
+    for log_len in &[10, 13, 16] {
+        let (points, scalars) = random_inputs(1 << log_len);
+        group.bench_with_input(
+            BenchmarkId::new("msm_variable_base", log_len),
+            &(&points, &scalars),
+            |b, (points, scalars)| b.iter(|| msm_variable_base(points, scalars)),
+        );
+        group.bench_with_input(
+            BenchmarkId::new("pippenger", log_len),
+            &(&points, &scalars),
+            |b, (points, scalars)| b.iter(|| pippenger(points, scalars)),
+        );
+    }
+    group.finish();
+}
+
+fn fixed_base(c: &mut Criterion) {
+    let mut group = c.benchmark_group("fixed base msm");
+    group.sample_size(10);
+    let base = G1Projective::generator();
+    for log_len in &[10, 13, 16] {
+        let (_, scalars) = random_inputs(1 << log_len);
+        group.bench_with_input(
+            BenchmarkId::new("scalar multiplications", log_len),
+            &scalars,
+            |b, scalars| {
+                b.iter(|| {
+                    scalars
+                        .par_iter()
+                        .map(|scalar| base * scalar)
+                        .collect::<Vec<_>>()
+                })
+            },
+        );
+        group.bench_with_input(
+            BenchmarkId::new("FixedBaseTable", log_len),
+            &scalars,
+            |b, scalars| b.iter(|| FixedBaseTable::new(base, scalars.len()).batch_mul(scalars)),
+        );
+    }
+    group.finish();
+}
+
+criterion_group!(benches, variable_base, fixed_base);
+criterion_main!(benches);
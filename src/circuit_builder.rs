@@ -11,152 +11,75 @@ use crate::constraint_system::StandardComposer;
 use crate::proof_system::{Proof, ProverKey, VerifierKey};
 use anyhow::Result;
 use dusk_bls12_381::BlsScalar;
-use dusk_jubjub::{JubJubAffine, JubJubScalar};
 use thiserror::Error;
 
-const BLS_SCALAR: u8 = 1;
-const JUBJUB_SCALAR: u8 = 2;
-const JUBJUB_AFFINE: u8 = 3;
-
-/// Public Input
-#[derive(Debug, Copy, Clone)]
-pub enum PublicInput {
-    /// Scalar Input
-    BlsScalar(BlsScalar, usize),
-    /// Embedded Scalar Input
-    JubJubScalar(JubJubScalar, usize),
-    /// Point as Public Input
-    AffinePoint(JubJubAffine, usize, usize),
+/// The circuit synthesized by `Circuit::synthesize`, along with the handles
+/// of the variables its witness is assigned to.
+#[derive(Debug, Clone)]
+pub struct CircuitShape<V> {
+    composer: StandardComposer,
+    variables: V,
 }
 
-impl PublicInput {
-    /// Returns the serialized-size of the `PublicInput` structure.
-    pub const fn serialized_size() -> usize {
-        33usize
+impl<V> CircuitShape<V> {
+    /// Returns the composer holding the gates of the circuit.
+    pub fn composer(&self) -> &StandardComposer {
+        &self.composer
     }
 
-    /// Returns the byte-representation of a [`PublicInput`].
-    /// Note that the underlying variants of this enum have different
-    /// sizes on it's byte-representation. Therefore, we need to return
-    /// the biggest one to set it as the default one.
-    pub fn to_bytes(&self) -> [u8; Self::serialized_size()] {
-        let mut bytes = [0u8; Self::serialized_size()];
-        match self {
-            Self::BlsScalar(scalar, _) => {
-                bytes[0] = BLS_SCALAR;
-                bytes[1..33].copy_from_slice(&scalar.to_bytes());
-                bytes
-            }
-            Self::JubJubScalar(scalar, _) => {
-                bytes[0] = JUBJUB_SCALAR;
-                bytes[1..33].copy_from_slice(&scalar.to_bytes());
-                bytes
-            }
-            Self::AffinePoint(point, _, _) => {
-                bytes[0] = JUBJUB_AFFINE;
-                bytes[1..Self::serialized_size()].copy_from_slice(&point.to_bytes());
-                bytes
-            }
-        }
-    }
-
-    /// Generate a [`PublicInput`] structure from it's byte representation.
-    pub fn from_bytes(bytes: &[u8]) -> Result<Self, CircuitErrors> {
-        if bytes.len() < Self::serialized_size() {
-            return Err(CircuitErrors::InvalidPublicInputBytes.into());
-        } else {
-            let mut array_bytes = [0u8; 32];
-            array_bytes.copy_from_slice(&bytes[1..Self::serialized_size()]);
-            match bytes[0] {
-                BLS_SCALAR => Ok(Self::BlsScalar(
-                    Option::from(BlsScalar::from_bytes(&array_bytes))
-                        .ok_or_else(|| CircuitErrors::InvalidPublicInputBytes.into())?,
-                    0,
-                )),
-                JUBJUB_SCALAR => Ok(Self::JubJubScalar(
-                    Option::from(JubJubScalar::from_bytes(&array_bytes))
-                        .ok_or_else(|| CircuitErrors::InvalidPublicInputBytes.into())?,
-                    0,
-                )),
-                JUBJUB_AFFINE => Ok(Self::AffinePoint(
-                    Option::from(JubJubAffine::from_bytes(array_bytes))
-                        .ok_or_else(|| CircuitErrors::InvalidPublicInputBytes.into())?,
-                    0,
-                    0,
-                )),
-                _ => unreachable!(),
-            }
-        }
-    }
-
-    /// Returns the positions that of a PublicInput struct
-    fn pos(&self) -> [usize; 2] {
-        match self {
-            PublicInput::BlsScalar(_, pos) => [*pos, 0],
-            PublicInput::JubJubScalar(_, pos) => [*pos, 0],
-            PublicInput::AffinePoint(_, pos_x, pos_y) => [*pos_x, *pos_y],
-        }
+    /// Returns the handles of the variables returned by `Circuit::synthesize`.
+    pub fn variables(&self) -> &V {
+        &self.variables
     }
 }
 
 /// Circuit representation for a gadget with all of the tools that it
 /// should implement.
+///
+/// The circuit is synthesized once by `synthesize` in `compile`, before any
+/// witness is known, and its shape is stored in the circuit. The witness of
+/// each proof is then assigned to that shape by `assign` in `gen_proof`.
 pub trait Circuit<'a>
 where
     Self: Sized,
 {
-    /// Gadget implementation used to fill the composer.
-    fn gadget(&mut self, composer: &mut StandardComposer) -> Result<()>;
+    /// Handles of the variables of the circuit whose values are set by
+    /// `assign`.
+    type Variables;
+
+    /// Adds the gates of the circuit to the composer and returns the handles
+    /// of its inputs.
+    ///
+    /// No witness is known at this point: the inputs are allocated with
+    /// `StandardComposer::add_input` and the public inputs declared by name
+    /// with `StandardComposer::public_input`, both holding zero, and their
+    /// values must not be read.
+    fn synthesize(&self, composer: &mut StandardComposer) -> Result<Self::Variables>;
+
+    /// Sets the values of the `variables` returned by `synthesize`, public
+    /// inputs included, with `StandardComposer::assign`.
+    ///
+    /// The variables derived from them by the gadgets are computed afterwards
+    /// by `StandardComposer::generate_witness`, so no gate may be added.
+    fn assign(&self, composer: &mut StandardComposer, variables: &Self::Variables) -> Result<()>;
+
     /// Compiles the circuit by using a function that returns a `Result`
     /// with the `ProverKey`, `VerifierKey` and the circuit size.
+    ///
+    /// The circuit is synthesized once, both keys are preprocessed from it
+    /// and its shape is stored with `set_shape`.
     fn compile(&mut self, pub_params: &PublicParameters) -> Result<(ProverKey, VerifierKey)> {
-        use crate::proof_system::{Prover, Verifier};
         // Setup PublicParams
         let (ck, _) = pub_params.trim(self.get_trim_size())?;
-        // Generate & save `ProverKey` with some random values.
-        let mut prover = Prover::new(b"CircuitCompilation");
-        self.gadget(prover.mut_cs())?;
-        prover.preprocess(&ck)?;
-
-        // Generate & save `VerifierKey` with some random values.
-        let mut verifier = Verifier::new(b"CircuitCompilation");
-        self.gadget(verifier.mut_cs())?;
-        verifier.preprocess(&ck)?;
-        Ok((
-            prover
-                .prover_key
-                .expect("Unexpected error. Missing ProverKey in compilation"),
-            verifier
-                .verifier_key
-                .expect("Unexpected error. Missing VerifierKey in compilation"),
-        ))
-    }
-
-    /// /// Return a mutable reference to the Public Inputs storage of the circuit.
-    fn get_mut_pi_positions(&mut self) -> &mut Vec<PublicInput>;
-
-    /// Return a reference to the Public Inputs storage of the circuit.
-    fn get_pi_positions(&self) -> &Vec<PublicInput>;
-
-    /// Build PI vector for Proof verifications.
-    fn build_pi(&self, pub_inputs: &[PublicInput]) -> Result<Vec<BlsScalar>> {
-        let mut pi = vec![BlsScalar::zero(); self.get_trim_size()];
-        pub_inputs
-            .iter()
-            .zip(self.get_pi_positions())
-            .for_each(|(real_value, real_pos)| {
-                match real_value {
-                    PublicInput::BlsScalar(value, _) => pi[real_pos.pos()[0]] = -value,
-                    PublicInput::JubJubScalar(value, _) => {
-                        pi[real_pos.pos()[0]] = -BlsScalar::from(*value)
-                    }
-                    PublicInput::AffinePoint(value, _, _) => {
-                        pi[real_pos.pos()[0]] = -value.get_x();
-                        pi[real_pos.pos()[1]] = -value.get_y();
-                    }
-                };
-            });
-        Ok(pi)
+        let mut composer = StandardComposer::new();
+        let variables = self.synthesize(&mut composer)?;
+        let shape = CircuitShape {
+            composer: composer.clone(),
+            variables,
+        };
+        let keys = composer.preprocess(&ck)?;
+        self.set_shape(shape);
+        Ok(keys)
     }
 
     /// Returns the size at which we trim the `PublicParameters`
@@ -168,41 +91,64 @@ where
     /// trimming the Public Parameters.
     fn set_trim_size(&mut self, size: usize);
 
+    /// Returns the shape of the circuit stored by `compile`, if it was
+    /// compiled.
+    fn get_shape(&self) -> Option<&CircuitShape<Self::Variables>>;
+
+    /// Stores the shape of the circuit, which the witness of its proofs is
+    /// assigned to.
+    fn set_shape(&mut self, shape: CircuitShape<Self::Variables>);
+
     /// Generates a proof using the provided `CircuitInputs` & `ProverKey` instances.
+    ///
+    /// The witness is assigned by `assign` to the shape stored by `compile`,
+    /// without synthesizing the circuit again.
     fn gen_proof(
-        &mut self,
+        &self,
         pub_params: &PublicParameters,
         prover_key: &ProverKey,
         transcript_initialisation: &'static [u8],
     ) -> Result<Proof> {
         use crate::proof_system::Prover;
+        let shape = self.get_shape().ok_or(CircuitErrors::UncompiledCircuit)?;
         let (ck, _) = pub_params.trim(self.get_trim_size())?;
+        // Assign the witness to the gates of the circuit
+        let mut composer = shape.composer.clone();
+        self.assign(&mut composer, &shape.variables)?;
+        composer.generate_witness();
+        // The circuit must be the one the ProverKey was compiled from, and
+        // not have been changed by `assign`
+        if composer.circuit_digest() != prover_key.circuit_digest {
+            return Err(CircuitErrors::MismatchedProverKey.into());
+        }
         // New Prover instance
         let mut prover = Prover::new(transcript_initialisation);
-        // Fill witnesses for Prover
-        self.gadget(prover.mut_cs())?;
+        *prover.mut_cs() = composer;
         // Add ProverKey to Prover
         prover.prover_key = Some(prover_key.clone());
         prover.prove(&ck)
     }
 
-    /// Verifies a proof using the provided `CircuitInputs` & `VerifierKey` instances.
+    /// Verifies a proof using the provided `VerifierKey` and the values of the
+    /// public inputs, given in the order they were declared, without
+    /// synthesizing the circuit.
+    ///
+    /// The values are checked against the `PublicInputLayout` of the
+    /// `VerifierKey`.
     fn verify_proof(
-        &mut self,
+        &self,
         pub_params: &PublicParameters,
         verifier_key: &VerifierKey,
         transcript_initialisation: &'static [u8],
         proof: &Proof,
-        pub_inputs: &[PublicInput],
+        pub_inputs: &[BlsScalar],
     ) -> Result<()> {
         use crate::proof_system::Verifier;
         let (_, vk) = pub_params.trim(self.get_trim_size())?;
         // New Verifier instance
         let mut verifier = Verifier::new(transcript_initialisation);
-        // Fill witnesses for Verifier
-        self.gadget(verifier.mut_cs())?;
-        verifier.verifier_key = Some(*verifier_key);
-        verifier.verify(proof, &vk, &self.build_pi(pub_inputs)?)
+        verifier.verifier_key = Some(verifier_key.clone());
+        verifier.verify(proof, &vk, pub_inputs)
     }
 }
 
@@ -217,15 +163,20 @@ pub enum CircuitErrors {
     /// attribute is uninitialized.
     #[error("PI constructor attribute is uninitialized")]
     UninitializedPIGenerator,
-    /// PublicInput serialization error
-    #[error("Invalid PublicInput bytes")]
-    InvalidPublicInputBytes,
+    /// This error occurs when the circuit assigned by the prover does not
+    /// have the digest of the circuit the ProverKey was compiled from.
+    #[error("the circuit does not match the ProverKey")]
+    MismatchedProverKey,
+    /// This error occurs when a proof is generated for a circuit whose shape
+    /// was not stored by `compile`.
+    #[error("the circuit is not compiled")]
+    UncompiledCircuit,
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
-    use crate::constraint_system::StandardComposer;
+    use crate::constraint_system::{StandardComposer, Variable};
     use crate::proof_system::{ProverKey, VerifierKey};
     use anyhow::Result;
 
@@ -236,58 +187,64 @@ mod tests {
     // 4) a * b = d where D is a PI
     pub struct TestCircuit<'a> {
         inputs: Option<&'a [BlsScalar]>,
-        pi_positions: Vec<PublicInput>,
         trim_size: usize,
+        shape: Option<CircuitShape<[Variable; 4]>>,
     }
 
     impl<'a> Default for TestCircuit<'a> {
         fn default() -> Self {
             TestCircuit {
                 inputs: None,
-                pi_positions: vec![],
                 trim_size: 1 << 9,
+                shape: None,
             }
         }
     }
 
     impl<'a> Circuit<'a> for TestCircuit<'a> {
-        fn gadget(&mut self, composer: &mut StandardComposer) -> Result<()> {
-            let inputs = self
-                .inputs
-                .ok_or_else(|| CircuitErrors::CircuitInputsNotFound)?;
-            let pi = self.get_mut_pi_positions();
-            let a = composer.add_input(inputs[0]);
-            let b = composer.add_input(inputs[1]);
+        type Variables = [Variable; 4];
+
+        fn synthesize(&self, composer: &mut StandardComposer) -> Result<[Variable; 4]> {
+            let a = composer.add_input(BlsScalar::zero());
+            let b = composer.add_input(BlsScalar::zero());
+            let c = composer.public_input("c", BlsScalar::zero());
+            let d = composer.public_input("d", BlsScalar::zero());
             // Make first constraint a + b = c
-            pi.push(PublicInput::BlsScalar(-inputs[2], composer.circuit_size()));
             composer.poly_gate(
                 a,
                 b,
-                composer.zero_var,
+                c,
                 BlsScalar::zero(),
                 BlsScalar::one(),
                 BlsScalar::one(),
+                -BlsScalar::one(),
                 BlsScalar::zero(),
                 BlsScalar::zero(),
-                -inputs[2],
             );
 
             // Check that a and b are in range
             composer.range_gate(a, 1 << 6);
             composer.range_gate(b, 1 << 5);
             // Make second constraint a * b = d
-            pi.push(PublicInput::BlsScalar(-inputs[3], composer.circuit_size()));
             composer.poly_gate(
                 a,
                 b,
-                composer.zero_var,
+                d,
                 BlsScalar::one(),
                 BlsScalar::zero(),
                 BlsScalar::zero(),
-                BlsScalar::one(),
+                -BlsScalar::one(),
+                BlsScalar::zero(),
                 BlsScalar::zero(),
-                -inputs[3],
             );
+            Ok([a, b, c, d])
+        }
+
+        fn assign(&self, composer: &mut StandardComposer, variables: &[Variable; 4]) -> Result<()> {
+            let inputs = self.inputs.ok_or(CircuitErrors::CircuitInputsNotFound)?;
+            for (var, value) in variables.iter().zip(inputs.iter()) {
+                composer.assign(*var, *value);
+            }
             Ok(())
         }
 
@@ -300,12 +257,12 @@ mod tests {
             self.trim_size = size;
         }
 
-        fn get_mut_pi_positions(&mut self) -> &mut Vec<PublicInput> {
-            &mut self.pi_positions
+        fn get_shape(&self) -> Option<&CircuitShape<[Variable; 4]>> {
+            self.shape.as_ref()
         }
 
-        fn get_pi_positions(&self) -> &Vec<PublicInput> {
-            &self.pi_positions
+        fn set_shape(&mut self, shape: CircuitShape<[Variable; 4]>) {
+            self.shape = Some(shape);
         }
     }
 
@@ -314,18 +271,17 @@ mod tests {
         // Generate CRS
         let pub_params = PublicParameters::setup(1 << 10, &mut rand::thread_rng())?;
 
+        // Generate new inputs
+        let inputs2 = [
+            BlsScalar::from(20u64),
+            BlsScalar::from(5u64),
+            BlsScalar::from(25u64),
+            BlsScalar::from(100u64),
+        ];
+
+        // Initialize the circuit, without assigning its inputs
+        let mut circuit = TestCircuit::default();
         {
-            // Generate circuit compilation params
-            let inputs = [
-                BlsScalar::from(25u64),
-                BlsScalar::from(5u64),
-                BlsScalar::from(30u64),
-                BlsScalar::from(125u64),
-            ];
-
-            // Initialize the circuit
-            let mut circuit = TestCircuit::default();
-            circuit.inputs = Some(&inputs);
             // Compile the circuit
             let (prover_key, verifier_key) = circuit.compile(&pub_params)?;
             // Write the keys
@@ -342,29 +298,144 @@ mod tests {
         // Read VerifierKey
         let verifier_key = VerifierKey::from_bytes(&std::fs::read("vk_testcirc")?[..]).unwrap();
 
-        // Generate new inputs
-        // Generate circuit compilation params
-        let inputs2 = [
-            BlsScalar::from(20u64),
-            BlsScalar::from(5u64),
-            BlsScalar::from(25u64),
-            BlsScalar::from(100u64),
-        ];
-
-        // Prover POV
-        let proof = {
-            let mut circuit = TestCircuit::default();
-            circuit.inputs = Some(&inputs2);
-            circuit.gen_proof(&pub_params, &prover_key, b"Test")
-        }?;
+        // Should fail as the inputs are not assigned
+        let err = circuit
+            .gen_proof(&pub_params, &prover_key, b"Test")
+            .unwrap_err();
+        assert!(matches!(
+            err.downcast_ref::<CircuitErrors>(),
+            Some(CircuitErrors::CircuitInputsNotFound)
+        ));
 
-        // Verifier POV
-        let mut circuit = TestCircuit::default();
+        // Prover POV, which assigns the inputs to the compiled circuit
         circuit.inputs = Some(&inputs2);
-        let public_inputs2 = vec![
-            PublicInput::BlsScalar(BlsScalar::from(25u64), 0),
-            PublicInput::BlsScalar(BlsScalar::from(100u64), 0),
-        ];
-        circuit.verify_proof(&pub_params, &verifier_key, b"Test", &proof, &public_inputs2)
+        let proof = circuit.gen_proof(&pub_params, &prover_key, b"Test")?;
+
+        // Verifier POV, which does not know the witness
+        let verifier = TestCircuit::default();
+        let public_inputs2 = [inputs2[2], inputs2[3]];
+        verifier.verify_proof(&pub_params, &verifier_key, b"Test", &proof, &public_inputs2)?;
+        assert_eq!(
+            verifier_key.public_inputs.names().collect::<Vec<_>>(),
+            vec!["c", "d"]
+        );
+
+        // Should fail as the public inputs do not match the proof
+        let wrong_public_inputs = [inputs2[2] + BlsScalar::one(), inputs2[3]];
+        assert!(verifier
+            .verify_proof(
+                &pub_params,
+                &verifier_key,
+                b"Test",
+                &proof,
+                &wrong_public_inputs
+            )
+            .is_err());
+
+        // Should fail as a public input is missing
+        assert!(verifier
+            .verify_proof(&pub_params, &verifier_key, b"Test", &proof, &inputs2[2..3])
+            .is_err());
+
+        // Should fail as the circuit does not match the ProverKey
+        let mut other_prover_key = prover_key.clone();
+        other_prover_key.circuit_digest += BlsScalar::one();
+        let err = circuit
+            .gen_proof(&pub_params, &other_prover_key, b"Test")
+            .unwrap_err();
+        assert!(matches!(
+            err.downcast_ref::<CircuitErrors>(),
+            Some(CircuitErrors::MismatchedProverKey)
+        ));
+
+        // Should fail as the circuit is not compiled
+        let mut uncompiled = TestCircuit::default();
+        uncompiled.inputs = Some(&inputs2);
+        let err = uncompiled
+            .gen_proof(&pub_params, &prover_key, b"Test")
+            .unwrap_err();
+        assert!(matches!(
+            err.downcast_ref::<CircuitErrors>(),
+            Some(CircuitErrors::UncompiledCircuit)
+        ));
+        Ok(())
+    }
+
+    // Checks that the public input `a` is invertible, with its inverse
+    // computed when the witness is assigned, which panics if `a` is zero
+    #[derive(Default)]
+    struct InverseCircuit {
+        a: BlsScalar,
+        shape: Option<CircuitShape<[Variable; 2]>>,
+    }
+
+    impl<'a> Circuit<'a> for InverseCircuit {
+        type Variables = [Variable; 2];
+
+        fn synthesize(&self, composer: &mut StandardComposer) -> Result<[Variable; 2]> {
+            let a = composer.public_input("a", BlsScalar::zero());
+            let a_inv = composer.add_input(BlsScalar::zero());
+            // a * a_inv - 1 = 0
+            composer.mul_gate(
+                a,
+                a_inv,
+                composer.zero_var,
+                BlsScalar::one(),
+                BlsScalar::zero(),
+                -BlsScalar::one(),
+                BlsScalar::zero(),
+            );
+            Ok([a, a_inv])
+        }
+
+        fn assign(&self, composer: &mut StandardComposer, variables: &[Variable; 2]) -> Result<()> {
+            composer.assign(variables[0], self.a);
+            composer.assign(variables[1], self.a.invert().unwrap());
+            Ok(())
+        }
+
+        fn get_trim_size(&self) -> usize {
+            1 << 5
+        }
+
+        fn set_trim_size(&mut self, _: usize) {}
+
+        fn get_shape(&self) -> Option<&CircuitShape<[Variable; 2]>> {
+            self.shape.as_ref()
+        }
+
+        fn set_shape(&mut self, shape: CircuitShape<[Variable; 2]>) {
+            self.shape = Some(shape);
+        }
+    }
+
+    #[test]
+    fn test_compile_without_witness() -> Result<()> {
+        let pub_params = PublicParameters::setup(1 << 6, &mut rand::thread_rng())?;
+
+        // The circuit is compiled without assigning its witness, which would
+        // panic as zero is not invertible
+        let mut circuit = InverseCircuit::default();
+        let (prover_key, verifier_key) = circuit.compile(&pub_params)?;
+
+        circuit.a = BlsScalar::from(7u64);
+        let proof = circuit.gen_proof(&pub_params, &prover_key, b"Inverse")?;
+        circuit.verify_proof(
+            &pub_params,
+            &verifier_key,
+            b"Inverse",
+            &proof,
+            &[BlsScalar::from(7u64)],
+        )?;
+        assert!(circuit
+            .verify_proof(
+                &pub_params,
+                &verifier_key,
+                b"Inverse",
+                &proof,
+                &[BlsScalar::from(8u64)]
+            )
+            .is_err());
+        Ok(())
     }
 }
@@ -0,0 +1,236 @@
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this
+// file, You can obtain one at http://mozilla.org/MPL/2.0/.
+//
+// Copyright (c) DUSK NETWORK. All rights reserved.
+
+//! Updatable setup of the Public Parameters.
+//!
+//! Each participant of a ceremony multiplies the secret `\beta` of the
+//! current Public Parameters by its own secret `s` and publishes a
+//! [`Contribution`] proving that it knows `s`. The final Public Parameters
+//! are sound as long as one of the participants discarded its secret.
+use super::{errors::KZG10Errors, key::CommitKey, key::OpeningKey, srs::PublicParameters};
+use super::{Commitment, TranscriptProtocol};
+use crate::serialisation::{read_g1_affine, read_g2_affine, read_scalar, write_scalar};
+use crate::util;
+use anyhow::{Error, Result};
+use dusk_bls12_381::{BlsScalar, G1Affine, G1Projective, G2Affine, G2Prepared};
+use merlin::Transcript;
+use rand_core::RngCore;
+
+/// The public record of a participant updating the Public Parameters with a
+/// secret `s`, along with a proof of knowledge of `s`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Contribution {
+    /// `s \beta H`, the `\beta H` of the updated Public Parameters.
+    pub beta_h: G2Affine,
+    /// `s G`, where `G` is the generator of G1 of the Public Parameters.
+    pub s_g: G1Affine,
+    /// The commitment `r G` of the Schnorr proof of knowledge of `s`.
+    pub r_g: G1Affine,
+    /// The response `r + c s` of the Schnorr proof of knowledge of `s`.
+    pub response: BlsScalar,
+}
+
+impl Contribution {
+    /// Serialised size of a [`Contribution`].
+    pub const SIZE: usize = 48 + 96 + 48 + 32;
+
+    /// Serialises a [`Contribution`] into bytes.
+    pub fn to_bytes(&self) -> [u8; Self::SIZE] {
+        let mut bytes = Vec::with_capacity(Self::SIZE);
+        bytes.extend_from_slice(&self.s_g.to_compressed());
+        bytes.extend_from_slice(&self.beta_h.to_compressed());
+        bytes.extend_from_slice(&self.r_g.to_compressed());
+        write_scalar(&self.response, &mut bytes);
+
+        let mut array = [0u8; Self::SIZE];
+        array.copy_from_slice(&bytes);
+        array
+    }
+
+    /// Deserialises a slice of bytes into a [`Contribution`].
+    pub fn from_bytes(bytes: &[u8]) -> Result<Contribution, Error> {
+        let (s_g, rest) = read_g1_affine(bytes)?;
+        let (beta_h, rest) = read_g2_affine(rest)?;
+        let (r_g, rest) = read_g1_affine(rest)?;
+        let (response, _) = read_scalar(rest)?;
+
+        Ok(Contribution {
+            beta_h,
+            s_g,
+            r_g,
+            response,
+        })
+    }
+
+    /// Checks that the contribution updated the Public Parameters whose
+    /// generator of G1 is `g` and whose `\beta H` is `previous_beta_h`,
+    /// and that the participant knows its secret.
+    pub fn verify(&self, g: G1Affine, previous_beta_h: G2Affine) -> Result<(), Error> {
+        if bool::from(self.s_g.is_identity()) {
+            return Err(KZG10Errors::ContributionIsZero.into());
+        }
+
+        // Schnorr proof of knowledge of `s`
+        let challenge = self.challenge(&previous_beta_h);
+        if G1Affine::from(g * self.response) != G1Affine::from(self.r_g + self.s_g * challenge) {
+            return Err(KZG10Errors::InvalidContribution.into());
+        }
+
+        // e(s G, \beta H) == e(G, s \beta H)
+        let pairing = dusk_bls12_381::multi_miller_loop(&[
+            (&self.s_g, &G2Prepared::from(previous_beta_h)),
+            (&-g, &G2Prepared::from(self.beta_h)),
+        ])
+        .final_exponentiation();
+
+        if pairing != dusk_bls12_381::Gt::identity() {
+            return Err(KZG10Errors::InvalidContribution.into());
+        }
+        Ok(())
+    }
+
+    /// Computes the Schnorr challenge, binding the proof to the update from
+    /// `previous_beta_h`.
+    fn challenge(&self, previous_beta_h: &G2Affine) -> BlsScalar {
+        let mut transcript = Transcript::new(b"dusk-plonk-srs-contribution");
+        transcript.append_message(b"previous_beta_h", &previous_beta_h.to_compressed());
+        transcript.append_message(b"beta_h", &self.beta_h.to_compressed());
+        transcript.append_commitment(b"s_g", &Commitment::from_affine(self.s_g));
+        transcript.append_commitment(b"r_g", &Commitment::from_affine(self.r_g));
+        transcript.challenge_scalar(b"c")
+    }
+}
+
+impl PublicParameters {
+    /// Updates the public parameters with the contributor's `secret`, which
+    /// must be discarded afterwards, and returns them along with the
+    /// [`Contribution`] proving the update.
+    ///
+    /// The powers `{ \beta^i G }` become `{ (s \beta)^i G }`, while the
+    /// generators `G` and `H` are left unchanged.
+    /// Returns an error if the `secret` is zero.
+    pub fn contribute<R: RngCore>(
+        &self,
+        secret: &BlsScalar,
+        rng: &mut R,
+    ) -> Result<(PublicParameters, Contribution), Error> {
+        if secret == &BlsScalar::zero() {
+            return Err(KZG10Errors::ContributionIsZero.into());
+        }
+
+        // Compute powers of the secret up to and including secret^max_degree
+        let powers_of_secret = util::powers_of(secret, self.max_degree());
+        let powers_of_g =
+            util::slow_multiscalar_mul_pairwise(&powers_of_secret, &self.commit_key.powers_of_g);
+
+        // Normalise all projective points
+        let mut normalised_g = vec![G1Affine::identity(); powers_of_g.len()];
+        G1Projective::batch_normalize(&powers_of_g, &mut normalised_g);
+
+        let g = self.opening_key.g;
+        let beta_h = G2Affine::from(self.opening_key.beta_h * secret);
+
+        // Schnorr proof of knowledge of the secret
+        let nonce = util::random_scalar(rng);
+        let mut contribution = Contribution {
+            beta_h,
+            s_g: G1Affine::from(g * secret),
+            r_g: G1Affine::from(g * nonce),
+            response: BlsScalar::zero(),
+        };
+        let challenge = contribution.challenge(&self.opening_key.beta_h);
+        contribution.response = nonce + challenge * secret;
+
+        let public_parameters = PublicParameters {
+            commit_key: CommitKey {
+                powers_of_g: normalised_g,
+            },
+            opening_key: OpeningKey::new(g, self.opening_key.h, beta_h),
+        };
+
+        Ok((public_parameters, contribution))
+    }
+
+    /// Checks that the public parameters result from applying the chain of
+    /// `contributions` to the `initial` ones, and that their powers are
+    /// consistent, see [`PublicParameters::verify`].
+    pub fn verify_contributions<R: RngCore>(
+        &self,
+        initial: &PublicParameters,
+        contributions: &[Contribution],
+        rng: &mut R,
+    ) -> Result<(), Error> {
+        let g = initial.opening_key.g;
+        if self.opening_key.g != g || self.opening_key.h != initial.opening_key.h {
+            return Err(KZG10Errors::InvalidContribution.into());
+        }
+
+        let beta_h = contributions.iter().try_fold(
+            initial.opening_key.beta_h,
+            |previous_beta_h, contribution| {
+                contribution.verify(g, previous_beta_h)?;
+                Ok::<_, Error>(contribution.beta_h)
+            },
+        )?;
+        if self.opening_key.beta_h != beta_h {
+            return Err(KZG10Errors::InvalidContribution.into());
+        }
+
+        self.verify(rng)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_contributions() {
+        let mut rng = rand::thread_rng();
+        let initial = PublicParameters::setup(20, &mut rng).unwrap();
+
+        let mut public_parameters = PublicParameters::from_bytes(&initial.into_bytes()).unwrap();
+        let mut contributions = Vec::new();
+        for _ in 0..3 {
+            let secret = util::random_scalar(&mut rng);
+            let (updated, contribution) = public_parameters.contribute(&secret, &mut rng).unwrap();
+            public_parameters = updated;
+            contributions.push(Contribution::from_bytes(&contribution.to_bytes()).unwrap());
+        }
+        assert!(public_parameters
+            .verify_contributions(&initial, &contributions, &mut rng)
+            .is_ok());
+
+        // Should fail as the contributions are not applied in this order
+        contributions.swap(0, 1);
+        assert!(public_parameters
+            .verify_contributions(&initial, &contributions, &mut rng)
+            .is_err());
+        contributions.swap(0, 1);
+
+        // Should fail as the last contribution is missing
+        assert!(public_parameters
+            .verify_contributions(&initial, &contributions[..2], &mut rng)
+            .is_err());
+
+        // Should fail as the proof of knowledge does not match the secret
+        let mut forged = contributions.clone();
+        forged[1].response += BlsScalar::one();
+        assert!(public_parameters
+            .verify_contributions(&initial, &forged, &mut rng)
+            .is_err());
+
+        // Should fail as the parameters do not result from the contributions
+        let other = PublicParameters::setup(20, &mut rng).unwrap();
+        assert!(other
+            .verify_contributions(&initial, &contributions, &mut rng)
+            .is_err());
+
+        assert!(public_parameters
+            .contribute(&BlsScalar::zero(), &mut rng)
+            .is_err());
+    }
+}
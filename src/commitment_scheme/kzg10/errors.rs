@@ -33,4 +33,20 @@ pub enum KZG10Errors {
     /// This error occurs when the pairing check fails at being equal to the Identity point.
     #[error("pairing check failed")]
     PairingCheckFailure,
+    /// This error occurs when the powers of the PublicParameters are not
+    /// successive powers of the secret of the opening key.
+    #[error("the powers of the PublicParameters are not consistent")]
+    InconsistentPowers,
+    /// This error occurs when the user tries to read more powers from a
+    /// powers-of-tau transcript than it holds.
+    #[error("the powers-of-tau transcript does not hold enough powers")]
+    NotEnoughPowers,
+    /// This error occurs when the user tries to contribute to the
+    /// PublicParameters with a secret that is zero.
+    #[error("cannot contribute to PublicParameters with a zero secret")]
+    ContributionIsZero,
+    /// This error occurs when the proof of knowledge of a contribution or its
+    /// link to the previous PublicParameters is invalid.
+    #[error("invalid contribution to the PublicParameters")]
+    InvalidContribution,
 }
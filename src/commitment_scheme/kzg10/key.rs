@@ -7,13 +7,10 @@
 //! Key module contains the utilities and data structures
 //! that support the generation and usage of Commit and
 //! Opening keys.
-use super::{errors::KZG10Errors, AggregateProof, Commitment, Proof};
+use super::{errors::KZG10Errors, msm::pippenger, AggregateProof, Commitment, Proof};
 use crate::{fft::Polynomial, transcript::TranscriptProtocol, util};
 use anyhow::{Error, Result};
-use dusk_bls12_381::{
-    multiscalar_mul::msm_variable_base, BlsScalar, G1Affine, G1Projective, G2Affine, G2Prepared,
-};
-use merlin::Transcript;
+use dusk_bls12_381::{BlsScalar, G1Affine, G1Projective, G2Affine, G2Prepared};
 
 /// Opening Key is used to verify opening proofs made about a committed polynomial.
 #[derive(Clone, Debug)]
@@ -112,7 +109,7 @@ impl CommitKey {
         self.check_commit_degree_is_within_bounds(polynomial.degree())?;
 
         // Compute commitment
-        let commitment = msm_variable_base(&self.powers_of_g, &polynomial.coeffs);
+        let commitment = pippenger(&self.powers_of_g, &polynomial.coeffs);
         Ok(Commitment::from_projective(commitment))
     }
 
@@ -130,11 +127,11 @@ impl CommitKey {
     /// Computes a single witness for multiple polynomials at the same point, by taking
     /// a random linear combination of the individual witnesses.
     /// We apply the same optimisation mentioned in when computing each witness; removing f(z).
-    pub(crate) fn compute_aggregate_witness(
+    pub(crate) fn compute_aggregate_witness<T: TranscriptProtocol>(
         &self,
         polynomials: &[Polynomial],
         point: &BlsScalar,
-        transcript: &mut Transcript,
+        transcript: &mut T,
     ) -> Polynomial {
         let challenge = transcript.challenge_scalar(b"aggregate_witness");
         let powers = util::powers_of(&challenge, polynomials.len() - 1);
@@ -169,12 +166,12 @@ impl CommitKey {
     /// Creates an opening proof that multiple polynomials were evaluated at the same point
     /// and that each evaluation produced the correct evaluation point.
     /// Returns an error if any of the polynomial's degrees are too large.
-    pub fn open_multiple(
+    pub fn open_multiple<T: TranscriptProtocol>(
         &self,
         polynomials: &[Polynomial],
         evaluations: Vec<BlsScalar>,
         point: &BlsScalar,
-        transcript: &mut Transcript,
+        transcript: &mut T,
     ) -> Result<AggregateProof, Error> {
         // Commit to polynomials
         let mut polynomial_commitments = Vec::with_capacity(polynomials.len());
@@ -259,12 +256,26 @@ impl OpeningKey {
     }
 
     /// Checks whether a batch of polynomials evaluated at different points, returned their specified value.
-    pub fn batch_check(
+    pub fn batch_check<T: TranscriptProtocol>(
         &self,
         points: &[BlsScalar],
         proofs: &[Proof],
-        transcript: &mut Transcript,
+        transcript: &mut T,
     ) -> Result<(), Error> {
+        let (total_w, total_c) = self.fold_batch(points, proofs, transcript);
+        self.check_folded(total_w, total_c)
+    }
+
+    /// Folds a batch of polynomials evaluated at different points into a pair of
+    /// points `(W, C)`, such that the batch is correct iff `e(-W, beta_h) * e(C, h) = 1`.
+    /// Pairs of different batches can be added together after being scaled by
+    /// random factors, and checked at once using `check_folded`.
+    pub(crate) fn fold_batch<T: TranscriptProtocol>(
+        &self,
+        points: &[BlsScalar],
+        proofs: &[Proof],
+        transcript: &mut T,
+    ) -> (G1Projective, G1Projective) {
         let mut total_c = G1Projective::identity();
         let mut total_w = G1Projective::identity();
 
@@ -285,6 +296,15 @@ impl OpeningKey {
         }
         total_c -= self.g * g_multiplier;
 
+        (total_w, total_c)
+    }
+
+    /// Checks a pair of points `(W, C)` returned by `fold_batch`, with a single pairing.
+    pub(crate) fn check_folded(
+        &self,
+        total_w: G1Projective,
+        total_c: G1Projective,
+    ) -> Result<(), Error> {
         let affine_total_w = G1Affine::from(-total_w);
         let affine_total_c = G1Affine::from(total_c);
 
@@ -5,17 +5,19 @@
 // Copyright (c) DUSK NETWORK. All rights reserved.
 
 //! Implementation of the KZG10 polynomial commitment scheme.
+pub mod ceremony;
 pub mod errors;
 pub mod key;
+pub mod msm;
 pub mod srs;
 
+pub use ceremony::Contribution;
 pub use key::{CommitKey, OpeningKey};
 pub use srs::PublicParameters;
 
 use crate::transcript::TranscriptProtocol;
 use crate::util::powers_of;
 use dusk_bls12_381::{BlsScalar, G1Affine, G1Projective};
-use merlin::Transcript;
 
 #[derive(Copy, Clone, Debug)]
 /// Proof that a polynomial `p` was correctly evaluated at a point `z`
@@ -59,7 +61,7 @@ impl AggregateProof {
 
     /// Flattens an `AggregateProof` into a `Proof`.
     /// The transcript must have the same view as the transcript that was used to aggregate the witness in the proving stage.
-    pub fn flatten(&self, transcript: &mut Transcript) -> Proof {
+    pub fn flatten<T: TranscriptProtocol>(&self, transcript: &mut T) -> Proof {
         let challenge = transcript.challenge_scalar(b"aggregate_witness");
         let powers = powers_of(&challenge, self.commitments_to_polynomials.len() - 1);
 
@@ -0,0 +1,300 @@
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this
+// file, You can obtain one at http://mozilla.org/MPL/2.0/.
+//
+// Copyright (c) DUSK NETWORK. All rights reserved.
+
+//! Multi-scalar multiplications in G1.
+//!
+//! [`pippenger`] computes the commitments to the polynomials from the powers
+//! of the commit key, while a [`FixedBaseTable`] multiplies a single point
+//! by many scalars, as done to generate the powers of the public parameters.
+
+use dusk_bls12_381::{BlsScalar, G1Affine, G1Projective};
+use rayon::prelude::*;
+
+/// Number of bits of the scalars of BLS12-381.
+const SCALAR_BITS: usize = 255;
+
+/// Computes `\sum_i scalars[i] * points[i]` with Pippenger's bucket method,
+/// processing the windows of the scalars in parallel.
+///
+/// The scalars are recoded with signed digits, so each window only needs
+/// half as many buckets, and the size of the windows grows with the number
+/// of points. Extra points or scalars are ignored.
+pub fn pippenger(points: &[G1Affine], scalars: &[BlsScalar]) -> G1Projective {
+    let len = std::cmp::min(points.len(), scalars.len());
+    if len == 0 {
+        return G1Projective::identity();
+    }
+    let window = pippenger_window(len);
+    let num_windows = SCALAR_BITS / window + 1;
+
+    // The canonical representation of every scalar, from which the digits
+    // of each window are recoded on the fly
+    let bytes: Vec<[u8; 32]> = scalars[..len]
+        .par_iter()
+        .map(|scalar| scalar.to_bytes())
+        .collect();
+
+    let window_sums: Vec<G1Projective> = (0..num_windows)
+        .into_par_iter()
+        .map(|w| {
+            // The bucket `b` holds the points whose digit is `b + 1`
+            let mut buckets = vec![G1Projective::identity(); 1 << (window - 1)];
+            for (scalar, point) in bytes.iter().zip(points[..len].iter()) {
+                let digit = signed_digit(scalar, window, w);
+                if digit > 0 {
+                    let bucket = &mut buckets[(digit - 1) as usize];
+                    *bucket = bucket.add_mixed(point);
+                } else if digit < 0 {
+                    let bucket = &mut buckets[(-digit - 1) as usize];
+                    *bucket = bucket.add_mixed(&-point);
+                }
+            }
+
+            // \sum_b (b + 1) * buckets[b], with a running sum from the last bucket
+            let mut running_sum = G1Projective::identity();
+            let mut sum = G1Projective::identity();
+            for bucket in buckets.iter().rev() {
+                running_sum += bucket;
+                sum += running_sum;
+            }
+            sum
+        })
+        .collect();
+
+    // \sum_w 2^{w * window} * window_sums[w]
+    window_sums
+        .iter()
+        .rev()
+        .fold(G1Projective::identity(), |total, window_sum| {
+            (0..window).fold(total, |total, _| total.double()) + window_sum
+        })
+}
+
+/// Returns the size of the windows of [`pippenger`] for `len` points, which
+/// is about `ln(len) + 2` bits.
+fn pippenger_window(len: usize) -> usize {
+    if len < 32 {
+        3
+    } else {
+        // ln(len) ~ log2(len) * 69 / 100
+        (log2(len) * 69 / 100) + 2
+    }
+}
+
+/// Returns the signed digit `d_w` in `[-2^{window - 1}, 2^{window - 1}]` of
+/// the window `w` of the canonical representation `bytes` of a scalar, such
+/// that `scalar = \sum_w d_w 2^{w * window}`.
+///
+/// The window borrows `2^window` from the next one when its top bit is set
+/// and gives it back as a carry, so each digit only depends on the bits of
+/// its window and on the top bit of the previous one.
+fn signed_digit(bytes: &[u8; 32], window: usize, w: usize) -> i32 {
+    let bit = |i: usize| -> i32 {
+        if i < 8 * bytes.len() {
+            ((bytes[i / 8] >> (i % 8)) & 1) as i32
+        } else {
+            0
+        }
+    };
+
+    let start = w * window;
+    let unsigned = (0..window).fold(0, |acc, j| acc + (bit(start + j) << j));
+    let carry = if w == 0 { 0 } else { bit(start - 1) };
+    unsigned + carry - (bit(start + window - 1) << window)
+}
+
+/// Multiples of a fixed point, precomputed to multiply it by many scalars
+/// with additions only.
+///
+/// The scalars are split into windows of `window` bits, and the table holds
+/// the `k 2^{w * window} P` for every window `w` and every digit `k`.
+#[derive(Debug, Clone)]
+pub struct FixedBaseTable {
+    window: usize,
+    table: Vec<Vec<G1Affine>>,
+}
+
+impl FixedBaseTable {
+    /// Largest size of the windows, which bounds the size of the table.
+    const MAX_WINDOW: usize = 12;
+
+    /// Precomputes the multiples of `base` needed to multiply it by
+    /// `num_scalars` scalars, with the size of the windows minimising the
+    /// number of additions to compute the table and the products.
+    pub fn new(base: G1Projective, num_scalars: usize) -> FixedBaseTable {
+        let window = (1..=Self::MAX_WINDOW)
+            .min_by_key(|window| {
+                let num_windows = (SCALAR_BITS - 1) / window + 1;
+                num_windows * ((1 << window) + num_scalars)
+            })
+            .unwrap();
+        Self::with_window(base, window)
+    }
+
+    /// Precomputes the multiples of `base` with windows of `window` bits.
+    ///
+    /// # Panics
+    ///
+    /// If `window` is zero or larger than 12.
+    pub fn with_window(base: G1Projective, window: usize) -> FixedBaseTable {
+        assert!(window > 0 && window <= Self::MAX_WINDOW);
+        let num_windows = (SCALAR_BITS - 1) / window + 1;
+
+        // 2^{w * window} P for every window
+        let mut window_bases = Vec::with_capacity(num_windows);
+        let mut window_base = base;
+        for _ in 0..num_windows {
+            window_bases.push(window_base);
+            window_base = (0..window).fold(window_base, |point, _| point.double());
+        }
+
+        let table = window_bases
+            .par_iter()
+            .map(|window_base| {
+                let mut multiples = Vec::with_capacity(1 << window);
+                let mut multiple = G1Projective::identity();
+                for _ in 0..(1 << window) {
+                    multiples.push(multiple);
+                    multiple += window_base;
+                }
+                let mut normalised = vec![G1Affine::identity(); multiples.len()];
+                G1Projective::batch_normalize(&multiples, &mut normalised);
+                normalised
+            })
+            .collect();
+
+        FixedBaseTable { window, table }
+    }
+
+    /// Returns the size of the windows of the table.
+    pub fn window(&self) -> usize {
+        self.window
+    }
+
+    /// Multiplies the base of the table by `scalar`.
+    pub fn mul(&self, scalar: &BlsScalar) -> G1Projective {
+        let bytes = scalar.to_bytes();
+        let bit = |i: usize| ((bytes[i / 8] >> (i % 8)) & 1) as usize;
+
+        self.table
+            .iter()
+            .enumerate()
+            .fold(G1Projective::identity(), |acc, (w, multiples)| {
+                let digit = (0..self.window)
+                    .map(|j| w * self.window + j)
+                    .filter(|i| *i < SCALAR_BITS)
+                    .fold(0, |digit, i| digit | (bit(i) << (i - w * self.window)));
+                acc.add_mixed(&multiples[digit])
+            })
+    }
+
+    /// Multiplies the base of the table by each of the `scalars`, in parallel.
+    pub fn batch_mul(&self, scalars: &[BlsScalar]) -> Vec<G1Projective> {
+        scalars.par_iter().map(|scalar| self.mul(scalar)).collect()
+    }
+}
+
+fn log2(x: usize) -> usize {
+    (std::mem::size_of::<usize>() * 8) - x.leading_zeros() as usize - 1
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::util;
+
+    fn naive_msm(points: &[G1Affine], scalars: &[BlsScalar]) -> G1Projective {
+        points
+            .iter()
+            .zip(scalars.iter())
+            .map(|(point, scalar)| point * scalar)
+            .sum()
+    }
+
+    #[test]
+    fn test_pippenger() {
+        let mut rng = rand::thread_rng();
+        for len in &[0, 1, 5, 40, 300] {
+            let points: Vec<G1Affine> = (0..*len)
+                .map(|_| G1Affine::from(util::random_g1_point(&mut rng)))
+                .collect();
+            let mut scalars: Vec<BlsScalar> =
+                (0..*len).map(|_| BlsScalar::random(&mut rng)).collect();
+            // Edge cases of the recoding of the scalars
+            if *len >= 5 {
+                scalars[0] = BlsScalar::zero();
+                scalars[1] = BlsScalar::one();
+                scalars[2] = -BlsScalar::one();
+                scalars[3] = BlsScalar::from(u64::MAX);
+            }
+
+            assert_eq!(
+                G1Affine::from(pippenger(&points, &scalars)),
+                G1Affine::from(naive_msm(&points, &scalars))
+            );
+            // Extra points are ignored, as with the powers of a commit key
+            assert_eq!(
+                G1Affine::from(pippenger(&points, &scalars[..len / 2])),
+                G1Affine::from(naive_msm(&points, &scalars[..len / 2]))
+            );
+        }
+    }
+
+    #[test]
+    fn test_signed_digits() {
+        let mut rng = rand::thread_rng();
+        let mut scalars: Vec<BlsScalar> = (0..10).map(|_| BlsScalar::random(&mut rng)).collect();
+        scalars.push(BlsScalar::zero());
+        scalars.push(-BlsScalar::one());
+        for window in 3..16 {
+            let radix = BlsScalar::from(1 << window);
+            for scalar in scalars.iter() {
+                let bytes = scalar.to_bytes();
+                let recomposed =
+                    (0..SCALAR_BITS / window + 1)
+                        .rev()
+                        .fold(BlsScalar::zero(), |acc, w| {
+                            let digit = signed_digit(&bytes, window, w);
+                            assert!(digit.abs() <= 1 << (window - 1));
+                            let digit_scalar = BlsScalar::from(digit.unsigned_abs() as u64);
+                            acc * radix
+                                + if digit < 0 {
+                                    -digit_scalar
+                                } else {
+                                    digit_scalar
+                                }
+                        });
+                assert_eq!(recomposed, *scalar);
+            }
+        }
+    }
+
+    #[test]
+    fn test_fixed_base_table() {
+        let mut rng = rand::thread_rng();
+        let base = util::random_g1_point(&mut rng);
+        let mut scalars: Vec<BlsScalar> = (0..20).map(|_| BlsScalar::random(&mut rng)).collect();
+        scalars.push(BlsScalar::zero());
+        scalars.push(-BlsScalar::one());
+
+        let expected: Vec<G1Affine> = scalars
+            .iter()
+            .map(|scalar| G1Affine::from(base * scalar))
+            .collect();
+        for table in &[
+            FixedBaseTable::new(base, scalars.len()),
+            FixedBaseTable::with_window(base, 1),
+            FixedBaseTable::with_window(base, 7),
+        ] {
+            let products: Vec<G1Affine> = table
+                .batch_mul(&scalars)
+                .into_iter()
+                .map(G1Affine::from)
+                .collect();
+            assert_eq!(products, expected);
+        }
+    }
+}
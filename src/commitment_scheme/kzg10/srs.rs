@@ -8,11 +8,14 @@
 use super::{
     errors::KZG10Errors,
     key::{CommitKey, OpeningKey},
+    msm::{pippenger, FixedBaseTable},
 };
+use crate::serialisation::{read_g1_affine, read_g2_affine};
 use crate::util;
 use anyhow::{Error, Result};
 use dusk_bls12_381::{G1Affine, G1Projective, G2Affine};
 use rand_core::RngCore;
+use std::io::{self, Read};
 
 use serde::de::Visitor;
 use serde::{self, Deserialize, Deserializer, Serialize, Serializer};
@@ -30,10 +33,19 @@ pub struct PublicParameters {
 
 impl_serde_into!(PublicParameters);
 
+/// Size of the hash of the previous challenge at the start of a powers-of-tau
+/// response file.
+const RESPONSE_HASH_SIZE: usize = 64;
+/// Size of a compressed G1 point.
+const G1_SIZE: usize = 48;
+/// Size of a compressed G2 point.
+const G2_SIZE: usize = 96;
+
 impl PublicParameters {
     /// Setup generates the public parameters using a random number generator.
     /// This method will in most cases be used for testing and exploration.
-    /// In reality, a `Trusted party` or a `Multiparty Computation` will used to generate the SRS.
+    /// In reality, a `Trusted party` or a `Multiparty Computation` will used to generate the SRS,
+    /// see [`PublicParameters::from_powers_of_tau`] to load the output of such a ceremony.
     /// Returns an error if the configured degree is less than one.
     pub fn setup<R: RngCore>(
         max_degree: usize,
@@ -53,7 +65,7 @@ impl PublicParameters {
         // Powers of G1 that will be used to commit to a specified polynomial
         let g = util::random_g1_point(&mut rng);
         let powers_of_g: Vec<G1Projective> =
-            util::slow_multiscalar_mul_single_base(&powers_of_beta, g);
+            FixedBaseTable::new(g, powers_of_beta.len()).batch_mul(&powers_of_beta);
         assert_eq!(powers_of_g.len(), max_degree + 1);
 
         // Normalise all projective points
@@ -72,6 +84,115 @@ impl PublicParameters {
         })
     }
 
+    /// Builds the public parameters from the powers `{ \beta^i G }` and the
+    /// G2 elements `H` and `\beta H` computed by a ceremony.
+    /// Returns an error if there are less than two powers or if they are not
+    /// consistent, see [`PublicParameters::verify`].
+    pub fn from_powers<R: RngCore>(
+        powers_of_g: Vec<G1Affine>,
+        h: G2Affine,
+        beta_h: G2Affine,
+        rng: &mut R,
+    ) -> Result<PublicParameters, Error> {
+        if powers_of_g.len() < 2 {
+            return Err(KZG10Errors::DegreeIsZero.into());
+        }
+
+        let public_parameters = PublicParameters {
+            opening_key: OpeningKey::new(powers_of_g[0], h, beta_h),
+            commit_key: CommitKey { powers_of_g },
+        };
+        public_parameters.verify(rng)?;
+        Ok(public_parameters)
+    }
+
+    /// Reads the public parameters up to `max_degree` from a powers-of-tau
+    /// response file, as written by the BLS12-381 `powersoftau` ceremony.
+    ///
+    /// A response file starts with the hash of the previous challenge and
+    /// holds the `2 * tau_powers_length - 1` powers of tau in G1 followed by
+    /// the `tau_powers_length` powers in G2, as compressed points.
+    /// The powers are checked with [`PublicParameters::verify`] before being
+    /// returned.
+    pub fn from_powers_of_tau<T: Read, R: RngCore>(
+        mut transcript: T,
+        tau_powers_length: usize,
+        max_degree: usize,
+        rng: &mut R,
+    ) -> Result<PublicParameters, Error> {
+        if tau_powers_length < 2 {
+            return Err(KZG10Errors::NotEnoughPowers.into());
+        }
+        let tau_powers_g1_length = tau_powers_length
+            .checked_mul(2)
+            .and_then(|length| length.checked_sub(1))
+            .ok_or(KZG10Errors::NotEnoughPowers)?;
+        if max_degree >= tau_powers_g1_length {
+            return Err(KZG10Errors::NotEnoughPowers.into());
+        }
+
+        let mut hash = [0u8; RESPONSE_HASH_SIZE];
+        transcript.read_exact(&mut hash)?;
+
+        let mut bytes = vec![0u8; (max_degree + 1) * G1_SIZE];
+        transcript.read_exact(&mut bytes)?;
+        let mut powers_of_g = Vec::with_capacity(max_degree + 1);
+        let mut remaining: &[u8] = &bytes;
+        for _ in 0..=max_degree {
+            let (point, rest) = read_g1_affine(remaining)?;
+            powers_of_g.push(point);
+            remaining = rest;
+        }
+
+        // Skip the powers of tau in G1 past the maximum degree
+        let skipped = ((tau_powers_g1_length - max_degree - 1) * G1_SIZE) as u64;
+        if io::copy(&mut transcript.by_ref().take(skipped), &mut io::sink())? < skipped {
+            return Err(KZG10Errors::NotEnoughPowers.into());
+        }
+
+        let mut bytes = [0u8; 2 * G2_SIZE];
+        transcript.read_exact(&mut bytes)?;
+        let (h, rest) = read_g2_affine(&bytes)?;
+        let (beta_h, _) = read_g2_affine(rest)?;
+
+        PublicParameters::from_powers(powers_of_g, h, beta_h, rng)
+    }
+
+    /// Checks with two pairings that the powers of the commit key are
+    /// successive powers of the `\beta` of the opening key, starting from
+    /// its generator `G`.
+    ///
+    /// Every `e(\beta^i G, \beta H) == e(\beta^{i+1} G, H)` is checked at once
+    /// on a random linear combination of the powers sampled from `rng`.
+    pub fn verify<R: RngCore>(&self, rng: &mut R) -> Result<(), Error> {
+        let powers_of_g = &self.commit_key.powers_of_g;
+        if powers_of_g.len() < 2 {
+            return Err(KZG10Errors::DegreeIsZero.into());
+        }
+        if powers_of_g[0] != self.opening_key.g
+            || bool::from(self.opening_key.g.is_identity())
+            || bool::from(self.opening_key.h.is_identity())
+        {
+            return Err(KZG10Errors::InconsistentPowers.into());
+        }
+
+        let max_degree = powers_of_g.len() - 1;
+        let scalars: Vec<_> = (0..max_degree).map(|_| util::random_scalar(rng)).collect();
+        let lhs = pippenger(&powers_of_g[..max_degree], &scalars);
+        let rhs = pippenger(&powers_of_g[1..], &scalars);
+
+        let pairing = dusk_bls12_381::multi_miller_loop(&[
+            (&G1Affine::from(lhs), &self.opening_key.prepared_beta_h),
+            (&G1Affine::from(-rhs), &self.opening_key.prepared_h),
+        ])
+        .final_exponentiation();
+
+        if pairing != dusk_bls12_381::Gt::identity() {
+            return Err(KZG10Errors::InconsistentPowers.into());
+        }
+        Ok(())
+    }
+
     /// Serialises a [`PublicParameters`] struct into a slice of bytes
     pub fn into_bytes(&self) -> Vec<u8> {
         let mut bytes = self.opening_key.to_bytes().to_vec();
@@ -139,4 +260,103 @@ mod test {
         assert_eq!(got_pp.opening_key.h, pp.opening_key.h);
         assert_eq!(got_pp.opening_key.beta_h, pp.opening_key.beta_h);
     }
+
+    // Writes a powers-of-tau response file holding the powers of `tau` with
+    // `tau_powers_length` powers in G2
+    fn write_response(tau: BlsScalar, tau_powers_length: usize) -> Vec<u8> {
+        let mut bytes = vec![0u8; RESPONSE_HASH_SIZE];
+        let powers = util::powers_of(&tau, 2 * tau_powers_length - 2);
+        for power in powers.iter() {
+            bytes.extend_from_slice(&G1Affine::from(G1Affine::generator() * power).to_compressed());
+        }
+        for power in powers[..tau_powers_length].iter() {
+            bytes.extend_from_slice(&G2Affine::from(G2Affine::generator() * power).to_compressed());
+        }
+        bytes
+    }
+
+    #[test]
+    fn test_from_powers_of_tau() {
+        let tau = util::random_scalar(&mut rand::thread_rng());
+        let response = write_response(tau, 8);
+
+        let pp =
+            PublicParameters::from_powers_of_tau(&response[..], 8, 10, &mut rand::thread_rng())
+                .unwrap();
+        assert_eq!(pp.max_degree(), 10);
+        assert_eq!(pp.commit_key.powers_of_g[0], G1Affine::generator());
+        assert_eq!(
+            pp.commit_key.powers_of_g[10],
+            G1Affine::from(G1Affine::generator() * tau.pow(&[10, 0, 0, 0]))
+        );
+        assert_eq!(
+            pp.opening_key.beta_h,
+            G2Affine::from(G2Affine::generator() * tau)
+        );
+
+        // The transcript only holds 15 powers in G1
+        assert!(PublicParameters::from_powers_of_tau(
+            &response[..],
+            8,
+            15,
+            &mut rand::thread_rng()
+        )
+        .is_err());
+        // Should fail rather than overflow on degenerate lengths
+        for &length in &[0, 1, usize::MAX] {
+            assert!(PublicParameters::from_powers_of_tau(
+                &response[..],
+                length,
+                0,
+                &mut rand::thread_rng()
+            )
+            .is_err());
+        }
+        // Should fail as the transcript is truncated
+        assert!(PublicParameters::from_powers_of_tau(
+            &response[..RESPONSE_HASH_SIZE + 12 * G1_SIZE],
+            8,
+            10,
+            &mut rand::thread_rng()
+        )
+        .is_err());
+    }
+
+    #[test]
+    fn test_inconsistent_powers() {
+        let mut rng = rand::thread_rng();
+        let pp = PublicParameters::setup(20, &mut rng).unwrap();
+        assert!(pp.verify(&mut rng).is_ok());
+
+        // Should fail as a power is replaced
+        let mut powers_of_g = pp.commit_key.powers_of_g.clone();
+        powers_of_g[7] = G1Affine::from(G1Projective::from(powers_of_g[7]) + powers_of_g[0]);
+        assert!(PublicParameters::from_powers(
+            powers_of_g,
+            pp.opening_key.h,
+            pp.opening_key.beta_h,
+            &mut rng
+        )
+        .is_err());
+
+        // Should fail as `beta_h` is for another secret
+        let beta_h = G2Affine::from(
+            dusk_bls12_381::G2Projective::from(pp.opening_key.beta_h) + pp.opening_key.h,
+        );
+        assert!(PublicParameters::from_powers(
+            pp.commit_key.powers_of_g.clone(),
+            pp.opening_key.h,
+            beta_h,
+            &mut rng
+        )
+        .is_err());
+
+        // Should fail as a response file with a G1 power changed
+        let tau = util::random_scalar(&mut rng);
+        let mut response = write_response(tau, 4);
+        let g1_power_3 = RESPONSE_HASH_SIZE + 3 * G1_SIZE;
+        response[g1_power_3..g1_power_3 + G1_SIZE]
+            .copy_from_slice(&G1Affine::from(G1Affine::generator() * tau).to_compressed());
+        assert!(PublicParameters::from_powers_of_tau(&response[..], 4, 5, &mut rng).is_err());
+    }
 }
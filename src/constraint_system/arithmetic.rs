@@ -71,10 +71,11 @@ impl StandardComposer {
         self.q_arith.push(BlsScalar::one());
         self.q_range.push(BlsScalar::zero());
         self.q_logic.push(BlsScalar::zero());
-        self.q_fixed_group_add.push(BlsScalar::zero());
         self.q_variable_group_add.push(BlsScalar::zero());
+        self.q_lookup.push(BlsScalar::zero());
+        self.q_sbox.push(BlsScalar::zero());
 
-        self.public_inputs.push(pi);
+        self.push_public_input(pi);
 
         self.perm.add_variables_to_map(a, b, c, d, self.n);
 
@@ -150,10 +151,11 @@ impl StandardComposer {
 
         self.q_range.push(BlsScalar::zero());
         self.q_logic.push(BlsScalar::zero());
-        self.q_fixed_group_add.push(BlsScalar::zero());
         self.q_variable_group_add.push(BlsScalar::zero());
+        self.q_lookup.push(BlsScalar::zero());
+        self.q_sbox.push(BlsScalar::zero());
 
-        self.public_inputs.push(pi);
+        self.push_public_input(pi);
 
         self.perm.add_variables_to_map(a, b, c, d, self.n);
 
@@ -212,11 +214,15 @@ impl StandardComposer {
         let q_o = -BlsScalar::one();
 
         // Compute the output wire
-        let a_eval = self.variables[&a];
-        let b_eval = self.variables[&b];
-        let d_eval = self.variables[&d];
-        let c_eval = (q_l * a_eval) + (q_r * b_eval) + (q_4 * d_eval) + q_c + pi;
-        let c = self.add_input(c_eval);
+        let c = self.add_input(BlsScalar::zero());
+        self.add_witness_generator(move |composer| {
+            let c_eval = (q_l * composer.variables[&a])
+                + (q_r * composer.variables[&b])
+                + (q_4 * composer.variables[&d])
+                + q_c
+                + pi;
+            composer.variables.insert(c, c_eval);
+        });
 
         self.big_add_gate(a, b, c, Some(d), q_l, q_r, q_o, q_4, q_c, pi)
     }
@@ -265,11 +271,14 @@ impl StandardComposer {
         };
 
         // Compute output wire
-        let a_eval = self.variables[&a];
-        let b_eval = self.variables[&b];
-        let d_eval = self.variables[&d];
-        let c_eval = (q_m * a_eval * b_eval) + (q_4 * d_eval) + q_c + pi;
-        let c = self.add_input(c_eval);
+        let c = self.add_input(BlsScalar::zero());
+        self.add_witness_generator(move |composer| {
+            let c_eval = (q_m * composer.variables[&a] * composer.variables[&b])
+                + (q_4 * composer.variables[&d])
+                + q_c
+                + pi;
+            composer.variables.insert(c, c_eval);
+        });
 
         self.big_mul_gate(a, b, c, Some(d), q_m, q_o, q_c, q_4, pi)
     }
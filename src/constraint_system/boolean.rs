@@ -31,8 +31,9 @@ impl StandardComposer {
 
         self.q_range.push(BlsScalar::zero());
         self.q_logic.push(BlsScalar::zero());
-        self.q_fixed_group_add.push(BlsScalar::zero());
         self.q_variable_group_add.push(BlsScalar::zero());
+        self.q_lookup.push(BlsScalar::zero());
+        self.q_sbox.push(BlsScalar::zero());
 
         self.public_inputs.push(BlsScalar::zero());
 
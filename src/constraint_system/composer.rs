@@ -12,22 +12,26 @@
 //! while having a big scope in utility terms.
 //!
 //! It allows us not only to build Add and Mul constraints but also to build
-//! ECC op. gates, Range checks, Logical gates (Bitwise ops) etc.
+//! ECC op. gates, Range checks, Logical gates (Bitwise ops), Table lookups etc.
 
 // Gate fn's have a large number of attributes but
 // it is intended to be like this in order to provide
 // maximum performance and minimum circuit sizes.
 #![allow(clippy::too_many_arguments)]
 
-use crate::constraint_system::Variable;
+use crate::constraint_system::custom::CustomSelectors;
+use crate::constraint_system::namespace::NamespaceSpan;
+use crate::constraint_system::witness::WitnessGenerators;
+use crate::constraint_system::{LookupTable, Variable};
 use crate::permutation::Permutation;
+use crate::proof_system::PublicInputLayout;
 use dusk_bls12_381::BlsScalar;
-use std::collections::HashMap;
+use std::collections::{BTreeSet, HashMap};
 
 /// A composer is a circuit builder
 /// and will dictate how a circuit is built
 /// We will have a default Composer called `StandardComposer`
-#[derive(Debug)]
+#[derive(Debug, Clone)]
 pub struct StandardComposer {
     // n represents the number of arithmetic gates in the circuit
     pub(crate) n: usize,
@@ -52,13 +56,19 @@ pub struct StandardComposer {
     pub(crate) q_range: Vec<BlsScalar>,
     // Logic selector
     pub(crate) q_logic: Vec<BlsScalar>,
-    // Fixed base group addition selector
-    pub(crate) q_fixed_group_add: Vec<BlsScalar>,
     // Variable base group addition selector
     pub(crate) q_variable_group_add: Vec<BlsScalar>,
+    // Lookup selector
+    pub(crate) q_lookup: Vec<BlsScalar>,
+    // Poseidon S-box selector
+    pub(crate) q_sbox: Vec<BlsScalar>,
 
     /// Public inputs vector
     pub public_inputs: Vec<BlsScalar>,
+    // Names and positions of the public inputs declared with `public_input`
+    pub(crate) public_input_layout: PublicInputLayout,
+    // Gates which were given a non-zero public input when they were added
+    pub(crate) public_input_gates: Vec<usize>,
 
     // Witness vectors
     pub(crate) w_l: Vec<Variable>,
@@ -75,8 +85,21 @@ pub struct StandardComposer {
     // These are the actual variable values
     // N.B. They should not be exposed to the end user once added into the composer
     pub(crate) variables: HashMap<Variable, BlsScalar>,
+    // Computations of the variables derived by the gadgets
+    pub(crate) witness_generators: WitnessGenerators,
 
     pub(crate) perm: Permutation,
+
+    // Fixed tables which the lookup gates check their wires against
+    pub(crate) lookup_tables: Vec<LookupTable>,
+
+    // Selectors of the registered custom gates
+    pub(crate) custom_gates: Vec<CustomSelectors>,
+
+    // Paths of the namespaces currently entered, with their first gate
+    pub(crate) open_namespaces: Vec<(String, usize)>,
+    // Gates added in the namespaces which were exited
+    pub(crate) namespaces: Vec<NamespaceSpan>,
 }
 
 impl StandardComposer {
@@ -84,6 +107,13 @@ impl StandardComposer {
     pub fn circuit_size(&self) -> usize {
         self.n
     }
+
+    /// Returns the size of the domain the circuit is padded to.
+    /// It must be a power of two that fits both the gates and the rows of the
+    /// lookup tables.
+    pub(crate) fn domain_size(&self) -> usize {
+        std::cmp::max(self.n, self.lookup_table_size()).next_power_of_two()
+    }
 }
 
 impl Default for StandardComposer {
@@ -129,9 +159,12 @@ impl StandardComposer {
             q_arith: Vec::with_capacity(expected_size),
             q_range: Vec::with_capacity(expected_size),
             q_logic: Vec::with_capacity(expected_size),
-            q_fixed_group_add: Vec::with_capacity(expected_size),
             q_variable_group_add: Vec::with_capacity(expected_size),
+            q_lookup: Vec::with_capacity(expected_size),
+            q_sbox: Vec::with_capacity(expected_size),
             public_inputs: Vec::with_capacity(expected_size),
+            public_input_layout: PublicInputLayout::default(),
+            public_input_gates: Vec::new(),
 
             w_l: Vec::with_capacity(expected_size),
             w_r: Vec::with_capacity(expected_size),
@@ -141,8 +174,16 @@ impl StandardComposer {
             zero_var: Variable(0),
 
             variables: HashMap::with_capacity(expected_size),
+            witness_generators: WitnessGenerators::default(),
 
             perm: Permutation::new(),
+
+            lookup_tables: Vec::new(),
+
+            custom_gates: Vec::new(),
+
+            open_namespaces: Vec::new(),
+            namespaces: Vec::new(),
         };
 
         // Reserve the first variable to be zero
@@ -203,10 +244,11 @@ impl StandardComposer {
 
         self.q_range.push(BlsScalar::zero());
         self.q_logic.push(BlsScalar::zero());
-        self.q_fixed_group_add.push(BlsScalar::zero());
         self.q_variable_group_add.push(BlsScalar::zero());
+        self.q_lookup.push(BlsScalar::zero());
+        self.q_sbox.push(BlsScalar::zero());
 
-        self.public_inputs.push(pi);
+        self.push_public_input(pi);
 
         self.perm
             .add_variables_to_map(a, b, c, self.zero_var, self.n);
@@ -231,20 +273,60 @@ impl StandardComposer {
         );
     }
 
+    /// Declares the public input `name` holding `value` and returns the
+    /// `Variable` constrained to it.
+    ///
+    /// The public inputs are recorded in the `PublicInputLayout` of the
+    /// `VerifierKey`, from which a verifier builds the public inputs of a
+    /// proof by name or in the order they were declared.
+    ///
+    /// # Panics
+    ///
+    /// If a public input with the same name was already declared.
+    pub fn public_input(&mut self, name: &str, value: BlsScalar) -> Variable {
+        let var = self.add_input(value);
+        self.public_input_layout.push(name, self.n);
+        self.constrain_to_constant(var, BlsScalar::zero(), -value);
+        var
+    }
+
+    /// Sets the public input `pi` of the gate being added, recording the gate
+    /// as one holding a public input if `pi` is not zero.
+    pub(crate) fn push_public_input(&mut self, pi: BlsScalar) {
+        if pi != BlsScalar::zero() {
+            self.public_input_gates.push(self.n);
+        }
+        self.public_inputs.push(pi);
+    }
+
+    /// Returns the indices of the gates holding a public input, either
+    /// declared with `public_input` or given directly to the gate when it
+    /// was added.
+    pub(crate) fn public_input_positions(&self) -> BTreeSet<usize> {
+        self.public_input_layout
+            .iter()
+            .map(|(_, position)| position)
+            .chain(self.public_input_gates.iter().copied())
+            .collect()
+    }
+
+    /// Returns the non-zero public inputs of the circuit along with the index
+    /// of their gate, to verify a proof with `Verifier::verify_sparse`.
+    pub fn sparse_public_inputs(&self) -> Vec<(usize, BlsScalar)> {
+        self.public_inputs
+            .iter()
+            .enumerate()
+            .filter(|(_, pi)| **pi != BlsScalar::zero())
+            .map(|(index, pi)| (index, *pi))
+            .collect()
+    }
+
     /// Asserts that two variables are the same
-    // XXX: Instead of wasting a gate, we can use the permutation polynomial to do this
+    ///
+    /// The variables are merged in the copy constraints of the permutation
+    /// argument, so the assertion does not add any gate.
     pub fn assert_equal(&mut self, a: Variable, b: Variable) {
-        self.poly_gate(
-            a,
-            b,
-            self.zero_var,
-            BlsScalar::zero(),
-            BlsScalar::one(),
-            -BlsScalar::one(),
-            BlsScalar::zero(),
-            BlsScalar::zero(),
-            BlsScalar::zero(),
-        );
+        self.perm.union(a, b);
     }
 
     /// Conditionally selects a Variable based on an input bit
@@ -306,8 +388,9 @@ impl StandardComposer {
         self.q_arith.push(BlsScalar::one());
         self.q_range.push(BlsScalar::zero());
         self.q_logic.push(BlsScalar::zero());
-        self.q_fixed_group_add.push(BlsScalar::zero());
         self.q_variable_group_add.push(BlsScalar::zero());
+        self.q_lookup.push(BlsScalar::zero());
+        self.q_sbox.push(BlsScalar::zero());
         self.public_inputs.push(BlsScalar::zero());
         let var_six = self.add_input(BlsScalar::from(6));
         let var_one = self.add_input(BlsScalar::from(1));
@@ -330,8 +413,9 @@ impl StandardComposer {
         self.q_arith.push(BlsScalar::one());
         self.q_range.push(BlsScalar::zero());
         self.q_logic.push(BlsScalar::zero());
-        self.q_fixed_group_add.push(BlsScalar::zero());
         self.q_variable_group_add.push(BlsScalar::zero());
+        self.q_lookup.push(BlsScalar::zero());
+        self.q_sbox.push(BlsScalar::zero());
         self.public_inputs.push(BlsScalar::zero());
         self.w_l.push(var_min_twenty);
         self.w_r.push(var_six);
@@ -345,100 +429,14 @@ impl StandardComposer {
     /// Utility function that allows to check on the "front-end"
     /// side of the PLONK implementation if the identity polynomial
     /// is satisfied for each one of the `StandardComposer`'s gates.
-    /// XXX: This is messy and will be removed in a later PR.
+    ///
+    /// # Panics
+    ///
+    /// If a gate is not satisfied, see `check_satisfied`.
     #[cfg(feature = "trace")]
     pub fn check_circuit_satisfied(&self) {
-        let w_l: Vec<&BlsScalar> = self
-            .w_l
-            .iter()
-            .map(|w_l_i| self.variables.get(&w_l_i).unwrap())
-            .collect();
-        let w_r: Vec<&BlsScalar> = self
-            .w_r
-            .iter()
-            .map(|w_r_i| self.variables.get(&w_r_i).unwrap())
-            .collect();
-        let w_o: Vec<&BlsScalar> = self
-            .w_o
-            .iter()
-            .map(|w_o_i| self.variables.get(&w_o_i).unwrap())
-            .collect();
-        let w_4: Vec<&BlsScalar> = self
-            .w_4
-            .iter()
-            .map(|w_4_i| self.variables.get(&w_4_i).unwrap())
-            .collect();
-        // Computes f(f-1)(f-2)(f-3)
-        let delta = |f: BlsScalar| -> BlsScalar {
-            let f_1 = f - BlsScalar::one();
-            let f_2 = f - BlsScalar::from(2);
-            let f_3 = f - BlsScalar::from(3);
-            f * f_1 * f_2 * f_3
-        };
-        let four = BlsScalar::from(4);
-        for i in 0..self.n {
-            let qm = self.q_m[i];
-            let ql = self.q_l[i];
-            let qr = self.q_r[i];
-            let qo = self.q_o[i];
-            let qc = self.q_c[i];
-            let q4 = self.q_4[i];
-            let qarith = self.q_arith[i];
-            let qrange = self.q_range[i];
-            let qlogic = self.q_logic[i];
-            let qfixed = self.q_fixed_group_add[i];
-            let qvar = self.q_variable_group_add[i];
-            let pi = self.public_inputs[i];
-
-            let a = w_l[i];
-            let a_next = w_l[(i + 1) % self.n];
-            let b = w_r[i];
-            let b_next = w_r[(i + 1) % self.n];
-            let c = w_o[i];
-            let d = w_4[i];
-            let d_next = w_4[(i + 1) % self.n];
-            #[cfg(feature = "trace-print")]
-            println!(
-                "--------------------------------------------\n
-            #Gate Index = {}
-            #Selector Polynomials:\n
-            - qm -> {:?}\n
-            - ql -> {:?}\n
-            - qr -> {:?}\n
-            - q4 -> {:?}\n
-            - qo -> {:?}\n
-            - qc -> {:?}\n
-            - q_arith -> {:?}\n
-            - q_range -> {:?}\n
-            - q_logic -> {:?}\n
-            - q_fixed_group_add -> {:?}\n
-            - q_variable_group_add -> {:?}\n
-            # Witness polynomials:\n
-            - w_l -> {:?}\n
-            - w_r -> {:?}\n
-            - w_o -> {:?}\n
-            - w_4 -> {:?}\n",
-                i, qm, ql, qr, q4, qo, qc, qarith, qrange, qlogic, qfixed, qvar, a, b, c, d
-            );
-            let k = qarith * ((qm * a * b) + (ql * a) + (qr * b) + (qo * c) + (q4 * d) + pi + qc)
-                + qlogic
-                    * (((delta(a_next - four * a) - delta(b_next - four * b)) * c)
-                        + delta(a_next - four * a)
-                        + delta(b_next - four * b)
-                        + delta(d_next - four * d)
-                        + match (qlogic == BlsScalar::one(), qlogic == -BlsScalar::one()) {
-                            (true, false) => (a & b) - d,
-                            (false, true) => (a ^ b) - d,
-                            (false, false) => BlsScalar::zero(),
-                            _ => unreachable!(),
-                        })
-                + qrange
-                    * (delta(c - four * d)
-                        + delta(b - four * c)
-                        + delta(a - four * b)
-                        + delta(d_next - four * a));
-
-            assert_eq!(k, BlsScalar::zero(), "Check failed at gate {}", i,);
+        if let Err(unsatisfied) = self.check_satisfied() {
+            panic!("{}", unsatisfied);
         }
     }
 }
@@ -448,6 +446,7 @@ mod tests {
     use super::super::helper::*;
     use super::*;
     use crate::commitment_scheme::kzg10::PublicParameters;
+    use crate::constraint_system::GateKind;
     use crate::proof_system::{Prover, Verifier};
 
     #[test]
@@ -496,6 +495,60 @@ mod tests {
         assert!(res.is_ok());
     }
 
+    #[test]
+    fn test_assert_equal() {
+        // The variables are merged in the copy constraints, without any gate
+        let mut composer = StandardComposer::new();
+        let a = composer.add_input(BlsScalar::from(5u64));
+        let b = composer.add_input(BlsScalar::from(6u64));
+        let circuit_size = composer.circuit_size();
+        composer.assert_equal(a, b);
+        assert_eq!(composer.circuit_size(), circuit_size);
+
+        // Should fail as both variables are used in a gate with different values
+        composer.add(
+            (BlsScalar::one(), a),
+            (BlsScalar::one(), b),
+            BlsScalar::zero(),
+            BlsScalar::zero(),
+        );
+        let unsatisfied = composer.check_satisfied().unwrap_err();
+        assert_eq!(unsatisfied.kind, GateKind::Copy);
+
+        let res = gadget_tester(
+            |composer| {
+                let a = composer.add_input(BlsScalar::from(5u64));
+                let b = composer.add_input(BlsScalar::from(5u64));
+                let sum = composer.add(
+                    (BlsScalar::one(), a),
+                    (BlsScalar::one(), b),
+                    BlsScalar::zero(),
+                    BlsScalar::zero(),
+                );
+                composer.assert_equal(a, b);
+                composer.constrain_to_constant(sum, BlsScalar::from(10u64), BlsScalar::zero());
+            },
+            32,
+        );
+        assert!(res.is_ok());
+
+        let res = gadget_tester(
+            |composer| {
+                let a = composer.add_input(BlsScalar::from(5u64));
+                let b = composer.add_input(BlsScalar::from(6u64));
+                composer.add(
+                    (BlsScalar::one(), a),
+                    (BlsScalar::one(), b),
+                    BlsScalar::zero(),
+                    BlsScalar::zero(),
+                );
+                composer.assert_equal(a, b);
+            },
+            32,
+        );
+        assert!(res.is_err());
+    }
+
     #[test]
     // XXX: Move this to integration tests
     fn test_multiple_proofs() {
@@ -513,7 +566,7 @@ mod tests {
         // Preprocess circuit
         prover.preprocess(&ck).unwrap();
 
-        let public_inputs = prover.cs.public_inputs.clone();
+        let public_inputs = prover.cs.sparse_public_inputs();
 
         let mut proofs = Vec::new();
 
@@ -539,7 +592,7 @@ mod tests {
         verifier.preprocess(&ck).unwrap();
 
         for proof in proofs {
-            assert!(verifier.verify(&proof, &vk, &public_inputs).is_ok());
+            assert!(verifier.verify_sparse(&proof, &vk, &public_inputs).is_ok());
         }
     }
 }
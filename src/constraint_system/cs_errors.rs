@@ -16,3 +16,20 @@ pub enum PreProcessingError {
     #[error("the length of the wires it's not the same")]
     MismatchedPolyLen,
 }
+
+/// Represents an error in the deserialisation of a `CircuitDescription`.
+#[derive(Error, Debug)]
+pub enum CircuitDescriptionError {
+    /// This error occurs when the bytes do not start with the magic bytes
+    /// and version of the format, or hold an invalid value.
+    #[error("the bytes are not a circuit description of a known format")]
+    UnknownFormat,
+    /// This error occurs when the selectors or wires do not hold a value for
+    /// every gate.
+    #[error("the length of the selectors or wires is not the number of gates")]
+    MismatchedLength,
+    /// This error occurs when a wire refers to a gate or variable which is
+    /// not part of the circuit.
+    #[error("a wire refers to a gate or variable out of the circuit")]
+    OutOfBounds,
+}
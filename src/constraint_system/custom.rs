@@ -0,0 +1,300 @@
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this
+// file, You can obtain one at http://mozilla.org/MPL/2.0/.
+//
+// Copyright (c) DUSK NETWORK. All rights reserved.
+
+use crate::constraint_system::{StandardComposer, Variable};
+use crate::proof_system::CustomGate;
+use dusk_bls12_381::BlsScalar;
+use std::sync::Arc;
+
+/// Selector columns of a custom gate registered in a `StandardComposer`.
+#[derive(Debug, Clone)]
+pub(crate) struct CustomSelectors {
+    pub(crate) gate: Arc<dyn CustomGate>,
+    // One column per selector. A column only holds the rows up to the last
+    // use of the gate and is padded with zeroes during preprocessing
+    pub(crate) columns: Vec<Vec<BlsScalar>>,
+}
+
+impl CustomSelectors {
+    /// Pads the columns with zeroes up to `n` rows.
+    pub(crate) fn pad(&mut self, n: usize) {
+        for column in self.columns.iter_mut() {
+            column.resize(n, BlsScalar::zero());
+        }
+    }
+}
+
+impl StandardComposer {
+    /// Registers a `CustomGate` and returns the identifier to pass to
+    /// `custom_gate` in order to use it.
+    ///
+    /// Custom gates are committed to in the order they were registered, so a
+    /// circuit must register the same gates in the same order for the prover
+    /// and the verifier.
+    ///
+    /// # Panics
+    ///
+    /// If a gate with the same name was already registered.
+    pub fn register_custom_gate(&mut self, gate: Arc<dyn CustomGate>) -> usize {
+        assert!(
+            self.custom_gates
+                .iter()
+                .all(|custom| custom.gate.name() != gate.name()),
+            "custom gate {} is already registered",
+            gate.name()
+        );
+
+        let columns = vec![Vec::new(); gate.num_selectors()];
+        self.custom_gates.push(CustomSelectors { gate, columns });
+        self.custom_gates.len() - 1
+    }
+
+    /// Adds a row constrained by the custom gate `gate_id`, whose selectors
+    /// take the values `selectors` and whose wires hold `a`, `b`, `c` and `d`.
+    ///
+    /// The selectors of the built-in gates are all zero in this row.
+    ///
+    /// # Panics
+    ///
+    /// If `gate_id` was not returned by `register_custom_gate` or if the
+    /// number of `selectors` does not match the gate.
+    pub fn custom_gate(
+        &mut self,
+        gate_id: usize,
+        (a, b, c, d): (Variable, Variable, Variable, Variable),
+        selectors: &[BlsScalar],
+    ) {
+        let n = self.n;
+        let custom = &mut self.custom_gates[gate_id];
+        assert_eq!(selectors.len(), custom.gate.num_selectors());
+
+        custom.pad(n);
+        for (column, selector) in custom.columns.iter_mut().zip(selectors.iter()) {
+            column.push(*selector);
+        }
+
+        self.w_l.push(a);
+        self.w_r.push(b);
+        self.w_o.push(c);
+        self.w_4.push(d);
+
+        self.q_m.push(BlsScalar::zero());
+        self.q_l.push(BlsScalar::zero());
+        self.q_r.push(BlsScalar::zero());
+        self.q_o.push(BlsScalar::zero());
+        self.q_c.push(BlsScalar::zero());
+        self.q_4.push(BlsScalar::zero());
+        self.q_arith.push(BlsScalar::zero());
+        self.q_range.push(BlsScalar::zero());
+        self.q_logic.push(BlsScalar::zero());
+        self.q_variable_group_add.push(BlsScalar::zero());
+        self.q_lookup.push(BlsScalar::zero());
+        self.q_sbox.push(BlsScalar::zero());
+
+        self.public_inputs.push(BlsScalar::zero());
+
+        self.perm.add_variables_to_map(a, b, c, d, self.n);
+        self.n += 1;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::super::helper::*;
+    use super::*;
+    use crate::commitment_scheme::kzg10::PublicParameters;
+    use crate::proof_system::proof_system_errors::ProofErrors;
+    use crate::proof_system::{GateWires, Prover, ProverKey, Verifier, VerifierKey};
+
+    // Constrains `c = a^3 + b` with its first selector and `a_next = a * b`
+    // with its second one
+    #[derive(Debug)]
+    struct TestGate;
+
+    impl CustomGate for TestGate {
+        fn name(&self) -> &'static str {
+            "test"
+        }
+
+        fn num_selectors(&self) -> usize {
+            2
+        }
+
+        fn coefficients(&self, wires: &GateWires, _: &BlsScalar) -> Vec<BlsScalar> {
+            vec![
+                wires.a.square() * wires.a + wires.b - wires.c,
+                wires.a_next - wires.a * wires.b,
+            ]
+        }
+    }
+
+    // Adds the rows computing `((2^3 + 3) * 3)^3` with the test gate, the
+    // last output being witnessed with an additional `c_offset`
+    fn test_rows(composer: &mut StandardComposer, c_offset: BlsScalar) {
+        let gate = composer.register_custom_gate(Arc::new(TestGate));
+
+        let two = composer.add_input(BlsScalar::from(2));
+        let three = composer.add_input(BlsScalar::from(3));
+        let eleven = composer.add_input(BlsScalar::from(11));
+        let thirty_three = composer.add_input(BlsScalar::from(33));
+        let zero = composer.zero_var;
+
+        // 11 = 2^3 + 3
+        composer.custom_gate(
+            gate,
+            (two, three, eleven, zero),
+            &[BlsScalar::one(), BlsScalar::zero()],
+        );
+        // 33 = 11 * 3, held by the left wire of the next row
+        composer.custom_gate(
+            gate,
+            (eleven, three, zero, zero),
+            &[BlsScalar::zero(), BlsScalar::one()],
+        );
+        let cube = composer.add_input(BlsScalar::from(33 * 33 * 33) + c_offset);
+        // 35937 = 33^3
+        composer.custom_gate(
+            gate,
+            (thirty_three, zero, cube, zero),
+            &[BlsScalar::one(), BlsScalar::zero()],
+        );
+        composer.constrain_to_constant(
+            cube,
+            BlsScalar::from(33 * 33 * 33) + c_offset,
+            BlsScalar::zero(),
+        );
+    }
+
+    #[test]
+    fn test_custom_gate() {
+        let res = gadget_tester(
+            |composer| {
+                dummy_gadget(7, composer);
+                test_rows(composer, BlsScalar::zero());
+                dummy_gadget(7, composer);
+            },
+            64,
+        );
+        assert!(res.is_ok());
+    }
+
+    #[test]
+    fn test_custom_gate_bad_witness() {
+        let res = gadget_tester(
+            |composer| {
+                let gate = composer.register_custom_gate(Arc::new(TestGate));
+                let two = composer.add_input(BlsScalar::from(2));
+                let three = composer.add_input(BlsScalar::from(3));
+                // 2^3 + 3 != 12
+                let twelve = composer.add_input(BlsScalar::from(12));
+                let zero = composer.zero_var;
+                composer.custom_gate(
+                    gate,
+                    (two, three, twelve, zero),
+                    &[BlsScalar::one(), BlsScalar::zero()],
+                );
+            },
+            64,
+        );
+        assert!(res.is_err());
+
+        // The output of the last row is not the cube of its input
+        let res = gadget_tester(
+            |composer| {
+                test_rows(composer, BlsScalar::one());
+            },
+            64,
+        );
+        assert!(res.is_err());
+    }
+
+    #[test]
+    fn test_serialise_deserialise_custom_keys() {
+        let public_parameters = PublicParameters::setup(128, &mut rand::thread_rng()).unwrap();
+        let (ck, _) = public_parameters.trim(64).unwrap();
+
+        let mut prover = Prover::new(b"demo");
+        test_rows(prover.mut_cs(), BlsScalar::zero());
+        prover.preprocess(&ck).unwrap();
+        let prover_key = prover.prover_key.unwrap();
+
+        let mut verifier = Verifier::new(b"demo");
+        test_rows(verifier.mut_cs(), BlsScalar::zero());
+        verifier.preprocess(&ck).unwrap();
+        let verifier_key = verifier.verifier_key.unwrap();
+
+        let gates: Vec<Arc<dyn CustomGate>> = vec![Arc::new(TestGate)];
+
+        let bytes = prover_key.to_bytes();
+        assert_eq!(
+            ProverKey::from_bytes_with_gates(&bytes, &gates).unwrap(),
+            prover_key
+        );
+        assert!(ProverKey::from_bytes(&bytes).is_err());
+
+        let bytes = verifier_key.to_bytes();
+        assert_eq!(
+            VerifierKey::from_bytes_with_gates(&bytes, &gates).unwrap(),
+            verifier_key
+        );
+        assert!(VerifierKey::from_bytes(&bytes).is_err());
+    }
+
+    // Returns a single coefficient for its two selectors
+    #[derive(Debug)]
+    struct ShortGate;
+
+    impl CustomGate for ShortGate {
+        fn name(&self) -> &'static str {
+            "short"
+        }
+
+        fn num_selectors(&self) -> usize {
+            2
+        }
+
+        fn coefficients(&self, _: &GateWires, _: &BlsScalar) -> Vec<BlsScalar> {
+            vec![BlsScalar::zero()]
+        }
+    }
+
+    #[test]
+    fn test_custom_gate_coefficient_count() {
+        let public_parameters = PublicParameters::setup(128, &mut rand::thread_rng()).unwrap();
+        let (ck, _) = public_parameters.trim(64).unwrap();
+
+        let mut prover = Prover::new(b"demo");
+        let composer = prover.mut_cs();
+        dummy_gadget(7, composer);
+        let gate = composer.register_custom_gate(Arc::new(ShortGate));
+        let zero = composer.zero_var;
+        composer.custom_gate(
+            gate,
+            (zero, zero, zero, zero),
+            &[BlsScalar::one(), BlsScalar::one()],
+        );
+        assert!(prover.cs.check_satisfied().is_err());
+
+        prover.preprocess(&ck).unwrap();
+        let err = prover.prove(&ck).unwrap_err();
+        match err.downcast_ref::<ProofErrors>() {
+            Some(ProofErrors::CustomGateCoefficientCount {
+                name,
+                expected,
+                found,
+            }) => assert_eq!((*name, *expected, *found), ("short", 2, 1)),
+            _ => panic!("unexpected error: {}", err),
+        }
+    }
+
+    #[test]
+    #[should_panic]
+    fn test_register_same_gate_twice() {
+        let mut composer = StandardComposer::new();
+        composer.register_custom_gate(Arc::new(TestGate));
+        composer.register_custom_gate(Arc::new(TestGate));
+    }
+}
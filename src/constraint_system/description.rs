@@ -0,0 +1,618 @@
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this
+// file, You can obtain one at http://mozilla.org/MPL/2.0/.
+//
+// Copyright (c) DUSK NETWORK. All rights reserved.
+
+//! Export of the circuit built by a `StandardComposer`, without its witness.
+//!
+//! A `CircuitDescription` holds everything the preprocessing of a circuit
+//! depends on, so that a composer rebuilt from it yields the same
+//! `ProverKey` and `VerifierKey`. Its `Display` implementation prints one
+//! line per gate, which can be diffed, while `to_bytes` gives a stable
+//! binary format that can be fingerprinted or loaded by another process.
+//!
+//! # Binary format
+//!
+//! Integers are big-endian `u64`s and scalars are 32 bytes long. A list is
+//! prefixed by its number of elements. The fields are, in order:
+//!
+//! 1. The magic bytes `b"plonk-circuit"` followed by the version byte `1`.
+//! 2. The number of gates `n`.
+//! 3. The lists of scalars `q_m`, `q_l`, `q_r`, `q_o`, `q_c`, `q_4`,
+//!    `q_arith`, `q_range`, `q_logic`, `q_variable_group_add`, `q_lookup`
+//!    and `q_sbox`.
+//! 4. The list of the indices of the gates holding a public input.
+//! 5. The lists of variable indices `w_l`, `w_r`, `w_o` and `w_4`.
+//! 6. The number of variables, followed by the wires of each variable as a
+//!    list of a wire byte (`0` to `3` for left, right, output and fourth)
+//!    followed by the index of the gate.
+//! 7. The list of lookup tables, each of them being a list of rows of three
+//!    scalars.
+//! 8. The list of custom gates, each of them being its name as a list of
+//!    bytes, its number of selectors and the list of scalars of each of its
+//!    selectors.
+//! 9. The list of the public inputs declared by name, each of them being its
+//!    name as a list of bytes followed by the index of its gate.
+
+use crate::constraint_system::cs_errors::CircuitDescriptionError;
+use crate::constraint_system::custom::CustomSelectors;
+use crate::constraint_system::witness::WitnessGenerators;
+use crate::constraint_system::{LookupTable, StandardComposer, Variable, WireData};
+use crate::permutation::Permutation;
+use crate::proof_system::widget::custom::{read_gate, write_gate};
+use crate::proof_system::{CustomGate, PublicInputLayout};
+use crate::serialisation::{read_n, read_scalars, read_u64, write_scalars, write_u64};
+use crate::transcript::TranscriptProtocol;
+use anyhow::{Error, Result};
+use dusk_bls12_381::BlsScalar;
+use merlin::Transcript;
+use std::collections::HashMap;
+use std::fmt;
+use std::sync::Arc;
+
+const MAGIC: &[u8] = b"plonk-circuit";
+const VERSION: u8 = 1;
+
+/// The gates, selectors and copy constraints of a circuit, without the
+/// values of its variables.
+#[derive(Debug, Clone)]
+pub struct CircuitDescription {
+    n: usize,
+    // Selectors in the order of the binary format
+    selectors: Vec<Vec<BlsScalar>>,
+    public_input_positions: Vec<usize>,
+    // Wires in the order of the binary format
+    wires: Vec<Vec<Variable>>,
+    // Wires of each variable, in the order they were added to the
+    // permutation
+    variable_wires: Vec<Vec<WireData>>,
+    lookup_tables: Vec<LookupTable>,
+    custom_gates: Vec<CustomSelectors>,
+    public_input_layout: PublicInputLayout,
+}
+
+const SELECTOR_NAMES: [&str; 12] = [
+    "q_m",
+    "q_l",
+    "q_r",
+    "q_o",
+    "q_c",
+    "q_4",
+    "q_arith",
+    "q_range",
+    "q_logic",
+    "q_variable_group_add",
+    "q_lookup",
+    "q_sbox",
+];
+
+impl CircuitDescription {
+    /// Returns the number of gates of the circuit.
+    pub fn circuit_size(&self) -> usize {
+        self.n
+    }
+
+    /// Returns the number of variables of the circuit.
+    pub fn num_variables(&self) -> usize {
+        self.variable_wires.len()
+    }
+
+    /// Returns the indices of the gates holding a public input.
+    pub fn public_input_positions(&self) -> &[usize] {
+        &self.public_input_positions
+    }
+
+    /// Returns the public inputs declared by name in the circuit.
+    pub fn public_input_layout(&self) -> &PublicInputLayout {
+        &self.public_input_layout
+    }
+
+    /// Serialises the description into the binary format documented in
+    /// this module.
+    pub fn to_bytes(&self) -> Vec<u8> {
+        let mut bytes = Vec::new();
+        bytes.extend_from_slice(MAGIC);
+        bytes.push(VERSION);
+        write_u64(self.n as u64, &mut bytes);
+
+        for selector in self.selectors.iter() {
+            write_scalars(selector, &mut bytes);
+        }
+
+        write_indices(self.public_input_positions.iter().copied(), &mut bytes);
+        for wire in self.wires.iter() {
+            write_indices(wire.iter().map(|var| var.0), &mut bytes);
+        }
+
+        write_u64(self.variable_wires.len() as u64, &mut bytes);
+        for wires in self.variable_wires.iter() {
+            write_u64(wires.len() as u64, &mut bytes);
+            for wire in wires.iter() {
+                let (kind, index) = match wire {
+                    WireData::Left(index) => (0, index),
+                    WireData::Right(index) => (1, index),
+                    WireData::Output(index) => (2, index),
+                    WireData::Fourth(index) => (3, index),
+                };
+                bytes.push(kind);
+                write_u64(*index as u64, &mut bytes);
+            }
+        }
+
+        write_u64(self.lookup_tables.len() as u64, &mut bytes);
+        for table in self.lookup_tables.iter() {
+            write_scalars(&table.0.concat(), &mut bytes);
+        }
+
+        write_u64(self.custom_gates.len() as u64, &mut bytes);
+        for custom in self.custom_gates.iter() {
+            write_gate(custom.gate.as_ref(), &mut bytes);
+            for column in custom.columns.iter() {
+                write_scalars(column, &mut bytes);
+            }
+        }
+
+        self.public_input_layout.write(&mut bytes);
+
+        bytes
+    }
+
+    /// Deserialises a description, resolving its custom gates by name among
+    /// `gates`.
+    pub fn from_bytes(
+        bytes: &[u8],
+        gates: &[Arc<dyn CustomGate>],
+    ) -> Result<CircuitDescription, Error> {
+        let (magic, rest) = read_n(MAGIC.len() + 1, bytes)?;
+        if magic[..MAGIC.len()] != *MAGIC || magic[MAGIC.len()] != VERSION {
+            return Err(CircuitDescriptionError::UnknownFormat.into());
+        }
+        let (n, mut rest) = read_u64(rest)?;
+
+        let mut selectors = Vec::with_capacity(SELECTOR_NAMES.len());
+        for _ in SELECTOR_NAMES.iter() {
+            let (selector, remaining) = read_scalars(rest)?;
+            selectors.push(selector);
+            rest = remaining;
+        }
+
+        let (public_input_positions, mut rest) = read_indices(rest)?;
+
+        let mut wires = Vec::with_capacity(4);
+        for _ in 0..4 {
+            let (wire, remaining) = read_indices(rest)?;
+            wires.push(wire.into_iter().map(Variable).collect::<Vec<_>>());
+            rest = remaining;
+        }
+
+        let (num_variables, mut rest) = read_u64(rest)?;
+        let mut variable_wires = Vec::new();
+        for _ in 0..num_variables {
+            let (num_wires, mut remaining) = read_u64(rest)?;
+            let mut var_wires = Vec::new();
+            for _ in 0..num_wires {
+                let (kind, index_bytes) = read_n(1, remaining)?;
+                let (index, index_rest) = read_u64(index_bytes)?;
+                let index = index as usize;
+                var_wires.push(match kind[0] {
+                    0 => WireData::Left(index),
+                    1 => WireData::Right(index),
+                    2 => WireData::Output(index),
+                    3 => WireData::Fourth(index),
+                    _ => return Err(CircuitDescriptionError::UnknownFormat.into()),
+                });
+                remaining = index_rest;
+            }
+            variable_wires.push(var_wires);
+            rest = remaining;
+        }
+
+        let (num_tables, mut rest) = read_u64(rest)?;
+        let mut lookup_tables = Vec::new();
+        for _ in 0..num_tables {
+            let (scalars, remaining) = read_scalars(rest)?;
+            if scalars.len() % 3 != 0 {
+                return Err(CircuitDescriptionError::UnknownFormat.into());
+            }
+            let rows = scalars
+                .chunks(3)
+                .map(|row| [row[0], row[1], row[2]])
+                .collect();
+            lookup_tables.push(LookupTable::new(rows));
+            rest = remaining;
+        }
+
+        let (num_custom, mut rest) = read_u64(rest)?;
+        let mut custom_gates = Vec::new();
+        for _ in 0..num_custom {
+            let (gate, mut remaining) = read_gate(rest, gates)?;
+            let mut columns = Vec::with_capacity(gate.num_selectors());
+            for _ in 0..gate.num_selectors() {
+                let (column, column_rest) = read_scalars(remaining)?;
+                columns.push(column);
+                remaining = column_rest;
+            }
+            custom_gates.push(CustomSelectors { gate, columns });
+            rest = remaining;
+        }
+
+        let (public_input_layout, _) = PublicInputLayout::read(rest)?;
+
+        let description = CircuitDescription {
+            n: n as usize,
+            selectors,
+            public_input_positions,
+            wires,
+            variable_wires,
+            lookup_tables,
+            custom_gates,
+            public_input_layout,
+        };
+        description.check()?;
+        Ok(description)
+    }
+
+    /// Checks that the gates and wires of the description are consistent.
+    fn check(&self) -> Result<(), Error> {
+        let n = self.n;
+        let num_variables = self.num_variables();
+
+        let columns_match = self.selectors.iter().all(|selector| selector.len() == n)
+            && self.wires.iter().all(|wire| wire.len() == n)
+            && self
+                .custom_gates
+                .iter()
+                .all(|custom| custom.columns.iter().all(|column| column.len() <= n));
+        if !columns_match {
+            return Err(CircuitDescriptionError::MismatchedLength.into());
+        }
+
+        let wires_in_circuit = self.public_input_positions.iter().all(|i| *i < n)
+            && self.public_input_layout.iter().all(|(_, i)| i < n)
+            && self.variable_wires.iter().flatten().all(|wire| match wire {
+                WireData::Left(i)
+                | WireData::Right(i)
+                | WireData::Output(i)
+                | WireData::Fourth(i) => *i < n,
+            });
+        let variables_exist =
+            num_variables > 0 && self.wires.iter().flatten().all(|var| var.0 < num_variables);
+        if !wires_in_circuit || !variables_exist {
+            return Err(CircuitDescriptionError::OutOfBounds.into());
+        }
+
+        Ok(())
+    }
+}
+
+fn write_indices(indices: impl ExactSizeIterator<Item = usize>, bytes: &mut Vec<u8>) {
+    write_u64(indices.len() as u64, bytes);
+    for index in indices {
+        write_u64(index as u64, bytes);
+    }
+}
+
+fn read_indices(bytes: &[u8]) -> Result<(Vec<usize>, &[u8]), Error> {
+    let (len, mut rest) = read_u64(bytes)?;
+    let mut indices = Vec::new();
+    for _ in 0..len {
+        let (index, remaining) = read_u64(rest)?;
+        indices.push(index as usize);
+        rest = remaining;
+    }
+    Ok((indices, rest))
+}
+
+impl fmt::Display for CircuitDescription {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        writeln!(f, "{} gates, {} variables", self.n, self.num_variables())?;
+        for index in 0..self.n {
+            write!(
+                f,
+                "{}: v{} v{} v{} v{}",
+                index,
+                self.wires[0][index].0,
+                self.wires[1][index].0,
+                self.wires[2][index].0,
+                self.wires[3][index].0
+            )?;
+            for (name, selector) in SELECTOR_NAMES.iter().zip(self.selectors.iter()) {
+                if selector[index] != BlsScalar::zero() {
+                    write!(f, " {}={:?}", name, selector[index])?;
+                }
+            }
+            for custom in self.custom_gates.iter() {
+                for (j, column) in custom.columns.iter().enumerate() {
+                    match column.get(index) {
+                        Some(selector) if *selector != BlsScalar::zero() => {
+                            write!(f, " {}[{}]={:?}", custom.gate.name(), j, selector)?
+                        }
+                        _ => (),
+                    }
+                }
+            }
+            writeln!(f)?;
+        }
+
+        writeln!(f, "public inputs: {:?}", self.public_input_positions)?;
+        for (name, position) in self.public_input_layout.iter() {
+            writeln!(f, "public input {}: {}", name, position)?;
+        }
+        for (var, wires) in self.variable_wires.iter().enumerate() {
+            writeln!(f, "v{}: {:?}", var, wires)?;
+        }
+        for (i, table) in self.lookup_tables.iter().enumerate() {
+            writeln!(f, "lookup table {}: {:?}", i + 1, table.rows())?;
+        }
+        Ok(())
+    }
+}
+
+impl StandardComposer {
+    /// Exports the gates, selectors and copy constraints of the circuit,
+    /// without the values of its variables.
+    ///
+    /// The public inputs are the ones declared with `public_input`, whatever
+    /// their value, along with the gates which were given a non-zero public
+    /// input directly when they were added.
+    pub fn circuit_description(&self) -> CircuitDescription {
+        let public_input_positions = self.public_input_positions();
+        let variable_wires = (0..self.perm.variable_map.len())
+            .map(|var| self.perm.variable_map[&Variable(var)].clone())
+            .collect();
+
+        CircuitDescription {
+            n: self.n,
+            selectors: vec![
+                self.q_m.clone(),
+                self.q_l.clone(),
+                self.q_r.clone(),
+                self.q_o.clone(),
+                self.q_c.clone(),
+                self.q_4.clone(),
+                self.q_arith.clone(),
+                self.q_range.clone(),
+                self.q_logic.clone(),
+                self.q_variable_group_add.clone(),
+                self.q_lookup.clone(),
+                self.q_sbox.clone(),
+            ],
+            public_input_positions: public_input_positions.into_iter().collect(),
+            wires: vec![
+                self.w_l.clone(),
+                self.w_r.clone(),
+                self.w_o.clone(),
+                self.w_4.clone(),
+            ],
+            variable_wires,
+            lookup_tables: self.lookup_tables.clone(),
+            custom_gates: self.custom_gates.clone(),
+            public_input_layout: self.public_input_layout.clone(),
+        }
+    }
+
+    /// Returns a digest of the gates, selectors, copy constraints, lookup
+    /// tables, custom gates and declared public inputs of the circuit, which
+    /// does not depend on the values of its variables.
+    ///
+    /// It is stored in the `ProverKey` of the circuit, taken before the
+    /// circuit is padded by the preprocessing, so that a witness can be
+    /// checked to be assigned to the same circuit before being proven.
+    pub fn circuit_digest(&self) -> BlsScalar {
+        let mut description = self.circuit_description();
+        // The gates setting their public input directly are located by its
+        // value, which depends on the witness
+        description.public_input_positions.clear();
+
+        let mut transcript = Transcript::new(b"dusk-plonk-circuit");
+        transcript.append_message(b"description", &description.to_bytes());
+        transcript.challenge_scalar(b"digest")
+    }
+
+    /// Rebuilds a composer from a `CircuitDescription`, with every variable
+    /// and public input set to zero.
+    ///
+    /// The composer can be preprocessed into the same keys as the one the
+    /// description was exported from, but it does not hold a witness that
+    /// can be proven.
+    pub fn from_circuit_description(description: CircuitDescription) -> StandardComposer {
+        let CircuitDescription {
+            n,
+            selectors,
+            public_input_positions,
+            wires,
+            variable_wires,
+            lookup_tables,
+            custom_gates,
+            public_input_layout,
+        } = description;
+
+        let mut selectors = selectors.into_iter();
+        let mut selector = || selectors.next().unwrap();
+        let mut wires = wires.into_iter();
+        let mut wire = || wires.next().unwrap();
+
+        let variables: HashMap<_, _> = (0..variable_wires.len())
+            .map(|var| (Variable(var), BlsScalar::zero()))
+            .collect();
+        let variable_map = variable_wires
+            .into_iter()
+            .enumerate()
+            .map(|(var, wires)| (Variable(var), wires))
+            .collect();
+        let (w_l, w_r, w_o, w_4) = (wire(), wire(), wire(), wire());
+        let perm = Permutation::from_variable_wires(variable_map, [&w_l, &w_r, &w_o, &w_4]);
+
+        StandardComposer {
+            n,
+            q_m: selector(),
+            q_l: selector(),
+            q_r: selector(),
+            q_o: selector(),
+            q_c: selector(),
+            q_4: selector(),
+            q_arith: selector(),
+            q_range: selector(),
+            q_logic: selector(),
+            q_variable_group_add: selector(),
+            q_lookup: selector(),
+            q_sbox: selector(),
+            public_inputs: vec![BlsScalar::zero(); n],
+            w_l,
+            w_r,
+            w_o,
+            w_4,
+            zero_var: Variable(0),
+            variables,
+            witness_generators: WitnessGenerators::default(),
+            perm,
+            lookup_tables,
+            custom_gates,
+            public_input_layout,
+            public_input_gates: public_input_positions,
+            open_namespaces: Vec::new(),
+            namespaces: Vec::new(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::commitment_scheme::kzg10::PublicParameters;
+    use crate::proof_system::{GateWires, Prover, Verifier};
+
+    // Constrains `c = a * b` with its selector
+    #[derive(Debug)]
+    struct ProductGate;
+
+    impl CustomGate for ProductGate {
+        fn name(&self) -> &'static str {
+            "product"
+        }
+
+        fn num_selectors(&self) -> usize {
+            1
+        }
+
+        fn coefficients(&self, wires: &GateWires, _: &BlsScalar) -> Vec<BlsScalar> {
+            vec![wires.a * wires.b - wires.c]
+        }
+    }
+
+    fn build_circuit(composer: &mut StandardComposer) {
+        let a = composer.add_input(BlsScalar::from(5));
+        let b = composer.add_input(BlsScalar::from(7));
+        composer.range_gate(a, 8);
+        let c = composer.xor_gate(a, b, 8);
+        composer.poly_gate(
+            a,
+            b,
+            c,
+            BlsScalar::zero(),
+            BlsScalar::one(),
+            BlsScalar::one(),
+            -BlsScalar::one(),
+            BlsScalar::zero(),
+            BlsScalar::from(10),
+        );
+
+        let table = composer.add_lookup_table(LookupTable::from_binary_fn(2, |x, y| x & y));
+        let one = composer.add_input(BlsScalar::one());
+        let three = composer.add_input(BlsScalar::from(3));
+        composer.lookup_gate(table, one, three, one);
+
+        let gate = composer.register_custom_gate(Arc::new(ProductGate));
+        let product = composer.add_input(BlsScalar::from(35));
+        composer.custom_gate(
+            gate,
+            (a, b, product, composer.zero_var),
+            &[BlsScalar::one()],
+        );
+    }
+
+    #[test]
+    fn test_circuit_description_roundtrip() {
+        let mut composer = StandardComposer::new();
+        build_circuit(&mut composer);
+        let gates: Vec<Arc<dyn CustomGate>> = vec![Arc::new(ProductGate)];
+
+        let description = composer.circuit_description();
+        let bytes = description.to_bytes();
+        let loaded = CircuitDescription::from_bytes(&bytes, &gates).unwrap();
+        assert_eq!(loaded.to_bytes(), bytes);
+        assert_eq!(loaded.to_string(), description.to_string());
+        assert_eq!(loaded.circuit_size(), composer.circuit_size());
+        assert_eq!(loaded.num_variables(), composer.variables.len());
+        assert_eq!(
+            loaded.public_input_positions(),
+            &[composer.circuit_size() - 3]
+        );
+
+        // The loaded circuit is preprocessed into the same keys
+        let public_parameters = PublicParameters::setup(128, &mut rand::thread_rng()).unwrap();
+        let (ck, _) = public_parameters.trim(64).unwrap();
+
+        let mut prover = Prover::new(b"description");
+        build_circuit(prover.mut_cs());
+        prover.preprocess(&ck).unwrap();
+        let mut loaded_prover = Prover::new(b"description");
+        *loaded_prover.mut_cs() = StandardComposer::from_circuit_description(loaded.clone());
+        loaded_prover.preprocess(&ck).unwrap();
+        assert!(prover.prover_key == loaded_prover.prover_key);
+
+        let mut verifier = Verifier::new(b"description");
+        build_circuit(verifier.mut_cs());
+        verifier.preprocess(&ck).unwrap();
+        let mut loaded_verifier = Verifier::new(b"description");
+        *loaded_verifier.mut_cs() = StandardComposer::from_circuit_description(loaded);
+        loaded_verifier.preprocess(&ck).unwrap();
+        assert_eq!(
+            verifier.verifier_key.unwrap().to_bytes(),
+            loaded_verifier.verifier_key.unwrap().to_bytes()
+        );
+    }
+
+    #[test]
+    fn test_circuit_description_zero_public_inputs() {
+        let mut composer = StandardComposer::new();
+        let n = composer.circuit_size();
+        let root = composer.public_input("root", BlsScalar::zero());
+        let var_one = composer.add_input(BlsScalar::one());
+        composer.constrain_to_constant(var_one, BlsScalar::zero(), -BlsScalar::one());
+        composer.constrain_to_constant(root, BlsScalar::zero(), BlsScalar::zero());
+
+        // The declared public input is located even though its value is zero
+        let description = composer.circuit_description();
+        assert_eq!(description.public_input_positions(), &[n, n + 1]);
+
+        // The positions are kept by the composer loaded from the description
+        let loaded = StandardComposer::from_circuit_description(description.clone());
+        assert_eq!(
+            loaded.circuit_description().to_bytes(),
+            description.to_bytes()
+        );
+    }
+
+    #[test]
+    fn test_circuit_description_malformed() {
+        let mut composer = StandardComposer::new();
+        build_circuit(&mut composer);
+        let bytes = composer.circuit_description().to_bytes();
+        let gates: Vec<Arc<dyn CustomGate>> = vec![Arc::new(ProductGate)];
+
+        // The custom gate is unknown
+        assert!(CircuitDescription::from_bytes(&bytes, &[]).is_err());
+
+        let mut wrong_version = bytes.clone();
+        wrong_version[MAGIC.len()] += 1;
+        assert!(CircuitDescription::from_bytes(&wrong_version, &gates).is_err());
+        assert!(CircuitDescription::from_bytes(&bytes[..bytes.len() - 1], &gates).is_err());
+
+        // A wire refers to a variable which does not exist
+        let mut description = composer.circuit_description();
+        description.wires[0][0] = Variable(description.num_variables());
+        assert!(CircuitDescription::from_bytes(&description.to_bytes(), &gates).is_err());
+    }
+}
@@ -6,7 +6,10 @@
 
 use crate::constraint_system::StandardComposer;
 use crate::constraint_system::Variable;
+use crate::proof_system::widget::custom::same_gate;
+use crate::proof_system::widget::ecc::scalar_mul::fixed_base::FixedBaseGate;
 use dusk_bls12_381::BlsScalar;
+use std::sync::Arc;
 
 #[derive(Debug, Clone, Copy)]
 /// Contains all of the components needed to verify that a bit scalar multiplication was computed correctly
@@ -24,48 +27,57 @@ pub(crate) struct WnafRound {
     /// This is the multiplication of x_\alpha * y_\alpha
     /// we need this as a distinct wire, so that the degree of the polynomial does not go over 4
     pub xy_alpha: Variable,
-    /// This is the possible x co-ordinate of the wnaf point we are going to add
-    /// Actual x-co-ordinate = b_i * x_\beta
+    /// This is the coefficient of the digit in the x co-ordinate of the wnaf point we are going to add
+    /// Actual x-co-ordinate = b_i * x_\beta + (b_i)^3 * x_\gamma
     pub x_beta: BlsScalar,
-    /// This is the possible y co-ordinate of the wnaf point we are going to add
-    /// Actual y coordinate = (b_i)^2 [y_\beta -1] + 1
+    /// This is the constant term of the y co-ordinate of the wnaf point we are going to add
+    /// Actual y coordinate = y_\beta + (b_i)^2 * y_\gamma
     pub y_beta: BlsScalar,
-    /// This is the multiplication of x_\beta * y_\beta
+    /// This is the coefficient of the digit in x_\alpha * y_\alpha
+    /// Actual xy_\alpha = b_i * xy_\beta + (b_i)^3 * xy_\gamma
     pub xy_beta: BlsScalar,
+    /// This is the coefficient of the cube of the digit in the x co-ordinate
+    pub x_gamma: BlsScalar,
+    /// This is the coefficient of the square of the digit in the y co-ordinate
+    pub y_gamma: BlsScalar,
+    /// This is the coefficient of the cube of the digit in x_\alpha * y_\alpha
+    pub xy_gamma: BlsScalar,
 }
 
 impl StandardComposer {
     /// Fixed group addition of a jubjub point
     pub(crate) fn fixed_group_add(&mut self, wnaf_round: WnafRound) {
-        self.w_l.push(wnaf_round.acc_x);
-        self.w_r.push(wnaf_round.acc_y);
-        self.w_o.push(wnaf_round.xy_alpha);
-        self.w_4.push(wnaf_round.accumulated_bit);
-
-        self.q_l.push(wnaf_round.x_beta);
-        self.q_r.push(wnaf_round.y_beta);
-
-        self.q_c.push(wnaf_round.xy_beta);
-        self.q_o.push(BlsScalar::zero());
-        self.q_fixed_group_add.push(BlsScalar::one());
-        self.q_variable_group_add.push(BlsScalar::zero());
-
-        self.q_m.push(BlsScalar::zero());
-        self.q_4.push(BlsScalar::zero());
-        self.q_arith.push(BlsScalar::zero());
-        self.q_range.push(BlsScalar::zero());
-        self.q_logic.push(BlsScalar::zero());
-
-        self.public_inputs.push(BlsScalar::zero());
-
-        self.perm.add_variables_to_map(
-            wnaf_round.acc_x,
-            wnaf_round.acc_y,
-            wnaf_round.xy_alpha,
-            wnaf_round.accumulated_bit,
-            self.n,
+        let gate = self.fixed_base_gate();
+        self.custom_gate(
+            gate,
+            (
+                wnaf_round.acc_x,
+                wnaf_round.acc_y,
+                wnaf_round.xy_alpha,
+                wnaf_round.accumulated_bit,
+            ),
+            &[
+                BlsScalar::one(),
+                wnaf_round.x_beta,
+                wnaf_round.y_beta,
+                wnaf_round.xy_beta,
+                wnaf_round.x_gamma,
+                wnaf_round.y_gamma,
+                wnaf_round.xy_gamma,
+            ],
         );
+    }
 
-        self.n += 1;
+    /// Returns the identifier of the fixed base gate, which is registered
+    /// the first time it is used.
+    fn fixed_base_gate(&mut self) -> usize {
+        match self
+            .custom_gates
+            .iter()
+            .position(|custom| same_gate(custom.gate.as_ref(), &FixedBaseGate))
+        {
+            Some(gate) => gate,
+            None => self.register_custom_gate(Arc::new(FixedBaseGate)),
+        }
     }
 }
@@ -24,25 +24,27 @@ impl Point {
         let x_2 = point_b.x;
         let y_2 = point_b.y;
 
-        // Compute the resulting point
-        let x_1_scalar = composer.variables.get(&x_1).unwrap();
-        let y_1_scalar = composer.variables.get(&y_1).unwrap();
-        let x_2_scalar = composer.variables.get(&x_2).unwrap();
-        let y_2_scalar = composer.variables.get(&y_2).unwrap();
+        // Add the rest of the prepared points into the composer
+        let x_1_y_2 = composer.add_input(BlsScalar::zero());
+        let x_3 = composer.add_input(BlsScalar::zero());
+        let y_3 = composer.add_input(BlsScalar::zero());
 
-        let p1 = JubJubAffine::from_raw_unchecked(*x_1_scalar, *y_1_scalar);
-        let p2 = JubJubAffine::from_raw_unchecked(*x_2_scalar, *y_2_scalar);
+        composer.add_witness_generator(move |composer| {
+            // Compute the resulting point
+            let x_1_scalar = composer.variables[&x_1];
+            let y_1_scalar = composer.variables[&y_1];
+            let x_2_scalar = composer.variables[&x_2];
+            let y_2_scalar = composer.variables[&y_2];
 
-        let point: JubJubAffine = (JubJubExtended::from(p1) + p2).into();
-        let x_3_scalar = point.get_x();
-        let y_3_scalar = point.get_y();
+            let p1 = JubJubAffine::from_raw_unchecked(x_1_scalar, y_1_scalar);
+            let p2 = JubJubAffine::from_raw_unchecked(x_2_scalar, y_2_scalar);
 
-        let x1_scalar_y2_scalar = x_1_scalar * y_2_scalar;
+            let point: JubJubAffine = (JubJubExtended::from(p1) + p2).into();
 
-        // Add the rest of the prepared points into the composer
-        let x_1_y_2 = composer.add_input(x1_scalar_y2_scalar);
-        let x_3 = composer.add_input(x_3_scalar);
-        let y_3 = composer.add_input(y_3_scalar);
+            composer.variables.insert(x_1_y_2, x_1_scalar * y_2_scalar);
+            composer.variables.insert(x_3, point.get_x());
+            composer.variables.insert(y_3, point.get_y());
+        });
 
         composer.w_l.append(&mut vec![x_1, x_3]);
         composer.w_r.append(&mut vec![y_1, y_3]);
@@ -76,12 +78,15 @@ impl Point {
         composer
             .q_logic
             .append(&mut vec![BlsScalar::zero(), BlsScalar::zero()]);
-        composer
-            .q_fixed_group_add
-            .append(&mut vec![BlsScalar::zero(), BlsScalar::zero()]);
 
         composer.q_variable_group_add.push(BlsScalar::one());
+
+        composer.q_lookup.push(BlsScalar::zero());
+
+        composer.q_sbox.push(BlsScalar::zero());
         composer.q_variable_group_add.push(BlsScalar::zero());
+        composer.q_lookup.push(BlsScalar::zero());
+        composer.q_sbox.push(BlsScalar::zero());
 
         composer
             .public_inputs
@@ -123,7 +128,7 @@ mod test {
 
                 composer.assert_equal_point(point, point2);
 
-                composer.assert_equal_public_point(point.into(), expected_point);
+                composer.assert_equal_public_point(point.into(), "expected", expected_point);
             },
             2000,
         );
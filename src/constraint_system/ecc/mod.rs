@@ -11,7 +11,7 @@ pub mod scalar_mul;
 
 use crate::constraint_system::{variable::Variable, StandardComposer};
 use dusk_bls12_381::BlsScalar;
-use dusk_jubjub::EDWARDS_D;
+use dusk_jubjub::{JubJubAffine, JubJubExtended, JubJubScalar, EDWARDS_D};
 
 /// Represents a JubJub point in the circuit
 #[derive(Debug, Clone, Copy)]
@@ -51,6 +51,16 @@ impl Point {
         Point { x, y }
     }
     /// Converts an JubJubAffine into a constraint system Point
+    /// constrained to be on the curve
+    pub fn from_private_affine_checked(
+        composer: &mut StandardComposer,
+        affine: dusk_jubjub::JubJubAffine,
+    ) -> Point {
+        let point = Point::from_private_affine(composer, affine);
+        composer.assert_on_curve(point);
+        point
+    }
+    /// Converts an JubJubAffine into a constraint system Point
     /// without constraining the values
     pub fn from_public_affine(
         composer: &mut StandardComposer,
@@ -185,13 +195,11 @@ impl Point {
         );
 
         // Compute the inverse
-        let inv_x_denom = composer
-            .variables
-            .get(&x_denominator)
-            .unwrap()
-            .invert()
-            .unwrap();
-        let inv_x_denom = composer.add_input(inv_x_denom);
+        let inv_x_denom = composer.add_input(BlsScalar::zero());
+        composer.add_witness_generator(move |composer| {
+            let inv = composer.variables[&x_denominator].invert().unwrap();
+            composer.variables.insert(inv_x_denom, inv);
+        });
 
         // Assert that we actually have the inverse
         // inv_x * x = 1
@@ -212,13 +220,11 @@ impl Point {
             BlsScalar::one(),
             BlsScalar::zero(),
         );
-        let inv_y_denom = composer
-            .variables
-            .get(&y_denominator)
-            .unwrap()
-            .invert()
-            .unwrap();
-        let inv_y_denom = composer.add_input(inv_y_denom);
+        let inv_y_denom = composer.add_input(BlsScalar::zero());
+        composer.add_witness_generator(move |composer| {
+            let inv = composer.variables[&y_denominator].invert().unwrap();
+            composer.variables.insert(inv_y_denom, inv);
+        });
         // Assert that we actually have the inverse
         // inv_y * y = 1
         composer.mul_gate(
@@ -255,19 +261,90 @@ impl Point {
 // XXX: Should we put these as methods on the point struct instead?
 // Rationale, they only apply to points, whereas methods on the composer apply generally to Variables
 impl StandardComposer {
-    /// Asserts that a point in the circuit is equal to a known public point
+    /// Asserts that a point in the circuit is equal to a known public point,
+    /// declared with `public_point` as the public inputs `{name}.x` and
+    /// `{name}.y`.
     pub fn assert_equal_public_point(
         &mut self,
         point: Point,
+        name: &str,
         public_point: dusk_jubjub::JubJubAffine,
     ) {
-        self.constrain_to_constant(point.x, BlsScalar::zero(), -public_point.get_x());
-        self.constrain_to_constant(point.y, BlsScalar::zero(), -public_point.get_y());
+        let public_point = self.public_point(name, public_point);
+        self.assert_equal_point(point, public_point);
+    }
+    /// Declares the public inputs `{name}.x` and `{name}.y` holding the
+    /// coordinates of `public_point`, and returns the `Point` constrained to
+    /// them.
+    pub fn public_point(&mut self, name: &str, public_point: dusk_jubjub::JubJubAffine) -> Point {
+        let x = self.public_input(&format!("{}.x", name), public_point.get_x());
+        let y = self.public_input(&format!("{}.y", name), public_point.get_y());
+        Point { x, y }
     }
     /// Asserts that a point in the circuit is equal to another point in the circuit
     pub fn assert_equal_point(&mut self, point_a: Point, point_b: Point) {
         self.assert_equal(point_a.x, point_b.x);
-        self.assert_equal(point_b.y, point_b.y);
+        self.assert_equal(point_a.y, point_b.y);
+    }
+    /// Asserts that a point in the circuit satisfies the twisted Edwards
+    /// equation `-x^2 + y^2 = 1 + d * x^2 * y^2` of JubJub
+    pub fn assert_on_curve(&mut self, point: Point) {
+        let x_sq = self.mul(
+            BlsScalar::one(),
+            point.x,
+            point.x,
+            BlsScalar::zero(),
+            BlsScalar::zero(),
+        );
+        let y_sq = self.mul(
+            BlsScalar::one(),
+            point.y,
+            point.y,
+            BlsScalar::zero(),
+            BlsScalar::zero(),
+        );
+        // -x^2 + y^2 - d * x^2 * y^2 - 1 = 0
+        self.poly_gate(
+            x_sq,
+            y_sq,
+            self.zero_var,
+            -EDWARDS_D,
+            -BlsScalar::one(),
+            BlsScalar::one(),
+            BlsScalar::zero(),
+            -BlsScalar::one(),
+            BlsScalar::zero(),
+        );
+    }
+    /// Asserts that a point in the circuit is in the prime order subgroup of
+    /// JubJub, identity included, which also constrains it to be on the curve
+    ///
+    /// The point is constrained to be eight times a point on the curve, which
+    /// only exists for the points whose cofactor is cleared.
+    pub fn assert_prime_order(&mut self, point: Point) {
+        let quotient = Point::from_private_affine(self, JubJubAffine::identity());
+        self.add_witness_generator(move |composer| {
+            let x = composer.variables[&point.x];
+            let y = composer.variables[&point.y];
+            let (x_sq, y_sq) = (x.square(), y.square());
+            let on_curve = y_sq - x_sq == BlsScalar::one() + EDWARDS_D * x_sq * y_sq;
+
+            // The point divided by the cofactor, left to the identity when the
+            // point is not on the curve, so the constraints fail
+            let value = if on_curve {
+                let cofactor_inv = JubJubScalar::from(8u64).invert().unwrap();
+                let point = JubJubExtended::from(JubJubAffine::from_raw_unchecked(x, y));
+                JubJubAffine::from(point * cofactor_inv)
+            } else {
+                JubJubAffine::identity()
+            };
+            composer.variables.insert(quotient.x, value.get_x());
+            composer.variables.insert(quotient.y, value.get_y());
+        });
+        self.assert_on_curve(quotient);
+
+        let multiple = (0..3).fold(quotient, |multiple, _| multiple.fast_add(self, multiple));
+        self.assert_equal_point(multiple, point);
     }
 }
 
@@ -300,4 +377,46 @@ mod tests {
         );
         assert!(res.is_ok());
     }
+
+    #[test]
+    fn test_point_validation() {
+        let generator = JubJubExtended::from(dusk_jubjub::GENERATOR);
+        // The point of order 2, on the curve but outside the prime order subgroup
+        let torsion = JubJubAffine::from_raw_unchecked(BlsScalar::zero(), -BlsScalar::one());
+        let mixed = JubJubAffine::from(generator + JubJubExtended::from(torsion));
+
+        // On the curve
+        for affine in &[dusk_jubjub::GENERATOR, torsion, mixed] {
+            let mut composer = StandardComposer::new();
+            Point::from_private_affine_checked(&mut composer, *affine);
+            assert!(composer.check_satisfied().is_ok());
+        }
+        let mut composer = StandardComposer::new();
+        let off_curve = JubJubAffine::from_raw_unchecked(BlsScalar::one(), BlsScalar::one());
+        Point::from_private_affine_checked(&mut composer, off_curve);
+        assert!(composer.check_satisfied().is_err());
+
+        // In the prime order subgroup
+        for affine in &[dusk_jubjub::GENERATOR, JubJubAffine::identity()] {
+            let mut composer = StandardComposer::new();
+            let point = Point::from_private_affine(&mut composer, *affine);
+            composer.assert_prime_order(point);
+            assert!(composer.check_satisfied().is_ok());
+        }
+        for affine in &[torsion, mixed, off_curve] {
+            let mut composer = StandardComposer::new();
+            let point = Point::from_private_affine(&mut composer, *affine);
+            composer.assert_prime_order(point);
+            assert!(composer.check_satisfied().is_err());
+        }
+
+        let res = gadget_tester(
+            |composer| {
+                let point = Point::from_private_affine_checked(composer, dusk_jubjub::GENERATOR);
+                composer.assert_prime_order(point);
+            },
+            64,
+        );
+        assert!(res.is_ok());
+    }
 }
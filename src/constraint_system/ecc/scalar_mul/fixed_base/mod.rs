@@ -10,138 +10,232 @@ use crate::constraint_system::{variable::Variable, StandardComposer};
 use dusk_bls12_381::BlsScalar;
 use dusk_jubjub::{JubJubAffine, JubJubExtended, JubJubScalar};
 
-fn compute_wnaf_point_multiples(generator: JubJubExtended, num_bits: usize) -> Vec<JubJubAffine> {
-    assert!(generator.is_prime_order().unwrap_u8() == 1);
+/// Number of base 4 digits of the scalars, as the JubJub scalars are below
+/// `4^126 = 2^252`.
+const NUM_DIGITS: usize = 126;
+
+/// The multiples `4^i H` and `3 * 4^i H` of half `H` of a generator `G` added
+/// by the gates of `scalar_mul`, which can be computed once for the scalar
+/// multiplications sharing the generator.
+#[derive(Debug, Clone)]
+pub struct GeneratorTable {
+    multiples: Vec<(JubJubAffine, JubJubAffine)>,
+    // The point `(4^126 - 1) H` the accumulator starts from
+    offset: JubJubAffine,
+}
 
-    let mut multiples = vec![JubJubExtended::default(); num_bits];
-    multiples[0] = generator;
-    for i in 1..num_bits {
-        multiples[i] = multiples[i - 1].double();
-    }
+impl GeneratorTable {
+    /// Computes the multiples of `generator`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `generator` does not have prime order, as the table holds
+    /// multiples of its half.
+    pub fn new(generator: JubJubExtended) -> GeneratorTable {
+        assert!(
+            generator.is_prime_order().unwrap_u8() == 1,
+            "the generator must have prime order"
+        );
+
+        let half = generator * JubJubScalar::from(2u64).invert().unwrap();
+        let mut points = Vec::with_capacity(2 * NUM_DIGITS + 1);
+        let mut power = half;
+        let mut offset = JubJubExtended::identity();
+        for _ in 0..NUM_DIGITS {
+            let triple = power.double() + power;
+            points.push(power);
+            points.push(triple);
+            offset += triple;
+            power = power.double().double();
+        }
+        points.push(offset);
 
-    dusk_jubjub::batch_normalize(&mut multiples).collect()
+        let mut points: Vec<JubJubAffine> = dusk_jubjub::batch_normalize(&mut points).collect();
+        let offset = points.pop().unwrap();
+        GeneratorTable {
+            multiples: points.chunks(2).map(|pair| (pair[0], pair[1])).collect(),
+            offset,
+        }
+    }
 }
 
 /// Computes a BlsScalar multiplication with the input scalar and a chosen generator
+///
+/// # Panics
+///
+/// Panics if `generator` does not have prime order.
 pub fn scalar_mul(
     composer: &mut StandardComposer,
     jubjub_scalar: Variable,
     generator: JubJubExtended,
 ) -> PointScalar {
-    // XXX: we can slice off 3 bits from the top of wnaf, since F_r prime has 252 bits.
-    // XXX :We can also move to base4 and have half the number of gates since wnaf adjacent entries product is zero, we will not go over the specified amount
-    let num_bits = 256;
-
-    // compute 2^iG
-    let mut point_multiples = compute_wnaf_point_multiples(generator, num_bits);
-    point_multiples.reverse();
-
-    // Fetch the raw scalar value as bls scalar, then convert to a jubjub scalar
-    // XXX: Not very Tidy, impl From function in JubJub
-    let raw_bls_scalar = composer.variables.get(&jubjub_scalar).unwrap();
-    let raw_jubjub_scalar = JubJubScalar::from_bytes(&raw_bls_scalar.to_bytes()).unwrap();
-
-    // Convert scalar to wnaf_2(k)
-    let wnaf_entries = raw_jubjub_scalar.compute_windowed_naf(2);
-    assert_eq!(wnaf_entries.len(), num_bits);
-
-    // Initialise the accumulators
-    let mut scalar_acc: Vec<BlsScalar> = Vec::new();
-    scalar_acc.push(BlsScalar::zero());
-    let mut point_acc: Vec<JubJubAffine> = Vec::new();
-    point_acc.push(JubJubAffine::identity());
-
-    // Auxillary point to help with checks on the backend
-    let mut xy_alphas = Vec::new();
-
-    // Load values into accumulators based on wnaf entries
-    for (i, entry) in wnaf_entries.iter().rev().enumerate() {
-        // Based on the WNAF, we decide what scalar and point to add
-        let (scalar_to_add, point_to_add) = match entry {
-            0 => { (BlsScalar::zero(), JubJubAffine::identity())},
-            -1 => {(BlsScalar::one().neg(), -point_multiples[i])},
-            1 => {(BlsScalar::one(), point_multiples[i])},
-            _ => unreachable!("Currently WNAF_2(k) is supported. The possible values are 1, -1 and 0. Current entry is {}", entry),
-        };
-
-        let prev_accumulator = BlsScalar::from(2u64) * scalar_acc[i];
-        scalar_acc.push(prev_accumulator + scalar_to_add);
-        point_acc
-            .push((JubJubExtended::from(point_acc[i]) + JubJubExtended::from(point_to_add)).into());
-
-        let x_alpha = point_to_add.get_x();
-        let y_alpha = point_to_add.get_y();
+    scalar_mul_with_table(composer, jubjub_scalar, &GeneratorTable::new(generator))
+}
 
-        xy_alphas.push(x_alpha * y_alpha);
+/// Computes a BlsScalar multiplication with the input scalar and the generator
+/// of a precomputed `GeneratorTable`
+///
+/// A scalar `s` below `4^126` is written as `(D + 4^126 - 1) / 2`, where `D`
+/// has 126 odd base 4 digits in `{-3, -1, 1, 3}`. Each gate adds the multiple
+/// of half the generator matching one digit to an accumulator starting from
+/// `(4^126 - 1) / 2` times the generator.
+pub fn scalar_mul_with_table(
+    composer: &mut StandardComposer,
+    jubjub_scalar: Variable,
+    table: &GeneratorTable,
+) -> PointScalar {
+    composer.push_namespace("scalar_mul");
+
+    // The point accumulator starts from the offset and the scalar accumulator
+    // from zero, while the next accumulators and the auxillary points, which
+    // help with checks on the backend, are computed by the witness generator
+    let mut accumulators = Vec::with_capacity(NUM_DIGITS + 1);
+    accumulators.push((
+        composer.add_witness_to_circuit_description(table.offset.get_x()),
+        composer.add_witness_to_circuit_description(table.offset.get_y()),
+        composer.zero_var,
+    ));
+    for _ in 0..NUM_DIGITS {
+        accumulators.push((
+            composer.add_input(BlsScalar::zero()),
+            composer.add_input(BlsScalar::zero()),
+            composer.add_input(BlsScalar::zero()),
+        ));
     }
-
-    for i in 0..num_bits {
-        let acc_x = composer.add_input(point_acc[i].get_x());
-        let acc_y = composer.add_input(point_acc[i].get_y());
-
-        let accumulated_bit = composer.add_input(scalar_acc[i]);
-
-        // We constrain the point accumulator to start from the Identity point
-        // and the Scalar accumulator to start from zero
-        if i == 0 {
-            composer.constrain_to_constant(acc_x, BlsScalar::zero(), BlsScalar::zero());
-            composer.constrain_to_constant(acc_y, BlsScalar::one(), BlsScalar::zero());
-            composer.constrain_to_constant(accumulated_bit, BlsScalar::zero(), BlsScalar::zero());
+    let xy_alphas: Vec<Variable> = (0..NUM_DIGITS)
+        .map(|_| composer.add_input(BlsScalar::zero()))
+        .collect();
+
+    let generator_table = table.clone();
+    let generated_accumulators = accumulators.clone();
+    let generated_xy_alphas = xy_alphas.clone();
+    composer.add_witness_generator(move |composer| {
+        let table = &generator_table;
+        let raw_bls_scalar = composer.variables[&jubjub_scalar];
+        let digits = odd_base4_digits(&raw_bls_scalar);
+
+        // Initialise the accumulators
+        let mut scalar_acc: Vec<BlsScalar> = Vec::with_capacity(NUM_DIGITS + 1);
+        scalar_acc.push(BlsScalar::zero());
+        let mut point_acc: Vec<JubJubExtended> = Vec::with_capacity(NUM_DIGITS + 1);
+        point_acc.push(table.offset.into());
+
+        // Load values into accumulators based on the digits, from the highest one
+        for i in 0..NUM_DIGITS {
+            let power = NUM_DIGITS - 1 - i;
+            let (single, triple) = table.multiples[power];
+            let (scalar_to_add, point_to_add) = match digits[power] {
+                -3 => (-BlsScalar::from(3u64), -triple),
+                -1 => (-BlsScalar::one(), -single),
+                1 => (BlsScalar::one(), single),
+                3 => (BlsScalar::from(3u64), triple),
+                digit => unreachable!(
+                    "The odd base 4 digits are 3, 1, -1 and -3. Current digit is {}",
+                    digit
+                ),
+            };
+
+            scalar_acc.push(BlsScalar::from(4u64) * scalar_acc[i] + scalar_to_add);
+            point_acc.push(point_acc[i] + JubJubExtended::from(point_to_add));
+
+            composer.variables.insert(
+                generated_xy_alphas[i],
+                point_to_add.get_x() * point_to_add.get_y(),
+            );
+        }
+        let point_acc: Vec<JubJubAffine> = dusk_jubjub::batch_normalize(&mut point_acc).collect();
+
+        for (i, (acc_x, acc_y, accumulated_bit)) in
+            generated_accumulators.iter().enumerate().skip(1)
+        {
+            composer.variables.insert(*acc_x, point_acc[i].get_x());
+            composer.variables.insert(*acc_y, point_acc[i].get_y());
+            composer.variables.insert(*accumulated_bit, scalar_acc[i]);
         }
+    });
 
-        let x_beta = point_multiples[i].get_x();
-        let y_beta = point_multiples[i].get_y();
+    // The coordinates of the points `b_i * P` for a digit `b_i` are
+    // interpolated from the points `P` and `3P` as `x = b_i * x_beta + b_i^3 *
+    // x_gamma` and `y = y_beta + b_i^2 * y_gamma`
+    let inv_eight = BlsScalar::from(8u64).invert().unwrap();
+    let inv_twenty_four = BlsScalar::from(24u64).invert().unwrap();
+    let three = BlsScalar::from(3u64);
 
-        let xy_alpha = composer.add_input(xy_alphas[i]);
+    for i in 0..NUM_DIGITS {
+        let (acc_x, acc_y, accumulated_bit) = accumulators[i];
 
-        let xy_beta = x_beta * y_beta;
+        let (single, triple) = table.multiples[NUM_DIGITS - 1 - i];
+        let (x_1, y_1) = (single.get_x(), single.get_y());
+        let (x_3, y_3) = (triple.get_x(), triple.get_y());
+
+        let x_gamma = (x_3 - three * x_1) * inv_twenty_four;
+        let y_gamma = (y_3 - y_1) * inv_eight;
+        let xy_gamma = (x_3 * y_3 - three * x_1 * y_1) * inv_twenty_four;
 
         let wnaf_round = WnafRound {
             acc_x,
             acc_y,
             accumulated_bit,
-            xy_alpha,
-            x_beta,
-            y_beta,
-            xy_beta,
+            xy_alpha: xy_alphas[i],
+            x_beta: x_1 - x_gamma,
+            y_beta: y_1 - y_gamma,
+            xy_beta: x_1 * y_1 - xy_gamma,
+            x_gamma,
+            y_gamma,
+            xy_gamma,
         };
 
         composer.fixed_group_add(wnaf_round);
     }
 
-    // Add last gate, but do not activate it for ECC
-    // It is for use with the previous gate
-    let acc_x = composer.add_input(point_acc[num_bits].get_x());
-    let acc_y = composer.add_input(point_acc[num_bits].get_y());
-    let xy_alpha = composer.zero_var;
-    let last_accumulated_bit = composer.add_input(scalar_acc[num_bits]);
+    // Add last gate, which holds the last accumulators for the previous gate
+    // and constrains `2 * scalar = D + 4^126 - 1`
+    let (acc_x, acc_y, last_accumulated_bit) = accumulators[NUM_DIGITS];
+    let four_pow = BlsScalar::from(4u64).pow(&[NUM_DIGITS as u64, 0, 0, 0]);
 
     composer.big_add_gate(
         acc_x,
         acc_y,
-        xy_alpha,
+        jubjub_scalar,
         Some(last_accumulated_bit),
         BlsScalar::zero(),
         BlsScalar::zero(),
-        BlsScalar::zero(),
-        BlsScalar::zero(),
-        BlsScalar::zero(),
+        BlsScalar::from(2u64),
+        -BlsScalar::one(),
+        BlsScalar::one() - four_pow,
         BlsScalar::zero(),
     );
 
-    // Constrain the last element in the accumulator to be equal to the input jubjub scalar
-    composer.assert_equal(last_accumulated_bit, jubjub_scalar);
-
+    composer.pop_namespace();
     PointScalar {
         point: Point { x: acc_x, y: acc_y },
-        scalar: last_accumulated_bit,
+        scalar: jubjub_scalar,
+    }
+}
+
+/// Writes `scalar` with odd base 4 digits in `{-3, -1, 1, 3}`, from the lowest
+/// digit, as `2 * scalar - 4^126 + 1`.
+///
+/// The scalars which do not fit in the digits are truncated, and then fail the
+/// constraint on the accumulated scalar.
+fn odd_base4_digits(scalar: &BlsScalar) -> [i8; NUM_DIGITS] {
+    let bytes = scalar.to_bytes();
+
+    let mut digits = [0i8; NUM_DIGITS];
+    for (i, digit) in digits.iter_mut().enumerate() {
+        // The base 4 digit b is mapped to 2b - 3
+        let base4 = (bytes[i / 4] >> (2 * (i % 4))) & 3;
+        *digit = 2 * base4 as i8 - 3;
     }
+    digits
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::commitment_scheme::kzg10::PublicParameters;
     use crate::constraint_system::helper::*;
+    use crate::proof_system::{Prover, ProverKey, Verifier, VerifierKey};
     use dusk_jubjub::GENERATOR;
 
     #[test]
@@ -162,7 +256,7 @@ mod tests {
 
                 let point_scalar = scalar_mul(composer, secret_scalar, GENERATOR.into());
 
-                composer.assert_equal_public_point(point_scalar.into(), expected_point);
+                composer.assert_equal_public_point(point_scalar.into(), "expected", expected_point);
             },
             600,
         );
@@ -182,7 +276,7 @@ mod tests {
 
                 let point_scalar = scalar_mul(composer, secret_scalar, GENERATOR.into());
 
-                composer.assert_equal_public_point(point_scalar.into(), expected_point);
+                composer.assert_equal_public_point(point_scalar.into(), "expected", expected_point);
             },
             600,
         );
@@ -203,7 +297,7 @@ mod tests {
 
                 let point_scalar = scalar_mul(composer, secret_scalar, GENERATOR.into());
 
-                composer.assert_equal_public_point(point_scalar.into(), expected_point);
+                composer.assert_equal_public_point(point_scalar.into(), "expected", expected_point);
             },
             600,
         );
@@ -236,7 +330,7 @@ mod tests {
                 };
                 let new_point = point_a.add(composer, point_b);
 
-                composer.assert_equal_public_point(new_point, affine_expected_point);
+                composer.assert_equal_public_point(new_point, "expected", affine_expected_point);
             },
             600,
         );
@@ -275,14 +369,14 @@ mod tests {
 
                 // Depending on the context, one can check if the resulting aG and bH are as expected
                 //
-                composer.assert_equal_public_point(aG.into(), c_a);
-                composer.assert_equal_public_point(bH.into(), c_b);
+                composer.assert_equal_public_point(aG.into(), "c_a", c_a);
+                composer.assert_equal_public_point(bH.into(), "c_b", c_b);
 
                 // Curve addition
                 let commitment = Point::from(aG).add(composer, bH.into());
 
                 // Add final constraints to ensure that the commitment that we computed is equal to the public point
-                composer.assert_equal_public_point(commitment, expected_point);
+                composer.assert_equal_public_point(commitment, "expected", expected_point);
             },
             1024,
         );
@@ -324,11 +418,97 @@ mod tests {
 
                 composer.assert_equal_point(commitment_a, commitment_b);
 
-                composer.assert_equal_public_point(commitment_a, expected_lhs);
-                composer.assert_equal_public_point(commitment_b, expected_rhs);
+                composer.assert_equal_public_point(commitment_a, "expected_lhs", expected_lhs);
+                composer.assert_equal_public_point(commitment_b, "expected_rhs", expected_rhs);
             },
             2048,
         );
         assert!(res.is_ok());
     }
+
+    #[test]
+    fn test_scalar_mul_with_table() {
+        let mut rng = rand::thread_rng();
+        let table = GeneratorTable::new(GENERATOR.into());
+        let scalars = vec![
+            JubJubScalar::zero(),
+            JubJubScalar::one(),
+            JubJubScalar::from(2u64),
+            -JubJubScalar::one(),
+            JubJubScalar::random(&mut rng),
+            JubJubScalar::random(&mut rng),
+        ];
+        for scalar in scalars {
+            let mut composer = StandardComposer::new();
+            let bls_scalar = BlsScalar::from_bytes(&scalar.to_bytes()).unwrap();
+            let secret_scalar = composer.add_input(bls_scalar);
+
+            let size = composer.circuit_size();
+            let point_scalar = scalar_mul_with_table(&mut composer, secret_scalar, &table);
+            // One gate per digit, the last accumulators and the initial x and y
+            assert_eq!(composer.circuit_size() - size, NUM_DIGITS + 3);
+
+            let expected_point: JubJubAffine = (JubJubExtended::from(GENERATOR) * scalar).into();
+            assert_eq!(
+                composer.variables[point_scalar.point().x()],
+                expected_point.get_x()
+            );
+            assert_eq!(
+                composer.variables[point_scalar.point().y()],
+                expected_point.get_y()
+            );
+            assert!(composer.check_satisfied().is_ok());
+        }
+
+        // 4^126 does not fit in the digits
+        let mut composer = StandardComposer::new();
+        let too_large = (0..NUM_DIGITS).fold(BlsScalar::one(), |acc, _| acc * BlsScalar::from(4));
+        let secret_scalar = composer.add_input(too_large);
+        scalar_mul_with_table(&mut composer, secret_scalar, &table);
+        assert!(composer.check_satisfied().is_err());
+    }
+
+    #[test]
+    #[should_panic(expected = "prime order")]
+    fn test_generator_table_small_order() {
+        // Adding the point of order 2 to the generator gives a point of
+        // order 2r
+        let order_two = JubJubAffine::from_raw_unchecked(BlsScalar::zero(), -BlsScalar::one());
+        GeneratorTable::new(JubJubExtended::from(GENERATOR) + order_two);
+    }
+
+    #[test]
+    fn test_fixed_base_gate_keys() {
+        let public_parameters = PublicParameters::setup(2 * 512, &mut rand::thread_rng()).unwrap();
+        let (ck, _) = public_parameters.trim(512).unwrap();
+
+        // Only the circuits doing fixed base scalar multiplications hold the
+        // selectors of the gate
+        let mut prover = Prover::new(b"demo");
+        dummy_gadget(7, prover.mut_cs());
+        prover.preprocess(&ck).unwrap();
+        assert!(prover.prover_key.unwrap().custom.is_empty());
+
+        let gadget = |composer: &mut StandardComposer| {
+            let secret_scalar = composer.add_input(BlsScalar::from(100u64));
+            scalar_mul(composer, secret_scalar, GENERATOR.into());
+        };
+
+        let mut prover = Prover::new(b"demo");
+        gadget(prover.mut_cs());
+        prover.preprocess(&ck).unwrap();
+        let prover_key = prover.prover_key.unwrap();
+        assert_eq!(prover_key.custom.len(), 1);
+
+        let mut verifier = Verifier::new(b"demo");
+        gadget(verifier.mut_cs());
+        verifier.preprocess(&ck).unwrap();
+        let verifier_key = verifier.verifier_key.unwrap();
+
+        // The keys are deserialised without providing the built-in gate
+        let bytes = prover_key.to_bytes();
+        assert_eq!(ProverKey::from_bytes(&bytes).unwrap(), prover_key);
+        let bytes = verifier_key.to_bytes();
+        assert_eq!(VerifierKey::from_bytes(&bytes).unwrap(), verifier_key);
+    }
 }
@@ -8,48 +8,217 @@ use crate::constraint_system::ecc::{Point, PointScalar};
 use crate::constraint_system::{variable::Variable, StandardComposer};
 use dusk_bls12_381::BlsScalar;
 
+/// Number of windows of two bits the scalars are split into, which covers
+/// the 252 bits of a JubJub scalar.
+const NUM_WINDOWS: usize = 126;
+
 /// Computes a BlsScalar multiplication with the input scalar and a chosen generator
+///
+/// The scalar `k`, which must be lower than `2^252`, is written as
+/// `\sum_i d_i 4^i - c` with the digits `d_i` in `{-3, -1, 1, 3}` and the
+/// top one positive, where `c` is one if `k` is even. Each window of two
+/// bits then costs two doublings and a single addition of `P` or `3P`, with
+/// the sign of the digit applied to the `x` coordinate.
 pub fn variable_base_scalar_mul(
     composer: &mut StandardComposer,
     jubjub_var: Variable,
     point: Point,
 ) -> PointScalar {
-    // Turn scalar into bits
-    let raw_bls_scalar = *composer.variables.get(&jubjub_var).unwrap();
-    let scalar_bits_var = scalar_decomposition(composer, jubjub_var, raw_bls_scalar);
+    composer.push_namespace("variable_base_scalar_mul");
+
+    // Precompute 3P, and the differences selecting between P and 3P
+    let point_3 = point.fast_add(composer, point).fast_add(composer, point);
+    let delta_x = composer.add(
+        (BlsScalar::one(), point_3.x),
+        (-BlsScalar::one(), point.x),
+        BlsScalar::zero(),
+        BlsScalar::zero(),
+    );
+    let delta_y = composer.add(
+        (BlsScalar::one(), point_3.y),
+        (-BlsScalar::one(), point.y),
+        BlsScalar::zero(),
+        BlsScalar::zero(),
+    );
+
+    // Allocate the bits of the signed digits of the scalar, from the highest
+    // digit, which are computed by the witness generator
+    let digits: Vec<(Variable, Variable)> = (0..NUM_WINDOWS)
+        .map(|_| {
+            (
+                composer.add_input(BlsScalar::zero()),
+                composer.add_input(BlsScalar::zero()),
+            )
+        })
+        .collect();
+    let even = composer.add_input(BlsScalar::zero());
+
+    let digit_vars = digits.clone();
+    composer.add_witness_generator(move |composer| {
+        // Turn scalar into signed digits
+        let raw_bls_scalar = composer.variables[&jubjub_var];
+        let (digits, even_bit) = signed_digits(&raw_bls_scalar);
 
-    let identity = Point::identity(composer);
-    let mut result = identity;
+        for ((sign, magnitude), (sign_bit, magnitude_bit)) in
+            digit_vars.iter().zip(digits.iter().rev())
+        {
+            composer
+                .variables
+                .insert(*sign, BlsScalar::from(*sign_bit as u64));
+            composer
+                .variables
+                .insert(*magnitude, BlsScalar::from(*magnitude_bit as u64));
+        }
+        composer
+            .variables
+            .insert(even, BlsScalar::from(even_bit as u64));
+    });
 
-    for bit in scalar_bits_var.into_iter().rev() {
+    // The top digit is 1 or 3, which is the first multiple of the point, and
+    // its sign is left unused
+    let (_, magnitude) = digits[0];
+    composer.boolean_gate(magnitude);
+    let mut accumulator = composer.add(
+        (BlsScalar::from(2), magnitude),
+        (BlsScalar::zero(), composer.zero_var),
+        BlsScalar::one(),
+        BlsScalar::zero(),
+    );
+    let mut result = select_multiple(composer, magnitude, point, delta_x, delta_y);
+
+    for &(sign, magnitude) in digits[1..].iter() {
+        composer.boolean_gate(sign);
+        composer.boolean_gate(magnitude);
+        accumulator = accumulate_digit(composer, accumulator, sign, magnitude);
+
+        let multiple = select_multiple(composer, magnitude, point, delta_x, delta_y);
+        // Negating the multiple only changes the sign of its x coordinate
+        let x = composer.big_mul(
+            BlsScalar::from(2),
+            sign,
+            multiple.x,
+            Some((-BlsScalar::one(), multiple.x)),
+            BlsScalar::zero(),
+            BlsScalar::zero(),
+        );
+
+        result = result.fast_add(composer, result);
         result = result.fast_add(composer, result);
-        let point_to_add = conditional_select_identity(composer, bit, point);
-        result = result.fast_add(composer, point_to_add);
+        result = result.fast_add(composer, Point { x, y: multiple.y });
     }
 
+    // Subtract the point if the scalar is even
+    composer.boolean_gate(even);
+    composer.add_gate(
+        accumulator,
+        even,
+        jubjub_var,
+        BlsScalar::one(),
+        -BlsScalar::one(),
+        -BlsScalar::one(),
+        BlsScalar::zero(),
+        BlsScalar::zero(),
+    );
+    let x = composer.mul(
+        -BlsScalar::one(),
+        even,
+        point.x,
+        BlsScalar::zero(),
+        BlsScalar::zero(),
+    );
+    let y = conditional_select_one(composer, even, point.y);
+    let result = result.fast_add(composer, Point { x, y });
+
+    composer.pop_namespace();
     PointScalar {
         point: result,
         scalar: jubjub_var,
     }
 }
 
-/// If bit == 0, then return zero else return value
-/// This is the polynomial f(x) = x * a
-/// Where x is the bit
-fn conditional_select_zero(
+/// If magnitude == 0, then return point else return 3 * point, given the
+/// differences of the coordinates of 3 * point and point
+fn select_multiple(
     composer: &mut StandardComposer,
-    bit: Variable,
-    value: Variable,
-) -> Variable {
-    // returns bit * value
-    composer.mul(
+    magnitude: Variable,
+    point: Point,
+    delta_x: Variable,
+    delta_y: Variable,
+) -> Point {
+    let x = composer.big_mul(
         BlsScalar::one(),
-        bit,
-        value,
+        magnitude,
+        delta_x,
+        Some((BlsScalar::one(), point.x)),
+        BlsScalar::zero(),
+        BlsScalar::zero(),
+    );
+    let y = composer.big_mul(
+        BlsScalar::one(),
+        magnitude,
+        delta_y,
+        Some((BlsScalar::one(), point.y)),
         BlsScalar::zero(),
         BlsScalar::zero(),
-    )
+    );
+
+    Point { x, y }
 }
+
+/// Returns `4 * accumulator + d` for the digit `d = (2 * sign - 1) * (1 + 2 *
+/// magnitude)`, which is the polynomial
+/// `4 * accumulator + 4 * sign * magnitude + 2 * sign - 2 * magnitude - 1`
+fn accumulate_digit(
+    composer: &mut StandardComposer,
+    accumulator: Variable,
+    sign: Variable,
+    magnitude: Variable,
+) -> Variable {
+    let four = BlsScalar::from(4);
+    let two = BlsScalar::from(2);
+
+    let output = composer.add_input(BlsScalar::zero());
+    composer.add_witness_generator(move |composer| {
+        let sign_scalar = composer.variables[&sign];
+        let magnitude_scalar = composer.variables[&magnitude];
+        let accumulator_scalar = composer.variables[&accumulator];
+        let output_scalar =
+            four * accumulator_scalar + four * sign_scalar * magnitude_scalar + two * sign_scalar
+                - two * magnitude_scalar
+                - BlsScalar::one();
+        composer.variables.insert(output, output_scalar);
+    });
+
+    composer.w_l.push(sign);
+    composer.w_r.push(magnitude);
+    composer.w_o.push(output);
+    composer.w_4.push(accumulator);
+
+    composer.q_m.push(four);
+    composer.q_l.push(two);
+    composer.q_r.push(-two);
+    composer.q_o.push(-BlsScalar::one());
+    composer.q_c.push(-BlsScalar::one());
+    composer.q_4.push(four);
+    composer.q_arith.push(BlsScalar::one());
+
+    composer.q_range.push(BlsScalar::zero());
+    composer.q_logic.push(BlsScalar::zero());
+    composer.q_variable_group_add.push(BlsScalar::zero());
+    composer.q_lookup.push(BlsScalar::zero());
+    composer.q_sbox.push(BlsScalar::zero());
+
+    composer.public_inputs.push(BlsScalar::zero());
+
+    composer
+        .perm
+        .add_variables_to_map(sign, magnitude, output, accumulator, composer.n);
+
+    composer.n += 1;
+
+    output
+}
+
 /// If bit == 0, then return 1 else return value
 /// This is the polynomial f(x) = 1 - x + xa
 /// Where x is the bit
@@ -58,11 +227,14 @@ fn conditional_select_one(
     bit: Variable,
     value: Variable,
 ) -> Variable {
-    let value_scalar = composer.variables.get(&value).unwrap();
-    let bit_scalar = composer.variables.get(&bit).unwrap();
+    let f_x = composer.add_input(BlsScalar::zero());
+    composer.add_witness_generator(move |composer| {
+        let value_scalar = composer.variables[&value];
+        let bit_scalar = composer.variables[&bit];
 
-    let f_x_scalar = BlsScalar::one() - bit_scalar + (bit_scalar * value_scalar);
-    let f_x = composer.add_input(f_x_scalar);
+        let f_x_scalar = BlsScalar::one() - bit_scalar + (bit_scalar * value_scalar);
+        composer.variables.insert(f_x, f_x_scalar);
+    });
 
     composer.poly_gate(
         bit,
@@ -79,68 +251,30 @@ fn conditional_select_one(
     f_x
 }
 
-// If bit == 0 choose identity, if bit == 1 choose point_b
-fn conditional_select_identity(
-    composer: &mut StandardComposer,
-    bit: Variable,
-    point_b: Point,
-) -> Point {
-    let x = conditional_select_zero(composer, bit, *point_b.x());
-    let y = conditional_select_one(composer, bit, *point_b.y());
+/// Splits `scalar` into the `(sign, magnitude)` bits of the digits
+/// `(2 * sign - 1) * (1 + 2 * magnitude)` of `scalar + even` in base 4, from
+/// the lowest one, along with `even`, which is one if `scalar` is even.
+///
+/// The odd number `n = scalar + even` is written as `2e - (4^126 - 1)`, so
+/// each digit `e_i` of `e` in base 4 gives the digit `2e_i - 3`.
+fn signed_digits(scalar: &BlsScalar) -> ([(u8, u8); NUM_WINDOWS], u8) {
+    let even = 1 - (scalar.to_bytes()[0] & 1);
+    let offset = BlsScalar::pow_of_2(2 * NUM_WINDOWS as u64) - BlsScalar::one();
+    let half = BlsScalar::from(2).invert().unwrap();
+    let e = (scalar + BlsScalar::from(even as u64) + offset) * half;
 
-    Point { x, y }
-}
-
-fn scalar_decomposition(
-    composer: &mut StandardComposer,
-    witness_var: Variable,
-    witness_scalar: BlsScalar,
-) -> Vec<Variable> {
-    // Decompose the bits
-    let scalar_bits = scalar_to_bits(&witness_scalar);
-
-    // Add all the bits into the composer
-    let scalar_bits_var: Vec<Variable> = scalar_bits
-        .iter()
-        .map(|bit| composer.add_input(BlsScalar::from(*bit as u64)))
-        .collect();
-
-    // Take the first 252 bits
-    let scalar_bits_var = scalar_bits_var[0..252].to_vec();
-
-    // Now ensure that the bits correctly accumulate to the witness given
-    let mut accumulator_var = composer.zero_var;
-    let mut accumulator_scalar = BlsScalar::zero();
-
-    for (power, bit) in scalar_bits_var.iter().enumerate() {
-        composer.boolean_gate(*bit);
-
-        let two_pow = BlsScalar::pow_of_2(power as u64);
-
-        let q_l_a = (two_pow, *bit);
-        let q_r_b = (BlsScalar::one(), accumulator_var);
-        let q_c = BlsScalar::zero();
-        let pi = BlsScalar::zero();
-
-        accumulator_var = composer.add(q_l_a, q_r_b, q_c, pi);
-
-        accumulator_scalar += two_pow * BlsScalar::from(scalar_bits[power] as u64);
+    let bytes = e.to_bytes();
+    let mut digits = [(0, 0); NUM_WINDOWS];
+    for (i, digit) in digits.iter_mut().enumerate() {
+        let e_i = (bytes[i / 4] >> (2 * (i % 4))) & 3;
+        let sign = e_i >> 1;
+        // 2e_i - 3 is -3 or 3 when both bits of e_i are equal
+        let magnitude = (sign == (e_i & 1)) as u8;
+        *digit = (sign, magnitude);
     }
-    composer.assert_equal(accumulator_var, witness_var);
-
-    scalar_bits_var
+    (digits, even)
 }
 
-fn scalar_to_bits(scalar: &BlsScalar) -> [u8; 256] {
-    let mut res = [0u8; 256];
-    let bytes = scalar.to_bytes();
-    for (byte, bits) in bytes.iter().zip(res.chunks_mut(8)) {
-        bits.iter_mut()
-            .enumerate()
-            .for_each(|(i, bit)| *bit = (byte >> i) & 1)
-    }
-    res
-}
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -168,10 +302,53 @@ mod tests {
 
                 let point_scalar = variable_base_scalar_mul(composer, secret_scalar, point);
 
-                composer.assert_equal_public_point(point_scalar.into(), expected_point);
+                composer.assert_equal_public_point(point_scalar.into(), "expected", expected_point);
             },
             4096,
         );
         assert!(res.is_ok());
     }
+
+    #[test]
+    fn test_var_base_scalar_mul_digits() {
+        let mut rng = rand::thread_rng();
+        let scalars = vec![
+            JubJubScalar::zero(),
+            JubJubScalar::one(),
+            JubJubScalar::from(2u64),
+            JubJubScalar::from(6u64),
+            -JubJubScalar::one(),
+            JubJubScalar::random(&mut rng),
+            JubJubScalar::random(&mut rng),
+        ];
+        for scalar in scalars {
+            let mut composer = StandardComposer::new();
+            let bls_scalar = BlsScalar::from_bytes(&scalar.to_bytes()).unwrap();
+            let secret_scalar = composer.add_input(bls_scalar);
+            let point = Point::from_private_affine(&mut composer, GENERATOR);
+
+            let size = composer.circuit_size();
+            let point_scalar = variable_base_scalar_mul(&mut composer, secret_scalar, point);
+            // A bit by bit double-and-add takes 8 gates per bit
+            assert!(composer.circuit_size() - size < 6 * 256);
+
+            let expected_point: JubJubAffine = (JubJubExtended::from(GENERATOR) * scalar).into();
+            assert_eq!(
+                composer.variables[point_scalar.point().x()],
+                expected_point.get_x()
+            );
+            assert_eq!(
+                composer.variables[point_scalar.point().y()],
+                expected_point.get_y()
+            );
+            assert!(composer.check_satisfied().is_ok());
+        }
+
+        // 2^252 does not fit in the digits
+        let mut composer = StandardComposer::new();
+        let secret_scalar = composer.add_input(BlsScalar::pow_of_2(252));
+        let point = Point::from_private_affine(&mut composer, GENERATOR);
+        variable_base_scalar_mul(&mut composer, secret_scalar, point);
+        assert!(composer.check_satisfied().is_err());
+    }
 }
@@ -47,14 +47,14 @@ pub(crate) fn gadget_tester(
         gadget(&mut prover.mut_cs());
 
         // Commit Key
-        let (ck, _) = public_parameters.trim(2 * prover.cs.circuit_size().next_power_of_two())?;
+        let (ck, _) = public_parameters.trim(2 * prover.cs.domain_size())?;
 
         // Preprocess circuit
         prover.preprocess(&ck)?;
 
         // Once the prove method is called, the public inputs are cleared
         // So pre-fetch these before calling Prove
-        let public_inputs = prover.cs.public_inputs.clone();
+        let public_inputs = prover.cs.sparse_public_inputs();
 
         // Compute Proof
         (prover.prove(&ck)?, public_inputs)
@@ -71,11 +71,11 @@ pub(crate) fn gadget_tester(
     gadget(&mut verifier.mut_cs());
 
     // Compute Commit and Verifier Key
-    let (ck, vk) = public_parameters.trim(verifier.cs.circuit_size().next_power_of_two())?;
+    let (ck, vk) = public_parameters.trim(verifier.cs.domain_size())?;
 
     // Preprocess circuit
     verifier.preprocess(&ck)?;
 
     // Verify proof
-    verifier.verify(&proof, &vk, &public_inputs)
+    verifier.verify_sparse(&proof, &vk, &public_inputs)
 }
@@ -29,27 +29,14 @@ impl StandardComposer {
         num_bits: usize,
         is_xor_gate: bool,
     ) -> Variable {
+        self.push_namespace("logic_gate");
+
         // Since we work on base4, we need to guarantee that we have an even
         // number of bits representing the greatest input.
         assert_eq!(num_bits & 1, 0);
         // We will have exactly `num_bits / 2` quads (quaternary digits) representing
         // both numbers.
         let num_quads = num_bits >> 1;
-        // Allocate accumulators for gate construction.
-        let mut left_accumulator = BlsScalar::zero();
-        let mut right_accumulator = BlsScalar::zero();
-        let mut out_accumulator = BlsScalar::zero();
-        let mut left_quad: u8;
-        let mut right_quad: u8;
-        // Get vars as bits and reverse them to get the Little Endian repr.
-        let a_bit_iter = BitIterator8::new(self.variables[&a].to_bytes());
-        let a_bits: Vec<_> = a_bit_iter.skip(256 - num_bits).collect();
-        let b_bit_iter = BitIterator8::new(self.variables[&b].to_bytes());
-        let b_bits: Vec<_> = b_bit_iter.skip(256 - num_bits).collect();
-        // XXX Doc this
-        assert!(a_bits.len() >= num_bits);
-        assert!(b_bits.len() >= num_bits);
-
         // If we take a look to the program memory structure of the ref. impl.
         // * +-----+-----+-----+-----+
         // * |  A  |  B  |  C  |  D  |
@@ -81,101 +68,15 @@ impl StandardComposer {
         // the first step above was done correctly to obtain the right format the the first row.
         // This means that we will need to pad the end of the memory program once we've built it.
         // As we can see in the last row structure: `| an  | bn  | --- | cn  |`.
-        for i in 0..num_quads {
-            // On each round, we will commit every accumulator step. To do so,
-            // we first need to get the ith quads of `a` and `b` and then compute
-            // `out_quad`(logical OP result) and `prod_quad`(intermediate prod result).
-
-            // Here we compute each quad by taking the most significant bit
-            // multiplying it by two and adding to it the less significant
-            // bit to form the quad with a ternary value encapsulated in an `u8`
-            // in Big Endian form.
-            left_quad = {
-                let idx = i << 1;
-                ((a_bits[idx] as u8) << 1) + (a_bits[idx + 1] as u8)
-            };
-            right_quad = {
-                let idx = i << 1;
-                ((b_bits[idx] as u8) << 1) + (b_bits[idx + 1] as u8)
-            };
-            let left_quad_fr = BlsScalar::from(left_quad as u64);
-            let right_quad_fr = BlsScalar::from(right_quad as u64);
-            // The `out_quad` is the result of the bitwise ops `&` or `^` between
-            // the left and right quads. The op is decided with a boolean flag set
-            // as input of the function.
-            let out_quad_fr = match is_xor_gate {
-                true => BlsScalar::from((left_quad ^ right_quad) as u64),
-                false => BlsScalar::from((left_quad & right_quad) as u64),
-            };
-            // We also need to allocate a helper item which is the result
-            // of the product between the left and right quads.
-            // This param is identified as `w` in the program memory and
-            // is needed to prevent the degree of our quotient polynomial from blowing up
-            let prod_quad_fr = BlsScalar::from((left_quad * right_quad) as u64);
-
-            // Now that we've computed this round results, we need to apply the
-            // logic transition constraint that will check the following:
-            // a      - 4 . a  ϵ [0, 1, 2, 3]
-            //   i + 1        i
-            //
-            //
-            //
-            //
-            //  b      - 4 . b  ϵ [0, 1, 2, 3]
-            //   i + 1        i
-            //
-            //
-            //
-            //
-            //                    /                 \          /                 \
-            //  c      - 4 . c  = | a      - 4 . a  | (& OR ^) | b      - 4 . b  |
-            //   i + 1        i   \  i + 1        i /          \  i + 1        i /
-            //
-            let prev_left_accum = left_accumulator;
-            let prev_right_accum = right_accumulator;
-            let prev_out_accum = out_accumulator;
-            // We also need to add the computed quad fr_s to the circuit representing a logic gate.
-            // To do so, we just mul by 4 the previous accomulated result and we add to it
-            // the new computed quad.
-            // With this technique we're basically accumulating the quads and adding them to get back to the
-            // starting value, at the i-th iteration.
-            //          i
-            //         ===
-            //         \                     j
-            //  x   =  /    q            . 4
-            //   i     ===   (bits/2 - j)
-            //        j = 0
-            //
-            left_accumulator *= BlsScalar::from(4u64);
-            left_accumulator += left_quad_fr;
-            right_accumulator *= BlsScalar::from(4u64);
-            right_accumulator += right_quad_fr;
-            out_accumulator *= BlsScalar::from(4u64);
-            out_accumulator += out_quad_fr;
-            // Apply logic transition constraints.
-            assert!(
-                left_accumulator - (prev_left_accum * BlsScalar::from(4u64))
-                    < BlsScalar::from(4u64)
-            );
-            assert!(
-                right_accumulator - (prev_right_accum * BlsScalar::from(4u64))
-                    < BlsScalar::from(4u64)
-            );
-            assert!(
-                out_accumulator - (prev_out_accum * BlsScalar::from(4u64)) < BlsScalar::from(4u64)
-            );
-
-            // Get variables pointing to the previous accumulated values.
-            let var_a = self.add_input(left_accumulator);
-            let var_b = self.add_input(right_accumulator);
-            // On the last row of the program memory, we need to pad the
-            // output wire with a zero since we started to include it's
-            // accumulators one gate before the other wire ones.
-            let var_c = match i == num_quads {
-                true => self.zero_var,
-                false => self.add_input(prod_quad_fr),
-            };
-            let var_4 = self.add_input(out_accumulator);
+        let mut rows = Vec::with_capacity(num_quads);
+        for _ in 0..num_quads {
+            // The accumulators of each row are computed by the witness
+            // generator below.
+            let var_a = self.add_input(BlsScalar::zero());
+            let var_b = self.add_input(BlsScalar::zero());
+            let var_c = self.add_input(BlsScalar::zero());
+            let var_4 = self.add_input(BlsScalar::zero());
+            rows.push([var_a, var_b, var_c, var_4]);
             // Add the variables to the variable map linking them to it's
             // corresponding gate index.
             //
@@ -201,6 +102,116 @@ impl StandardComposer {
             self.n += 1;
         }
 
+        let generated_rows = rows.clone();
+        self.add_witness_generator(move |composer| {
+            // Allocate accumulators for gate construction.
+            let mut left_accumulator = BlsScalar::zero();
+            let mut right_accumulator = BlsScalar::zero();
+            let mut out_accumulator = BlsScalar::zero();
+            let mut left_quad: u8;
+            let mut right_quad: u8;
+            // Get vars as bits and reverse them to get the Little Endian repr.
+            let a_bit_iter = BitIterator8::new(composer.variables[&a].to_bytes());
+            let a_bits: Vec<_> = a_bit_iter.skip(256 - num_bits).collect();
+            let b_bit_iter = BitIterator8::new(composer.variables[&b].to_bytes());
+            let b_bits: Vec<_> = b_bit_iter.skip(256 - num_bits).collect();
+            // XXX Doc this
+            assert!(a_bits.len() >= num_bits);
+            assert!(b_bits.len() >= num_bits);
+
+            for (i, [var_a, var_b, var_c, var_4]) in generated_rows.iter().enumerate() {
+                // On each round, we will commit every accumulator step. To do so,
+                // we first need to get the ith quads of `a` and `b` and then compute
+                // `out_quad`(logical OP result) and `prod_quad`(intermediate prod result).
+
+                // Here we compute each quad by taking the most significant bit
+                // multiplying it by two and adding to it the less significant
+                // bit to form the quad with a ternary value encapsulated in an `u8`
+                // in Big Endian form.
+                left_quad = {
+                    let idx = i << 1;
+                    ((a_bits[idx] as u8) << 1) + (a_bits[idx + 1] as u8)
+                };
+                right_quad = {
+                    let idx = i << 1;
+                    ((b_bits[idx] as u8) << 1) + (b_bits[idx + 1] as u8)
+                };
+                let left_quad_fr = BlsScalar::from(left_quad as u64);
+                let right_quad_fr = BlsScalar::from(right_quad as u64);
+                // The `out_quad` is the result of the bitwise ops `&` or `^` between
+                // the left and right quads. The op is decided with a boolean flag set
+                // as input of the function.
+                let out_quad_fr = match is_xor_gate {
+                    true => BlsScalar::from((left_quad ^ right_quad) as u64),
+                    false => BlsScalar::from((left_quad & right_quad) as u64),
+                };
+                // We also need to allocate a helper item which is the result
+                // of the product between the left and right quads.
+                // This param is identified as `w` in the program memory and
+                // is needed to prevent the degree of our quotient polynomial from blowing up
+                let prod_quad_fr = BlsScalar::from((left_quad * right_quad) as u64);
+
+                // Now that we've computed this round results, we need to apply the
+                // logic transition constraint that will check the following:
+                // a      - 4 . a  ϵ [0, 1, 2, 3]
+                //   i + 1        i
+                //
+                //
+                //
+                //
+                //  b      - 4 . b  ϵ [0, 1, 2, 3]
+                //   i + 1        i
+                //
+                //
+                //
+                //
+                //                    /                 \          /                 \
+                //  c      - 4 . c  = | a      - 4 . a  | (& OR ^) | b      - 4 . b  |
+                //   i + 1        i   \  i + 1        i /          \  i + 1        i /
+                //
+                let prev_left_accum = left_accumulator;
+                let prev_right_accum = right_accumulator;
+                let prev_out_accum = out_accumulator;
+                // We also need to add the computed quad fr_s to the circuit representing a logic gate.
+                // To do so, we just mul by 4 the previous accomulated result and we add to it
+                // the new computed quad.
+                // With this technique we're basically accumulating the quads and adding them to get back to the
+                // starting value, at the i-th iteration.
+                //          i
+                //         ===
+                //         \                     j
+                //  x   =  /    q            . 4
+                //   i     ===   (bits/2 - j)
+                //        j = 0
+                //
+                left_accumulator *= BlsScalar::from(4u64);
+                left_accumulator += left_quad_fr;
+                right_accumulator *= BlsScalar::from(4u64);
+                right_accumulator += right_quad_fr;
+                out_accumulator *= BlsScalar::from(4u64);
+                out_accumulator += out_quad_fr;
+                // Apply logic transition constraints.
+                assert!(
+                    left_accumulator - (prev_left_accum * BlsScalar::from(4u64))
+                        < BlsScalar::from(4u64)
+                );
+                assert!(
+                    right_accumulator - (prev_right_accum * BlsScalar::from(4u64))
+                        < BlsScalar::from(4u64)
+                );
+                assert!(
+                    out_accumulator - (prev_out_accum * BlsScalar::from(4u64))
+                        < BlsScalar::from(4u64)
+                );
+
+                // Set the variables pointing to the accumulated values.
+                composer.variables.insert(*var_a, left_accumulator);
+                composer.variables.insert(*var_b, right_accumulator);
+                composer.variables.insert(*var_c, prod_quad_fr);
+                composer.variables.insert(*var_4, out_accumulator);
+            }
+        });
+
         // We have one missing value for the last row of the program memory which
         // is `w_o` since the rest of wires are pointing one gate ahead.
         // To fix this, we simply pad with a 0 so the last row of the program memory
@@ -222,8 +233,9 @@ impl StandardComposer {
             self.q_o.push(BlsScalar::zero());
             self.q_4.push(BlsScalar::zero());
             self.q_range.push(BlsScalar::zero());
-            self.q_fixed_group_add.push(BlsScalar::zero());
             self.q_variable_group_add.push(BlsScalar::zero());
+            self.q_lookup.push(BlsScalar::zero());
+            self.q_sbox.push(BlsScalar::zero());
             match is_xor_gate {
                 true => {
                     self.q_c.push(-BlsScalar::one());
@@ -243,8 +255,9 @@ impl StandardComposer {
         self.q_o.push(BlsScalar::zero());
         self.q_4.push(BlsScalar::zero());
         self.q_range.push(BlsScalar::zero());
-        self.q_fixed_group_add.push(BlsScalar::zero());
         self.q_variable_group_add.push(BlsScalar::zero());
+        self.q_lookup.push(BlsScalar::zero());
+        self.q_sbox.push(BlsScalar::zero());
 
         self.q_c.push(BlsScalar::zero());
         self.q_logic.push(BlsScalar::zero());
@@ -281,6 +294,7 @@ impl StandardComposer {
                 & (BlsScalar::from(2u64).pow(&[(num_bits) as u64, 0, 0, 0]) - BlsScalar::one()),
             self.variables[&self.w_r[self.n - 1]]
         );
+        self.pop_namespace();
 
         // Once the inputs are checked against the accumulated additions,
         // we can safely return the resulting variable of the gate computation
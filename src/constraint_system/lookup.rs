@@ -0,0 +1,283 @@
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this
+// file, You can obtain one at http://mozilla.org/MPL/2.0/.
+//
+// Copyright (c) DUSK NETWORK. All rights reserved.
+
+//! Lookup tables and the lookup gate.
+//!
+//! A lookup gate forces the `(w_l, w_r, w_o)` wires of a gate to be one of the
+//! rows of a fixed table registered in the `StandardComposer`. This allows to
+//! check relations like XOR, AND, small ranges or S-boxes with a single gate
+//! instead of long accumulator chains.
+
+use crate::constraint_system::{StandardComposer, Variable};
+use dusk_bls12_381::BlsScalar;
+
+/// A fixed table of `(a, b, c)` rows that the wires of a lookup gate can be
+/// checked against.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct LookupTable(pub(crate) Vec<[BlsScalar; 3]>);
+
+impl LookupTable {
+    /// Creates a lookup table from its rows.
+    pub fn new(rows: Vec<[BlsScalar; 3]>) -> Self {
+        LookupTable(rows)
+    }
+
+    /// Returns the number of rows of the table.
+    pub fn len(&self) -> usize {
+        self.0.len()
+    }
+
+    /// Returns `true` if the table has no rows.
+    pub fn is_empty(&self) -> bool {
+        self.0.is_empty()
+    }
+
+    /// Returns the rows of the table.
+    pub fn rows(&self) -> &[[BlsScalar; 3]] {
+        &self.0
+    }
+
+    /// Creates a table holding `f(a, b)` for every pair of `num_bits` values,
+    /// as `(a, b, f(a, b))` rows.
+    pub fn from_binary_fn(num_bits: usize, f: impl Fn(u64, u64) -> u64) -> Self {
+        let size = 1u64 << num_bits;
+        let rows = (0..size)
+            .flat_map(|a| (0..size).map(move |b| (a, b)))
+            .map(|(a, b)| {
+                [
+                    BlsScalar::from(a),
+                    BlsScalar::from(b),
+                    BlsScalar::from(f(a, b)),
+                ]
+            })
+            .collect();
+        LookupTable(rows)
+    }
+
+    /// Creates a table holding `f(a)` for every `num_bits` value, as
+    /// `(a, f(a), 0)` rows.
+    /// This can be used to express S-boxes.
+    pub fn from_unary_fn(num_bits: usize, f: impl Fn(u64) -> u64) -> Self {
+        let rows = (0..1u64 << num_bits)
+            .map(|a| [BlsScalar::from(a), BlsScalar::from(f(a)), BlsScalar::zero()])
+            .collect();
+        LookupTable(rows)
+    }
+
+    /// Creates a table with the `(a, b, a ^ b)` rows for every pair of
+    /// `num_bits` values.
+    pub fn xor_table(num_bits: usize) -> Self {
+        LookupTable::from_binary_fn(num_bits, |a, b| a ^ b)
+    }
+
+    /// Creates a table with the `(a, b, a & b)` rows for every pair of
+    /// `num_bits` values.
+    pub fn and_table(num_bits: usize) -> Self {
+        LookupTable::from_binary_fn(num_bits, |a, b| a & b)
+    }
+
+    /// Creates a table with the `(a, 0, 0)` rows for every `num_bits` value.
+    pub fn range_table(num_bits: usize) -> Self {
+        LookupTable::from_unary_fn(num_bits, |_| 0)
+    }
+}
+
+impl StandardComposer {
+    /// Registers a lookup table in the circuit and returns the id that the
+    /// lookup gates use to refer to it.
+    ///
+    /// Tables are part of the circuit description, so they must be registered
+    /// in the same order for both the `Prover` and the `Verifier`.
+    pub fn add_lookup_table(&mut self, table: LookupTable) -> usize {
+        self.lookup_tables.push(table);
+        self.lookup_tables.len()
+    }
+
+    /// Adds a lookup gate which forces `(a, b, c)` to be one of the rows of the
+    /// table registered with `table_id`.
+    ///
+    /// # Panics
+    ///
+    /// If there is no table registered with `table_id`.
+    pub fn lookup_gate(&mut self, table_id: usize, a: Variable, b: Variable, c: Variable) {
+        assert!(table_id > 0 && table_id <= self.lookup_tables.len());
+
+        self.w_l.push(a);
+        self.w_r.push(b);
+        self.w_o.push(c);
+        self.w_4.push(self.zero_var);
+
+        // The table id is held in `q_c` so that a gate can only match the rows
+        // of the table it refers to
+        self.q_m.push(BlsScalar::zero());
+        self.q_l.push(BlsScalar::zero());
+        self.q_r.push(BlsScalar::zero());
+        self.q_o.push(BlsScalar::zero());
+        self.q_c.push(BlsScalar::from(table_id as u64));
+        self.q_4.push(BlsScalar::zero());
+        self.q_arith.push(BlsScalar::zero());
+        self.q_range.push(BlsScalar::zero());
+        self.q_logic.push(BlsScalar::zero());
+        self.q_variable_group_add.push(BlsScalar::zero());
+        self.q_lookup.push(BlsScalar::one());
+        self.q_sbox.push(BlsScalar::zero());
+
+        self.public_inputs.push(BlsScalar::zero());
+
+        self.perm
+            .add_variables_to_map(a, b, c, self.zero_var, self.n);
+        self.n += 1;
+    }
+
+    /// Looks up the row of the table registered with `table_id` whose first two
+    /// columns hold the values of `a` and `b`, and returns a `Variable` holding
+    /// the third column, constrained by a lookup gate.
+    ///
+    /// If no row matches, the result is set to zero and the proof will not
+    /// verify.
+    ///
+    /// # Panics
+    ///
+    /// If there is no table registered with `table_id`.
+    pub fn lookup(&mut self, table_id: usize, a: Variable, b: Variable) -> Variable {
+        assert!(table_id > 0 && table_id <= self.lookup_tables.len());
+
+        let c = self.add_input(BlsScalar::zero());
+        self.add_witness_generator(move |composer| {
+            let a_value = composer.variables[&a];
+            let b_value = composer.variables[&b];
+            let c_value = composer.lookup_tables[table_id - 1]
+                .rows()
+                .iter()
+                .find(|row| row[0] == a_value && row[1] == b_value)
+                .map(|row| row[2])
+                .unwrap_or_else(BlsScalar::zero);
+            composer.variables.insert(c, c_value);
+        });
+        self.lookup_gate(table_id, a, b, c);
+        c
+    }
+
+    /// Returns the rows of every registered table as `[a, b, c, table_id]`,
+    /// preceded by an all-zero row which the non-lookup gates are matched with.
+    pub(crate) fn lookup_table_rows(&self) -> Vec<[BlsScalar; 4]> {
+        let zero = BlsScalar::zero();
+        let mut rows = vec![[zero, zero, zero, zero]];
+        for (index, table) in self.lookup_tables.iter().enumerate() {
+            let table_id = BlsScalar::from((index + 1) as u64);
+            rows.extend(
+                table
+                    .rows()
+                    .iter()
+                    .map(|row| [row[0], row[1], row[2], table_id]),
+            );
+        }
+        rows
+    }
+
+    /// Returns the number of rows of all the registered tables, including the
+    /// all-zero row.
+    /// The circuit is padded to hold at least this amount of gates.
+    pub(crate) fn lookup_table_size(&self) -> usize {
+        1 + self
+            .lookup_tables
+            .iter()
+            .map(|table| table.len())
+            .sum::<usize>()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::super::helper::*;
+    use super::*;
+
+    #[test]
+    fn test_xor_and_lookup() {
+        let res = gadget_tester(
+            |composer| {
+                let xor_table = composer.add_lookup_table(LookupTable::xor_table(4));
+                let and_table = composer.add_lookup_table(LookupTable::and_table(4));
+
+                let a = composer.add_input(BlsScalar::from(0b1010));
+                let b = composer.add_input(BlsScalar::from(0b0110));
+
+                let xor = composer.lookup(xor_table, a, b);
+                let and = composer.lookup(and_table, a, b);
+                composer.constrain_to_constant(xor, BlsScalar::from(0b1100), BlsScalar::zero());
+                composer.constrain_to_constant(and, BlsScalar::from(0b0010), BlsScalar::zero());
+            },
+            1024,
+        );
+        assert!(res.is_ok());
+    }
+
+    #[test]
+    fn test_range_lookup() {
+        // Should pass as the value fits in 8 bits
+        let res = gadget_tester(
+            |composer| {
+                let range_table = composer.add_lookup_table(LookupTable::range_table(8));
+                let a = composer.add_input(BlsScalar::from(255));
+                composer.lookup_gate(range_table, a, composer.zero_var, composer.zero_var);
+            },
+            1024,
+        );
+        assert!(res.is_ok());
+
+        // Should fail as the value does not fit in 8 bits
+        let res = gadget_tester(
+            |composer| {
+                let range_table = composer.add_lookup_table(LookupTable::range_table(8));
+                let a = composer.add_input(BlsScalar::from(256));
+                composer.lookup_gate(range_table, a, composer.zero_var, composer.zero_var);
+            },
+            1024,
+        );
+        assert!(res.is_err());
+    }
+
+    #[test]
+    fn test_sbox_lookup() {
+        // A toy 4-bit S-box: x -> 7x + 3 mod 16
+        let res = gadget_tester(
+            |composer| {
+                let sbox_table =
+                    composer.add_lookup_table(LookupTable::from_unary_fn(4, |x| (x * 7 + 3) % 16));
+                let a = composer.add_input(BlsScalar::from(5));
+                let b = composer.add_input(BlsScalar::from((5 * 7 + 3) % 16));
+                composer.lookup_gate(sbox_table, a, b, composer.zero_var);
+            },
+            256,
+        );
+        assert!(res.is_ok());
+
+        // Rows of one table can not be used by a gate referring to another one
+        let res = gadget_tester(
+            |composer| {
+                let xor_table = composer.add_lookup_table(LookupTable::xor_table(2));
+                let sbox_table =
+                    composer.add_lookup_table(LookupTable::from_unary_fn(4, |x| (x * 7 + 3) % 16));
+                let a = composer.add_input(BlsScalar::from(1));
+                let b = composer.add_input(BlsScalar::from(2));
+                let c = composer.add_input(BlsScalar::from(3));
+                composer.lookup_gate(xor_table, a, b, c);
+                // `(1, 2, 3)` is a row of the XOR table, but not of the S-box one
+                composer.lookup_gate(sbox_table, a, b, c);
+            },
+            256,
+        );
+        assert!(res.is_err());
+    }
+
+    #[test]
+    #[should_panic]
+    fn test_unregistered_table() {
+        let mut composer = StandardComposer::new();
+        let a = composer.add_input(BlsScalar::one());
+        composer.lookup_gate(1, a, a, a);
+    }
+}
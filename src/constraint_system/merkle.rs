@@ -0,0 +1,140 @@
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this
+// file, You can obtain one at http://mozilla.org/MPL/2.0/.
+//
+// Copyright (c) DUSK NETWORK. All rights reserved.
+
+use crate::constraint_system::{StandardComposer, Variable};
+use crate::poseidon::Poseidon;
+use dusk_bls12_381::BlsScalar;
+
+impl StandardComposer {
+    /// Adds the gates opening a `MerkleTree` from `leaf` to the `root`,
+    /// declared as the public input `root_name`, and returns the `Variable`
+    /// holding the computed root.
+    ///
+    /// `siblings` and `directions` describe the path starting from the leaf,
+    /// as in a `MerkleOpening`. Each direction is constrained by a
+    /// `boolean_gate` to be `1` if the node is the right child of its parent
+    /// and `0` otherwise.
+    ///
+    /// # Panics
+    ///
+    /// If `siblings` and `directions` do not have the same length, or if a
+    /// public input named `root_name` was already declared.
+    pub fn merkle_opening(
+        &mut self,
+        poseidon: &Poseidon,
+        leaf: Variable,
+        siblings: &[Variable],
+        directions: &[Variable],
+        root_name: &str,
+        root: BlsScalar,
+    ) -> Variable {
+        assert_eq!(siblings.len(), directions.len());
+        self.push_namespace("merkle_opening");
+
+        let mut node = leaf;
+        for (sibling, direction) in siblings.iter().zip(directions.iter()) {
+            self.boolean_gate(*direction);
+
+            // left = node + direction * (sibling - node)
+            let diff = self.add(
+                (BlsScalar::one(), *sibling),
+                (-BlsScalar::one(), node),
+                BlsScalar::zero(),
+                BlsScalar::zero(),
+            );
+            let left = self.big_mul(
+                BlsScalar::one(),
+                *direction,
+                diff,
+                Some((BlsScalar::one(), node)),
+                BlsScalar::zero(),
+                BlsScalar::zero(),
+            );
+            // right = node + sibling - left
+            let right = self.big_add(
+                (BlsScalar::one(), node),
+                (BlsScalar::one(), *sibling),
+                Some((-BlsScalar::one(), left)),
+                BlsScalar::zero(),
+                BlsScalar::zero(),
+            );
+
+            node = self.poseidon_hash(poseidon, &[left, right]);
+        }
+
+        let root = self.public_input(root_name, root);
+        self.assert_equal(node, root);
+
+        self.pop_namespace();
+        node
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::super::helper::*;
+    use super::*;
+    use crate::merkle::MerkleTree;
+
+    // Opens the leaf at `index` of a tree holding 1, 2, 3, 4, 5 against the
+    // root of the tree plus `root_offset`, declared as the public input `name`
+    fn open_leaf(
+        composer: &mut StandardComposer,
+        index: usize,
+        name: &str,
+        root_offset: BlsScalar,
+    ) {
+        let leaves: Vec<_> = (1..6).map(BlsScalar::from).collect();
+        let tree = MerkleTree::new(3, &leaves).unwrap();
+        let opening = tree.opening(index).unwrap();
+
+        let leaf = composer.add_input(leaves.get(index).copied().unwrap_or_default());
+        let siblings: Vec<_> = opening
+            .siblings()
+            .iter()
+            .map(|sibling| composer.add_input(*sibling))
+            .collect();
+        let directions: Vec<_> = opening
+            .directions()
+            .iter()
+            .map(|is_right| composer.add_input(BlsScalar::from(*is_right as u64)))
+            .collect();
+
+        composer.merkle_opening(
+            &Poseidon::new(),
+            leaf,
+            &siblings,
+            &directions,
+            name,
+            tree.root() + root_offset,
+        );
+    }
+
+    #[test]
+    fn test_merkle_opening() {
+        let res = gadget_tester(
+            |composer| {
+                open_leaf(composer, 2, "root_2", BlsScalar::zero());
+                open_leaf(composer, 5, "root_5", BlsScalar::zero());
+                // An unused leaf can also be opened
+                open_leaf(composer, 6, "root_6", BlsScalar::zero());
+            },
+            4096,
+        );
+        assert!(res.is_ok());
+    }
+
+    #[test]
+    fn test_merkle_opening_wrong_root() {
+        let res = gadget_tester(
+            |composer| {
+                open_leaf(composer, 2, "root", BlsScalar::one());
+            },
+            1024,
+        );
+        assert!(res.is_err());
+    }
+}
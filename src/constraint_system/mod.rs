@@ -16,14 +16,36 @@ pub(crate) mod variable;
 pub mod arithmetic;
 /// Boolean gate
 pub mod boolean;
+/// Custom gates
+pub mod custom;
+/// Export and loading of circuits without their witness
+pub mod description;
 /// Elliptic Curve Crypto gates
 pub mod ecc;
 #[cfg(test)]
 pub(crate) mod helper;
 /// XOR and AND gates
 pub mod logic;
+/// Lookup gate and tables
+pub mod lookup;
+/// Merkle tree membership gadget
+pub mod merkle;
+/// Gate labelling and per-namespace gate counts
+pub mod namespace;
+/// Poseidon permutation gadget
+pub mod poseidon;
 /// Range gate
 pub mod range;
+/// Recursive proof verification gadget
+pub mod recursion;
+/// Satisfiability checks on the witness
+pub mod satisfiability;
+/// Assignment of the witness of a synthesized circuit
+pub mod witness;
 
 pub use composer::StandardComposer;
+pub use description::CircuitDescription;
+pub use lookup::LookupTable;
+pub use namespace::GateCountReport;
+pub use satisfiability::{GateKind, UnsatisfiedGate};
 pub use variable::{Variable, WireData};
@@ -0,0 +1,250 @@
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this
+// file, You can obtain one at http://mozilla.org/MPL/2.0/.
+//
+// Copyright (c) DUSK NETWORK. All rights reserved.
+
+//! Labels for the gates of a `StandardComposer`.
+//!
+//! Every gate added within `StandardComposer::namespace` is labelled with
+//! the path of the namespaces it was added in, such as `merkle/level3`.
+//! The built-in gadgets open a namespace named after themselves, so the
+//! `GateCountReport` of a circuit tells which gadgets its gates come from.
+
+use crate::constraint_system::StandardComposer;
+use std::collections::BTreeMap;
+use std::fmt;
+
+/// Separator of the names in the path of a namespace.
+pub const SEPARATOR: char = '/';
+
+/// The gates added within a namespace.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(crate) struct NamespaceSpan {
+    pub(crate) path: String,
+    // Number of namespaces the span is nested in, plus one
+    pub(crate) depth: usize,
+    pub(crate) start: usize,
+    pub(crate) end: usize,
+}
+
+impl NamespaceSpan {
+    fn contains(&self, index: usize) -> bool {
+        self.start <= index && index < self.end
+    }
+}
+
+impl StandardComposer {
+    /// Calls `f`, labelling the gates it adds with the namespace `name`
+    /// nested in the current namespace, and returns its result.
+    ///
+    /// # Example
+    ///
+    /// ```ignore
+    /// composer.namespace("merkle", |composer| {
+    ///     composer.namespace("level3", |composer| {
+    ///         // Gates labelled with `merkle/level3`
+    ///     })
+    /// });
+    /// ```
+    pub fn namespace<T, F>(&mut self, name: &str, f: F) -> T
+    where
+        F: FnOnce(&mut StandardComposer) -> T,
+    {
+        self.push_namespace(name);
+        let result = f(self);
+        self.pop_namespace();
+        result
+    }
+
+    /// Enters the namespace `name`, which must be exited by `pop_namespace`.
+    pub(crate) fn push_namespace(&mut self, name: &str) {
+        let path = match self.open_namespaces.last() {
+            Some((parent, _)) => format!("{}{}{}", parent, SEPARATOR, name),
+            None => name.to_string(),
+        };
+        self.open_namespaces.push((path, self.n));
+    }
+
+    /// Exits the last namespace entered by `push_namespace`.
+    pub(crate) fn pop_namespace(&mut self) {
+        let (path, start) = self
+            .open_namespaces
+            .pop()
+            .expect("no namespace was entered");
+        self.namespaces.push(NamespaceSpan {
+            path,
+            depth: self.open_namespaces.len() + 1,
+            start,
+            end: self.n,
+        });
+    }
+
+    /// Returns the spans of the exited namespaces followed by the ones which
+    /// are still open.
+    fn namespace_spans(&self) -> impl Iterator<Item = NamespaceSpan> + '_ {
+        let open = self
+            .open_namespaces
+            .iter()
+            .enumerate()
+            .map(move |(i, (path, start))| NamespaceSpan {
+                path: path.clone(),
+                depth: i + 1,
+                start: *start,
+                end: self.n,
+            });
+        self.namespaces.iter().cloned().chain(open)
+    }
+
+    /// Returns the path of the innermost namespace the gate at `index` was
+    /// added in, or `None` if it was not added in any namespace.
+    pub fn gate_namespace(&self, index: usize) -> Option<String> {
+        self.namespace_spans()
+            .filter(|span| span.contains(index))
+            .max_by_key(|span| span.depth)
+            .map(|span| span.path)
+    }
+
+    /// Counts the gates added in each namespace of the circuit.
+    pub fn gate_count_report(&self) -> GateCountReport {
+        let mut namespaces = BTreeMap::new();
+        let mut labelled = vec![false; self.n];
+        for span in self.namespace_spans() {
+            *namespaces.entry(span.path).or_insert(0) += span.end - span.start;
+            labelled[span.start..span.end]
+                .iter_mut()
+                .for_each(|gate| *gate = true);
+        }
+
+        GateCountReport {
+            total: self.n,
+            unlabelled: labelled.iter().filter(|gate| !**gate).count(),
+            namespaces,
+        }
+    }
+}
+
+/// The number of gates of a circuit added in each of its namespaces.
+///
+/// The count of a namespace includes the gates of the namespaces nested in
+/// it, and sums the gates of every time it was entered.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct GateCountReport {
+    total: usize,
+    unlabelled: usize,
+    namespaces: BTreeMap<String, usize>,
+}
+
+impl GateCountReport {
+    /// Returns the number of gates in the circuit.
+    pub fn total(&self) -> usize {
+        self.total
+    }
+
+    /// Returns the number of gates which were not added in any namespace.
+    pub fn unlabelled(&self) -> usize {
+        self.unlabelled
+    }
+
+    /// Returns the number of gates added in the namespace at `path`.
+    pub fn get(&self, path: &str) -> usize {
+        self.namespaces.get(path).copied().unwrap_or(0)
+    }
+
+    /// Returns the paths of the namespaces along with their number of gates,
+    /// sorted by path.
+    pub fn iter(&self) -> impl Iterator<Item = (&str, usize)> {
+        self.namespaces
+            .iter()
+            .map(|(path, count)| (path.as_str(), *count))
+    }
+}
+
+impl fmt::Display for GateCountReport {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        writeln!(f, "total: {}", self.total)?;
+        for (path, count) in self.iter() {
+            let depth = path.matches(SEPARATOR).count();
+            writeln!(f, "{:indent$}{}: {}", "", path, count, indent = 2 * depth)?;
+        }
+        writeln!(f, "unlabelled: {}", self.unlabelled)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::constraint_system::GateKind;
+    use dusk_bls12_381::BlsScalar;
+
+    #[test]
+    fn test_nested_namespaces() {
+        let mut composer = StandardComposer::new();
+        let dummy_gates = composer.circuit_size();
+        let a = composer.add_input(BlsScalar::from(3));
+
+        let start = composer.circuit_size();
+        let b = composer.namespace("outer", |composer| {
+            let b = composer.namespace("inner", |composer| {
+                composer.mul(BlsScalar::one(), a, a, BlsScalar::zero(), BlsScalar::zero())
+            });
+            composer.range_gate(b, 8);
+            b
+        });
+        let end = composer.circuit_size();
+        composer.namespace("outer", |composer| {
+            composer.add(
+                (BlsScalar::one(), a),
+                (BlsScalar::one(), b),
+                BlsScalar::zero(),
+                BlsScalar::zero(),
+            );
+        });
+
+        assert_eq!(composer.gate_namespace(start).unwrap(), "outer/inner");
+        assert_eq!(
+            composer.gate_namespace(start + 1).unwrap(),
+            "outer/range_gate"
+        );
+        assert_eq!(composer.gate_namespace(end).unwrap(), "outer");
+        assert!(composer.gate_namespace(0).is_none());
+
+        let report = composer.gate_count_report();
+        assert_eq!(report.total(), composer.circuit_size());
+        assert_eq!(report.get("outer"), composer.circuit_size() - dummy_gates);
+        assert_eq!(report.get("outer/inner"), 1);
+        assert_eq!(report.get("outer/range_gate"), end - start - 1);
+        assert_eq!(report.get("missing"), 0);
+        assert_eq!(report.unlabelled(), dummy_gates);
+        assert_eq!(
+            report.iter().map(|(path, _)| path).collect::<Vec<_>>(),
+            vec!["outer", "outer/inner", "outer/range_gate"]
+        );
+    }
+
+    #[test]
+    fn test_unsatisfied_gate_namespace() {
+        let mut composer = StandardComposer::new();
+        composer.namespace("merkle", |composer| {
+            composer.namespace("level3", |composer| {
+                let a = composer.add_input(BlsScalar::from(2));
+                // 2 * 2 != 5
+                composer.poly_gate(
+                    a,
+                    a,
+                    composer.zero_var,
+                    BlsScalar::one(),
+                    BlsScalar::zero(),
+                    BlsScalar::zero(),
+                    BlsScalar::zero(),
+                    -BlsScalar::from(5),
+                    BlsScalar::zero(),
+                );
+            });
+        });
+
+        let unsatisfied = composer.check_satisfied().unwrap_err();
+        assert_eq!(unsatisfied.kind, GateKind::Arithmetic);
+        assert_eq!(unsatisfied.namespace.unwrap(), "merkle/level3");
+    }
+}
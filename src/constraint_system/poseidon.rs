@@ -0,0 +1,239 @@
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this
+// file, You can obtain one at http://mozilla.org/MPL/2.0/.
+//
+// Copyright (c) DUSK NETWORK. All rights reserved.
+
+use crate::constraint_system::{StandardComposer, Variable};
+use crate::poseidon::{sbox, Poseidon, RATE, WIDTH};
+use dusk_bls12_381::BlsScalar;
+
+impl StandardComposer {
+    /// Adds a Poseidon S-box gate which constrains the returned `Variable` to
+    /// hold `(a + constant)^5`.
+    ///
+    /// The gate holds `(a + constant)^2` in its fourth wire, so the whole
+    /// S-box fits in a single row.
+    pub fn sbox_gate(&mut self, a: Variable, constant: BlsScalar) -> Variable {
+        let square = self.add_input(BlsScalar::zero());
+        let quint = self.add_input(BlsScalar::zero());
+        self.add_witness_generator(move |composer| {
+            let x = composer.variables[&a] + constant;
+            composer.variables.insert(square, x.square());
+            composer.variables.insert(quint, sbox(&x));
+        });
+
+        self.w_l.push(a);
+        self.w_r.push(self.zero_var);
+        self.w_o.push(quint);
+        self.w_4.push(square);
+
+        // The constant is held in `q_c` and added to `a` by the S-box widget
+        self.q_m.push(BlsScalar::zero());
+        self.q_l.push(BlsScalar::zero());
+        self.q_r.push(BlsScalar::zero());
+        self.q_o.push(BlsScalar::zero());
+        self.q_c.push(constant);
+        self.q_4.push(BlsScalar::zero());
+        self.q_arith.push(BlsScalar::zero());
+        self.q_range.push(BlsScalar::zero());
+        self.q_logic.push(BlsScalar::zero());
+        self.q_variable_group_add.push(BlsScalar::zero());
+        self.q_lookup.push(BlsScalar::zero());
+        self.q_sbox.push(BlsScalar::one());
+
+        self.public_inputs.push(BlsScalar::zero());
+
+        self.perm
+            .add_variables_to_map(a, self.zero_var, quint, square, self.n);
+        self.n += 1;
+
+        quint
+    }
+
+    /// Applies the Poseidon permutation to the `state` and returns the
+    /// permuted state.
+    ///
+    /// The round constants are folded into the gates, so a full round costs
+    /// 6 gates and a partial round 4 gates.
+    pub fn poseidon_permutation(
+        &mut self,
+        poseidon: &Poseidon,
+        state: [Variable; WIDTH],
+    ) -> [Variable; WIDTH] {
+        self.push_namespace("poseidon_permutation");
+        let mds = poseidon.mds();
+        let mut state = state;
+
+        for (round, constants) in poseidon.round_constants().iter().enumerate() {
+            if Poseidon::is_full_round(round) {
+                let mut sboxed = [self.zero_var; WIDTH];
+                for ((s, x), c) in sboxed.iter_mut().zip(state.iter()).zip(constants.iter()) {
+                    *s = self.sbox_gate(*x, *c);
+                }
+                for (s, row) in state.iter_mut().zip(mds.iter()) {
+                    *s = self.big_add(
+                        (row[0], sboxed[0]),
+                        (row[1], sboxed[1]),
+                        Some((row[2], sboxed[2])),
+                        BlsScalar::zero(),
+                        BlsScalar::zero(),
+                    );
+                }
+            } else {
+                // Only the first element goes through the S-box, the constants of
+                // the others are added by the MDS gates
+                let sboxed = self.sbox_gate(state[0], constants[0]);
+                let (x_1, x_2) = (state[1], state[2]);
+                for (s, row) in state.iter_mut().zip(mds.iter()) {
+                    *s = self.big_add(
+                        (row[0], sboxed),
+                        (row[1], x_1),
+                        Some((row[2], x_2)),
+                        row[1] * constants[1] + row[2] * constants[2],
+                        BlsScalar::zero(),
+                    );
+                }
+            }
+        }
+
+        self.pop_namespace();
+        state
+    }
+
+    /// Hashes the `inputs` and returns a `Variable` holding the result of
+    /// `Poseidon::hash` over their values.
+    pub fn poseidon_hash(&mut self, poseidon: &Poseidon, inputs: &[Variable]) -> Variable {
+        let capacity =
+            self.add_witness_to_circuit_description(BlsScalar::from(inputs.len() as u64));
+        let mut state = [capacity, self.zero_var, self.zero_var];
+
+        if inputs.is_empty() {
+            state = self.poseidon_permutation(poseidon, state);
+        }
+        for chunk in inputs.chunks(RATE) {
+            for (s, x) in state[1..].iter_mut().zip(chunk.iter()) {
+                *s = self.add(
+                    (BlsScalar::one(), *s),
+                    (BlsScalar::one(), *x),
+                    BlsScalar::zero(),
+                    BlsScalar::zero(),
+                );
+            }
+            state = self.poseidon_permutation(poseidon, state);
+        }
+
+        state[1]
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::super::helper::*;
+    use super::*;
+
+    #[test]
+    fn test_poseidon_permutation() {
+        let res = gadget_tester(
+            |composer| {
+                let poseidon = Poseidon::new();
+                let mut expected = [BlsScalar::from(3), BlsScalar::from(5), BlsScalar::from(7)];
+
+                let mut state = [composer.zero_var; WIDTH];
+                for (var, value) in state.iter_mut().zip(expected.iter()) {
+                    *var = composer.add_input(*value);
+                }
+                let state = composer.poseidon_permutation(&poseidon, state);
+
+                poseidon.permute(&mut expected);
+                for (var, value) in state.iter().zip(expected.iter()) {
+                    composer.constrain_to_constant(*var, *value, BlsScalar::zero());
+                }
+            },
+            1024,
+        );
+        assert!(res.is_ok());
+    }
+
+    #[test]
+    fn test_sbox_gate() {
+        let res = gadget_tester(
+            |composer| {
+                let a = composer.add_input(BlsScalar::from(3));
+                let quint = composer.sbox_gate(a, BlsScalar::from(2));
+                composer.constrain_to_constant(quint, BlsScalar::from(3125), BlsScalar::zero());
+            },
+            32,
+        );
+        assert!(res.is_ok());
+
+        // Should fail as the output is not the fifth power of the shifted input
+        let res = gadget_tester(
+            |composer| {
+                let a = composer.add_input(BlsScalar::from(3));
+                let quint = composer.sbox_gate(a, BlsScalar::from(2));
+                composer.variables.insert(quint, BlsScalar::from(3124));
+            },
+            32,
+        );
+        assert!(res.is_err());
+
+        // Should fail as the fourth wire does not hold the square
+        let res = gadget_tester(
+            |composer| {
+                let a = composer.add_input(BlsScalar::from(3));
+                composer.sbox_gate(a, BlsScalar::from(2));
+                let square = composer.w_4[composer.n - 1];
+                composer.variables.insert(square, BlsScalar::from(24));
+            },
+            32,
+        );
+        assert!(res.is_err());
+    }
+
+    #[test]
+    fn test_sbox_gate_opens_q_c() {
+        use crate::commitment_scheme::kzg10::PublicParameters;
+        use crate::proof_system::{Prover, Verifier};
+
+        let public_parameters = PublicParameters::setup(2 * 32, &mut rand::thread_rng()).unwrap();
+        let (ck, vk) = public_parameters.trim(2 * 16).unwrap();
+        let gadget = |composer: &mut StandardComposer| {
+            let a = composer.add_input(BlsScalar::from(3));
+            composer.sbox_gate(a, BlsScalar::from(2));
+        };
+
+        let mut prover = Prover::new(b"demo");
+        gadget(prover.mut_cs());
+        prover.preprocess(&ck).unwrap();
+        let mut proof = prover.prove(&ck).unwrap();
+
+        let mut verifier = Verifier::new(b"demo");
+        gadget(verifier.mut_cs());
+        verifier.preprocess(&ck).unwrap();
+        assert!(verifier.verify(&proof, &vk, &[]).is_ok());
+
+        // Should fail as the S-box constant is not the evaluation of q_c
+        proof.evaluations.q_c_eval += BlsScalar::one();
+        assert!(verifier.verify(&proof, &vk, &[]).is_err());
+    }
+
+    #[test]
+    fn test_poseidon_hash() {
+        let res = gadget_tester(
+            |composer| {
+                let poseidon = Poseidon::new();
+                // Test vectors covering an empty input, partial and full chunks
+                let vectors: [&[u64]; 4] = [&[], &[1], &[1, 2], &[1, 2, 3]];
+                for inputs in vectors.iter() {
+                    let inputs: Vec<_> = inputs.iter().map(|x| BlsScalar::from(*x)).collect();
+                    let vars: Vec<_> = inputs.iter().map(|x| composer.add_input(*x)).collect();
+                    let hash = composer.poseidon_hash(&poseidon, &vars);
+                    composer.constrain_to_constant(hash, poseidon.hash(&inputs), BlsScalar::zero());
+                }
+            },
+            2048,
+        );
+        assert!(res.is_ok());
+    }
+}
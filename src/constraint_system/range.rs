@@ -13,6 +13,8 @@ impl StandardComposer {
     /// Adds a range-constraint gate that checks and constrains a
     /// `Variable` to be inside of the range [0,num_bits].
     pub fn range_gate(&mut self, witness: Variable, num_bits: usize) {
+        self.push_namespace("range_gate");
+
         // Adds `variable` into the appropriate witness position
         // based on the accumulator number a_i
         let add_wire = |composer: &mut StandardComposer, i: usize, variable: Variable| {
@@ -46,12 +48,6 @@ impl StandardComposer {
         // Number of bits should be even, this means that user must pad the number of bits external.
         assert!(num_bits % 2 == 0);
 
-        // Convert witness to bit representation and reverse
-        let value = self.variables[&witness];
-        let bit_iter = BitIterator8::new(value.to_bytes());
-        let mut bits: Vec<_> = bit_iter.collect();
-        bits.reverse();
-
         // For a width-4 program, one gate will contain 4 accumulators
         // Each accumulator proves that a single quad is a base-4 digit.
         // Since there is 1-1 mapping between accumulators and quads
@@ -106,8 +102,6 @@ impl StandardComposer {
         // We collect the set of accumulators to return back to the user
         // and keep a running count of the current accumulator
         let mut accumulators: Vec<Variable> = Vec::new();
-        let mut accumulator = BlsScalar::zero();
-        let four = BlsScalar::from(4);
 
         // First we pad our gates by the necessary amount
         for i in 0..pad {
@@ -115,22 +109,36 @@ impl StandardComposer {
         }
 
         for i in pad..=num_quads {
-            // Convert each pair of bits to quads
-            let bit_index = (num_quads - i) << 1;
-            let q_0 = bits[bit_index] as u64;
-            let q_1 = bits[bit_index + 1] as u64;
-            let quad = q_0 + (2 * q_1);
-
-            // Compute the next accumulator term
-            accumulator = four * accumulator;
-            accumulator += BlsScalar::from(quad);
-
-            let accumulator_var = self.add_input(accumulator);
+            let accumulator_var = self.add_input(BlsScalar::zero());
             accumulators.push(accumulator_var);
 
             add_wire(self, i, accumulator_var);
         }
 
+        let accumulator_vars = accumulators.clone();
+        self.add_witness_generator(move |composer| {
+            // Convert witness to bit representation and reverse
+            let bit_iter = BitIterator8::new(composer.variables[&witness].to_bytes());
+            let mut bits: Vec<_> = bit_iter.collect();
+            bits.reverse();
+
+            let mut accumulator = BlsScalar::zero();
+            let four = BlsScalar::from(4);
+            for (i, accumulator_var) in (pad..=num_quads).zip(accumulator_vars.iter()) {
+                // Convert each pair of bits to quads
+                let bit_index = (num_quads - i) << 1;
+                let q_0 = bits[bit_index] as u64;
+                let q_1 = bits[bit_index + 1] as u64;
+                let quad = q_0 + (2 * q_1);
+
+                // Compute the next accumulator term
+                accumulator = four * accumulator;
+                accumulator += BlsScalar::from(quad);
+
+                composer.variables.insert(*accumulator_var, accumulator);
+            }
+        });
+
         // Set the selector polynomials for all of the gates we used
         let zeros = vec![BlsScalar::zero(); used_gates];
         let ones = vec![BlsScalar::one(); used_gates];
@@ -142,8 +150,9 @@ impl StandardComposer {
         self.q_c.extend(zeros.iter());
         self.q_arith.extend(zeros.iter());
         self.q_4.extend(zeros.iter());
-        self.q_fixed_group_add.extend(zeros.iter());
         self.q_variable_group_add.extend(zeros.iter());
+        self.q_lookup.extend(zeros.iter());
+        self.q_sbox.extend(zeros.iter());
         self.q_range.extend(ones.iter());
         self.q_logic.extend(zeros.iter());
         self.public_inputs.extend(zeros.iter());
@@ -164,6 +173,8 @@ impl StandardComposer {
         let last_accumulator = accumulators.len() - 1;
         self.assert_equal(accumulators[last_accumulator], witness);
         accumulators[last_accumulator] = witness;
+
+        self.pop_namespace();
     }
 }
 #[cfg(test)]
@@ -0,0 +1,1519 @@
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this
+// file, You can obtain one at http://mozilla.org/MPL/2.0/.
+//
+// Copyright (c) DUSK NETWORK. All rights reserved.
+
+//! Gadget verifying a `Proof` inside the circuit.
+//!
+//! The gadget replays the transcript of the verifier with a `TranscriptGadget`
+//! so only proofs created with the `PoseidonTranscript` can be verified.
+//! It then computes the quotient evaluation and the scalars of the
+//! linearisation commitment in the circuit, and folds every KZG10 opening as
+//! the native verifier does.
+//!
+//! The group operations of the final pairing check cannot be expressed over
+//! `BlsScalar`, so the gadget returns a `KzgAccumulator` instead: the scalars
+//! of the two multi-scalar multiplications the pairing is computed on, as
+//! circuit variables, together with the commitments they multiply. The proof
+//! is valid iff the accumulator passes `KzgAccumulator::check`, which can be
+//! done later, or for many accumulators at once.
+
+// Gadget fn's have a large number of attributes but
+// it is intended to be like this in order to mirror
+// the native verifier.
+#![allow(clippy::too_many_arguments)]
+
+mod transcript;
+
+pub use transcript::TranscriptGadget;
+
+use self::transcript::{chunk_to_scalar, CHUNK_SIZE};
+use crate::commitment_scheme::kzg10::{Commitment, OpeningKey};
+use crate::constraint_system::{StandardComposer, Variable};
+use crate::fft::EvaluationDomain;
+use crate::permutation::constants::{K1, K2, K3};
+use crate::proof_system::proof_system_errors::ProofErrors;
+use crate::proof_system::widget::custom::{same_gate, CustomGate};
+use crate::proof_system::widget::ecc::scalar_mul::fixed_base::FixedBaseGate;
+use crate::proof_system::widget::VerifierKey;
+use crate::proof_system::{Proof, PublicInputLayout};
+use crate::transcript::PROTOCOL_VERSION;
+use anyhow::{Error, Result};
+use dusk_bls12_381::{BlsScalar, G1Affine, G1Projective};
+use dusk_jubjub::EDWARDS_D;
+
+/// A `Commitment` allocated in the circuit.
+///
+/// It is held as the two scalars its compressed encoding is absorbed as by the
+/// transcript: the first 31 bytes and the remaining 17 bytes.
+#[derive(Debug, Clone, Copy)]
+pub struct CommitmentVariable {
+    chunks: [Variable; 2],
+}
+
+impl CommitmentVariable {
+    /// Size of a compressed commitment in bytes.
+    const SIZE: usize = 48;
+
+    /// Allocates the `commitment` in the circuit and constrains its chunks to
+    /// fit in their number of bytes.
+    pub fn new(composer: &mut StandardComposer, commitment: &Commitment) -> Self {
+        let bytes = commitment.0.to_compressed();
+        let mut chunks = [composer.zero_var; 2];
+        for (var, chunk) in chunks.iter_mut().zip(bytes.chunks(CHUNK_SIZE)) {
+            *var = composer.add_input(chunk_to_scalar(chunk));
+            composer.range_gate(*var, chunk.len() * 8);
+        }
+        CommitmentVariable { chunks }
+    }
+
+    /// Returns the chunks of the compressed commitment.
+    pub fn chunks(&self) -> &[Variable; 2] {
+        &self.chunks
+    }
+
+    /// Decodes the commitment from the values of its chunks.
+    fn value(&self, composer: &StandardComposer) -> Option<G1Affine> {
+        let mut bytes = [0u8; CommitmentVariable::SIZE];
+        for (chunk, var) in bytes.chunks_mut(CHUNK_SIZE).zip(self.chunks.iter()) {
+            let scalar_bytes = composer.variables[var].to_bytes();
+            if scalar_bytes[chunk.len()..].iter().any(|byte| *byte != 0) {
+                return None;
+            }
+            chunk.copy_from_slice(&scalar_bytes[..chunk.len()]);
+        }
+        Option::from(G1Affine::from_compressed(&bytes))
+    }
+}
+
+/// A `VerifierKey` allocated in the circuit.
+///
+/// The size of the circuit and the layout of its public inputs are constants
+/// of the circuit, while the commitments are variables. The only custom gate
+/// it can hold is the built-in fixed base gate.
+#[derive(Debug, Clone)]
+pub struct VerifierKeyVariables {
+    n: usize,
+    domain: EvaluationDomain,
+    public_inputs: PublicInputLayout,
+    public_input_gates: Vec<usize>,
+    q_m: CommitmentVariable,
+    q_l: CommitmentVariable,
+    q_r: CommitmentVariable,
+    q_o: CommitmentVariable,
+    q_c: CommitmentVariable,
+    q_4: CommitmentVariable,
+    q_arith: CommitmentVariable,
+    q_range: CommitmentVariable,
+    q_logic: CommitmentVariable,
+    q_variable_group_add: CommitmentVariable,
+    left_sigma: CommitmentVariable,
+    right_sigma: CommitmentVariable,
+    out_sigma: CommitmentVariable,
+    fourth_sigma: CommitmentVariable,
+    q_lookup: CommitmentVariable,
+    table_1: CommitmentVariable,
+    table_2: CommitmentVariable,
+    table_3: CommitmentVariable,
+    table_4: CommitmentVariable,
+    q_sbox: CommitmentVariable,
+    fixed_base: Option<Vec<CommitmentVariable>>,
+}
+
+impl VerifierKeyVariables {
+    /// Allocates the commitments of the `verifier_key` in the circuit.
+    ///
+    /// Fails with `ProofErrors::CustomGatesNotSupported` if the circuit uses
+    /// custom gates other than the built-in fixed base gate.
+    pub fn new(
+        composer: &mut StandardComposer,
+        verifier_key: &VerifierKey,
+    ) -> Result<VerifierKeyVariables, Error> {
+        let fixed_base = match verifier_key.custom.len() {
+            0 => None,
+            1 if same_gate(verifier_key.custom[0].gate.as_ref(), &FixedBaseGate) => {
+                Some(&verifier_key.custom[0].selectors)
+            }
+            _ => return Err(ProofErrors::CustomGatesNotSupported.into()),
+        };
+
+        let mut alloc = |commitment| CommitmentVariable::new(composer, commitment);
+        Ok(VerifierKeyVariables {
+            n: verifier_key.n,
+            domain: EvaluationDomain::new(verifier_key.n)?,
+            public_inputs: verifier_key.public_inputs.clone(),
+            public_input_gates: verifier_key.public_input_gates.clone(),
+            q_m: alloc(&verifier_key.arithmetic.q_m),
+            q_l: alloc(&verifier_key.arithmetic.q_l),
+            q_r: alloc(&verifier_key.arithmetic.q_r),
+            q_o: alloc(&verifier_key.arithmetic.q_o),
+            q_c: alloc(&verifier_key.arithmetic.q_c),
+            q_4: alloc(&verifier_key.arithmetic.q_4),
+            q_arith: alloc(&verifier_key.arithmetic.q_arith),
+            q_range: alloc(&verifier_key.range.q_range),
+            q_logic: alloc(&verifier_key.logic.q_logic),
+            q_variable_group_add: alloc(&verifier_key.variable_base.q_variable_group_add),
+            left_sigma: alloc(&verifier_key.permutation.left_sigma),
+            right_sigma: alloc(&verifier_key.permutation.right_sigma),
+            out_sigma: alloc(&verifier_key.permutation.out_sigma),
+            fourth_sigma: alloc(&verifier_key.permutation.fourth_sigma),
+            q_lookup: alloc(&verifier_key.lookup.q_lookup),
+            table_1: alloc(&verifier_key.lookup.table_1),
+            table_2: alloc(&verifier_key.lookup.table_2),
+            table_3: alloc(&verifier_key.lookup.table_3),
+            table_4: alloc(&verifier_key.lookup.table_4),
+            q_sbox: alloc(&verifier_key.sbox.q_sbox),
+            fixed_base: fixed_base.map(|selectors| selectors.iter().map(alloc).collect()),
+        })
+    }
+
+    /// Mirrors `VerifierKey::digest`.
+    fn digest(&self, composer: &mut StandardComposer) -> Variable {
+        let mut transcript = TranscriptGadget::new(composer, b"dusk-plonk-verifier-key");
+        let mut append = |label: &'static [u8], commitment| {
+            transcript.append_commitment(composer, label, commitment);
+        };
+        append(b"q_m", &self.q_m);
+        append(b"q_l", &self.q_l);
+        append(b"q_r", &self.q_r);
+        append(b"q_o", &self.q_o);
+        append(b"q_c", &self.q_c);
+        append(b"q_4", &self.q_4);
+        append(b"q_arith", &self.q_arith);
+        append(b"q_range", &self.q_range);
+        append(b"q_logic", &self.q_logic);
+        append(b"q_variable_group_add", &self.q_variable_group_add);
+
+        append(b"left_sigma", &self.left_sigma);
+        append(b"right_sigma", &self.right_sigma);
+        append(b"out_sigma", &self.out_sigma);
+        append(b"fourth_sigma", &self.fourth_sigma);
+
+        append(b"q_lookup", &self.q_lookup);
+        append(b"table_1", &self.table_1);
+        append(b"table_2", &self.table_2);
+        append(b"table_3", &self.table_3);
+        append(b"table_4", &self.table_4);
+
+        append(b"q_sbox", &self.q_sbox);
+
+        if let Some(selectors) = self.fixed_base.as_ref() {
+            transcript.append_message(composer, b"custom_gate", FixedBaseGate.name().as_bytes());
+            for selector in selectors.iter() {
+                transcript.append_commitment(composer, b"q_custom", selector);
+            }
+        }
+
+        // Append circuit size to transcript
+        transcript.circuit_domain_sep(composer, self.n as u64);
+
+        for (name, position) in self.public_inputs.iter() {
+            transcript.append_message(composer, b"public_input", name.as_bytes());
+            transcript.append_constant(composer, b"position", BlsScalar::from(position as u64));
+        }
+        for position in self.public_input_gates.iter() {
+            transcript.append_constant(composer, b"pi_gate", BlsScalar::from(*position as u64));
+        }
+
+        transcript.challenge_scalar(composer, b"digest")
+    }
+}
+
+/// A `Proof` allocated in the circuit.
+#[derive(Debug, Clone)]
+pub struct ProofVariables {
+    a_comm: CommitmentVariable,
+    b_comm: CommitmentVariable,
+    c_comm: CommitmentVariable,
+    d_comm: CommitmentVariable,
+    z_comm: CommitmentVariable,
+    f_comm: CommitmentVariable,
+    h_1_comm: CommitmentVariable,
+    h_2_comm: CommitmentVariable,
+    p_comm: CommitmentVariable,
+    t_1_comm: CommitmentVariable,
+    t_2_comm: CommitmentVariable,
+    t_3_comm: CommitmentVariable,
+    t_4_comm: CommitmentVariable,
+    w_z_comm: CommitmentVariable,
+    w_zw_comm: CommitmentVariable,
+    evaluations: EvaluationVariables,
+}
+
+/// The `ProofEvaluations` of a `Proof` allocated in the circuit.
+#[derive(Debug, Clone, Copy)]
+struct EvaluationVariables {
+    a_eval: Variable,
+    b_eval: Variable,
+    c_eval: Variable,
+    d_eval: Variable,
+    a_next_eval: Variable,
+    b_next_eval: Variable,
+    d_next_eval: Variable,
+    q_arith_eval: Variable,
+    q_c_eval: Variable,
+    left_sigma_eval: Variable,
+    right_sigma_eval: Variable,
+    out_sigma_eval: Variable,
+    lin_poly_eval: Variable,
+    perm_eval: Variable,
+    f_eval: Variable,
+    h_1_eval: Variable,
+    h_1_next_eval: Variable,
+    h_2_eval: Variable,
+    table_eval: Variable,
+    table_next_eval: Variable,
+    lookup_perm_eval: Variable,
+}
+
+impl ProofVariables {
+    /// Allocates the commitments and evaluations of the `proof` in the circuit.
+    pub fn new(composer: &mut StandardComposer, proof: &Proof) -> ProofVariables {
+        let mut alloc = |commitment| CommitmentVariable::new(composer, commitment);
+        let a_comm = alloc(&proof.a_comm);
+        let b_comm = alloc(&proof.b_comm);
+        let c_comm = alloc(&proof.c_comm);
+        let d_comm = alloc(&proof.d_comm);
+        let z_comm = alloc(&proof.z_comm);
+        let f_comm = alloc(&proof.f_comm);
+        let h_1_comm = alloc(&proof.h_1_comm);
+        let h_2_comm = alloc(&proof.h_2_comm);
+        let p_comm = alloc(&proof.p_comm);
+        let t_1_comm = alloc(&proof.t_1_comm);
+        let t_2_comm = alloc(&proof.t_2_comm);
+        let t_3_comm = alloc(&proof.t_3_comm);
+        let t_4_comm = alloc(&proof.t_4_comm);
+        let w_z_comm = alloc(&proof.w_z_comm);
+        let w_zw_comm = alloc(&proof.w_zw_comm);
+
+        let evaluations = &proof.evaluations;
+        let mut alloc = |eval| composer.add_input(eval);
+        let evaluations = EvaluationVariables {
+            a_eval: alloc(evaluations.a_eval),
+            b_eval: alloc(evaluations.b_eval),
+            c_eval: alloc(evaluations.c_eval),
+            d_eval: alloc(evaluations.d_eval),
+            a_next_eval: alloc(evaluations.a_next_eval),
+            b_next_eval: alloc(evaluations.b_next_eval),
+            d_next_eval: alloc(evaluations.d_next_eval),
+            q_arith_eval: alloc(evaluations.q_arith_eval),
+            q_c_eval: alloc(evaluations.q_c_eval),
+            left_sigma_eval: alloc(evaluations.left_sigma_eval),
+            right_sigma_eval: alloc(evaluations.right_sigma_eval),
+            out_sigma_eval: alloc(evaluations.out_sigma_eval),
+            lin_poly_eval: alloc(evaluations.lin_poly_eval),
+            perm_eval: alloc(evaluations.perm_eval),
+            f_eval: alloc(evaluations.f_eval),
+            h_1_eval: alloc(evaluations.h_1_eval),
+            h_1_next_eval: alloc(evaluations.h_1_next_eval),
+            h_2_eval: alloc(evaluations.h_2_eval),
+            table_eval: alloc(evaluations.table_eval),
+            table_next_eval: alloc(evaluations.table_next_eval),
+            lookup_perm_eval: alloc(evaluations.lookup_perm_eval),
+        };
+
+        ProofVariables {
+            a_comm,
+            b_comm,
+            c_comm,
+            d_comm,
+            z_comm,
+            f_comm,
+            h_1_comm,
+            h_2_comm,
+            p_comm,
+            t_1_comm,
+            t_2_comm,
+            t_3_comm,
+            t_4_comm,
+            w_z_comm,
+            w_zw_comm,
+            evaluations,
+        }
+    }
+}
+
+/// A commitment computed in the circuit, as a linear combination of
+/// allocated commitments.
+type CommitmentTerms = Vec<(Variable, CommitmentVariable)>;
+
+/// The pairing inputs of the KZG10 check of a `Proof` verified in the circuit.
+///
+/// The proof is valid iff `e(-W, beta_h) * e(C, h) = 1`, where `W` is the sum of
+/// the `w_terms` and `C` is the sum of the `c_terms` minus `g_scalar` times the
+/// generator of G1.
+/// To check the accumulator outside of the prover, the variables it holds must
+/// be exposed as public inputs of the circuit.
+#[derive(Debug, Clone)]
+pub struct KzgAccumulator {
+    w_terms: CommitmentTerms,
+    c_terms: CommitmentTerms,
+    g_scalar: Variable,
+}
+
+impl KzgAccumulator {
+    /// Returns the scalars and commitments the `W` point is the sum of.
+    pub fn w_terms(&self) -> &[(Variable, CommitmentVariable)] {
+        &self.w_terms
+    }
+
+    /// Returns the scalars and commitments the `C` point is the sum of,
+    /// before subtracting the generator.
+    pub fn c_terms(&self) -> &[(Variable, CommitmentVariable)] {
+        &self.c_terms
+    }
+
+    /// Returns the scalar the generator of G1 is subtracted from `C` with.
+    pub fn g_scalar(&self) -> Variable {
+        self.g_scalar
+    }
+
+    /// Computes the pairing inputs from the values of the variables in the
+    /// `composer` and checks them with a single pairing.
+    pub fn check(
+        &self,
+        composer: &StandardComposer,
+        opening_key: &OpeningKey,
+    ) -> Result<(), Error> {
+        let sum = |terms: &[(Variable, CommitmentVariable)]| {
+            terms
+                .iter()
+                .map(|(scalar, commitment)| {
+                    commitment
+                        .value(composer)
+                        .map(|point| point * composer.variables[scalar])
+                })
+                .sum::<Option<G1Projective>>()
+                .ok_or(ProofErrors::ProofVerificationError)
+        };
+        let total_w = sum(&self.w_terms)?;
+        let total_c = sum(&self.c_terms)? - opening_key.g * composer.variables[&self.g_scalar];
+
+        if opening_key.check_folded(total_w, total_c).is_err() {
+            return Err(ProofErrors::ProofVerificationError.into());
+        }
+        Ok(())
+    }
+}
+
+impl StandardComposer {
+    /// Verifies the `proof` of the circuit described by the `verifier_key` in
+    /// the circuit and returns its `KzgAccumulator`.
+    ///
+    /// The `transcript` must be keyed as the `PoseidonTranscript` of the
+    /// verifier, and the `pub_inputs` are the non-zero public inputs of the
+    /// proof, along with the index of their gate.
+    ///
+    /// # Panics
+    ///
+    /// If two public inputs share the same gate, or if a gate does not hold a
+    /// public input of the circuit described by the `verifier_key`.
+    pub fn verify_proof(
+        &mut self,
+        mut transcript: TranscriptGadget,
+        verifier_key: &VerifierKeyVariables,
+        proof: &ProofVariables,
+        pub_inputs: &[(usize, Variable)],
+    ) -> KzgAccumulator {
+        let domain = &verifier_key.domain;
+        let evaluations = &proof.evaluations;
+        let one = self.add_witness_to_circuit_description(BlsScalar::one());
+
+        // Mirrors `append_statement`, with the public inputs in the order of
+        // their gate
+        let verifier_key_digest = verifier_key.digest(self);
+        let mut sorted_pub_inputs = pub_inputs.to_vec();
+        sorted_pub_inputs.sort_by_key(|(index, _)| *index);
+        assert!(
+            sorted_pub_inputs
+                .windows(2)
+                .all(|pair| pair[0].0 != pair[1].0),
+            "public inputs must be held by distinct gates"
+        );
+        assert!(
+            sorted_pub_inputs
+                .iter()
+                .all(|(index, _)| verifier_key.public_input_gates.binary_search(index).is_ok()),
+            "public inputs must be held by the public input gates of the circuit"
+        );
+        transcript.append_message(self, b"dom-sep", PROTOCOL_VERSION);
+        transcript.append_scalar(self, b"vk_digest", verifier_key_digest);
+        transcript.append_constant(
+            self,
+            b"pi_count",
+            BlsScalar::from(sorted_pub_inputs.len() as u64),
+        );
+        for (index, pi) in sorted_pub_inputs.iter() {
+            transcript.append_constant(self, b"pi_index", BlsScalar::from(*index as u64));
+            transcript.append_scalar(self, b"pi", *pi);
+        }
+
+        // Add commitment to witness polynomials to transcript
+        transcript.append_commitment(self, b"w_l", &proof.a_comm);
+        transcript.append_commitment(self, b"w_r", &proof.b_comm);
+        transcript.append_commitment(self, b"w_o", &proof.c_comm);
+        transcript.append_commitment(self, b"w_4", &proof.d_comm);
+
+        // Compute table compression challenge
+        let zeta = transcript.challenge_scalar(self, b"zeta");
+        // Add commitment to lookup query and sorted polynomials to transcript
+        transcript.append_commitment(self, b"f", &proof.f_comm);
+        transcript.append_commitment(self, b"h_1", &proof.h_1_comm);
+        transcript.append_commitment(self, b"h_2", &proof.h_2_comm);
+
+        // Compute beta and gamma challenges
+        let beta = transcript.challenge_scalar(self, b"beta");
+        transcript.append_scalar(self, b"beta", beta);
+        let gamma = transcript.challenge_scalar(self, b"gamma");
+        // Compute delta and epsilon challenges
+        let delta = transcript.challenge_scalar(self, b"delta");
+        transcript.append_scalar(self, b"delta", delta);
+        let epsilon = transcript.challenge_scalar(self, b"epsilon");
+        // Add commitment to permutation polynomials to transcript
+        transcript.append_commitment(self, b"z", &proof.z_comm);
+        transcript.append_commitment(self, b"p", &proof.p_comm);
+
+        // Compute quotient challenge
+        let alpha = transcript.challenge_scalar(self, b"alpha");
+        let range_sep_challenge = transcript.challenge_scalar(self, b"range separation challenge");
+        let logic_sep_challenge = transcript.challenge_scalar(self, b"logic separation challenge");
+        let var_base_sep_challenge =
+            transcript.challenge_scalar(self, b"variable base separation challenge");
+        let lookup_sep_challenge =
+            transcript.challenge_scalar(self, b"lookup separation challenge");
+        let sbox_sep_challenge = transcript.challenge_scalar(self, b"sbox separation challenge");
+        let fixed_base_sep_challenge = verifier_key
+            .fixed_base
+            .as_ref()
+            .map(|_| transcript.challenge_scalar(self, b"custom gate separation challenge"));
+
+        // Add commitment to quotient polynomial to transcript
+        transcript.append_commitment(self, b"t_1", &proof.t_1_comm);
+        transcript.append_commitment(self, b"t_2", &proof.t_2_comm);
+        transcript.append_commitment(self, b"t_3", &proof.t_3_comm);
+        transcript.append_commitment(self, b"t_4", &proof.t_4_comm);
+
+        // Compute evaluation challenge
+        let z_challenge = transcript.challenge_scalar(self, b"z");
+
+        // Compute z^n by repeated squaring, as the domain size is a power of two
+        let mut z_n = z_challenge;
+        for _ in 0..domain.log_size_of_group {
+            z_n = self.product(z_n, z_n);
+        }
+
+        // Compute zero polynomial evaluated at `z_challenge`
+        let z_h_eval = self.linear_combination(&[(BlsScalar::one(), z_n)], -BlsScalar::one());
+
+        // Compute first lagrange polynomial evaluated at `z_challenge`
+        let n_fr = domain.size_as_field_element;
+        let l1_denominator = self.linear_combination(&[(n_fr, z_challenge)], -n_fr);
+        let l1_eval = self.quotient(z_h_eval, l1_denominator);
+
+        // Compute the public input polynomial evaluated at `z_challenge`
+        let pi_terms: Vec<_> = pub_inputs
+            .iter()
+            .map(|(index, pi)| {
+                let omega_inv_i = domain.group_gen_inv.pow(&[*index as u64, 0, 0, 0]);
+                let denominator =
+                    self.linear_combination(&[(omega_inv_i, z_challenge)], -BlsScalar::one());
+                (BlsScalar::one(), self.quotient(*pi, denominator))
+            })
+            .collect();
+        let pi_sum = self.linear_combination(&pi_terms, BlsScalar::zero());
+        let pi_eval = self.mul(
+            domain.size_inv,
+            pi_sum,
+            z_h_eval,
+            BlsScalar::zero(),
+            BlsScalar::zero(),
+        );
+
+        // Compute quotient polynomial evaluated at `z_challenge`
+        let alpha_sq = self.product(alpha, alpha);
+
+        // (a + beta * sigma_1 + gamma)(b + beta * sigma_2 + gamma)(c + beta * sigma_3 + gamma)
+        let sigma_0 = self.perm_factor(
+            evaluations.a_eval,
+            (BlsScalar::one(), beta, evaluations.left_sigma_eval),
+            gamma,
+        );
+        let sigma_1 = self.perm_factor(
+            evaluations.b_eval,
+            (BlsScalar::one(), beta, evaluations.right_sigma_eval),
+            gamma,
+        );
+        let sigma_2 = self.perm_factor(
+            evaluations.c_eval,
+            (BlsScalar::one(), beta, evaluations.out_sigma_eval),
+            gamma,
+        );
+        let sigma_product = self.product(sigma_0, sigma_1);
+        let sigma_product = self.product(sigma_product, sigma_2);
+
+        // ((d + gamma) * z_hat) * alpha
+        let d_gamma = self.linear_combination(
+            &[
+                (BlsScalar::one(), evaluations.d_eval),
+                (BlsScalar::one(), gamma),
+            ],
+            BlsScalar::zero(),
+        );
+        let b_3 = self.product(d_gamma, evaluations.perm_eval);
+        let b_3 = self.product(b_3, alpha);
+        let b = self.product(sigma_product, b_3);
+
+        // l_1(z) * alpha^2
+        let c = self.product(l1_eval, alpha_sq);
+
+        // (p_hat * (epsilon(1 + delta) + h_1 + delta * h_2)(epsilon(1 + delta) + h_2 + delta * h_1_next) * kappa
+        // + l_1(z) * kappa^2) * lookup_sep
+        let lookup_kappa = self.product(lookup_sep_challenge, lookup_sep_challenge);
+        let lookup_kappa_sq = self.product(lookup_kappa, lookup_kappa);
+        let epsilon_one_plus_delta = self.product_add(epsilon, delta, epsilon);
+        let h_0 = self.product_add(delta, evaluations.h_2_eval, evaluations.h_1_eval);
+        let h_0 = self.linear_combination(
+            &[
+                (BlsScalar::one(), h_0),
+                (BlsScalar::one(), epsilon_one_plus_delta),
+            ],
+            BlsScalar::zero(),
+        );
+        let h_1 = self.product_add(delta, evaluations.h_1_next_eval, evaluations.h_2_eval);
+        let h_1 = self.linear_combination(
+            &[
+                (BlsScalar::one(), h_1),
+                (BlsScalar::one(), epsilon_one_plus_delta),
+            ],
+            BlsScalar::zero(),
+        );
+        let d_0 = self.product(h_0, h_1);
+        let d_0 = self.product(d_0, evaluations.lookup_perm_eval);
+        let d_0 = self.product(d_0, lookup_kappa);
+        let l1_lookup_kappa_sq = self.product(l1_eval, lookup_kappa_sq);
+        let d = self.linear_combination(
+            &[
+                (BlsScalar::one(), d_0),
+                (BlsScalar::one(), l1_lookup_kappa_sq),
+            ],
+            BlsScalar::zero(),
+        );
+        let d = self.product(d, lookup_sep_challenge);
+
+        // (r + PI(z) - b - c - d) / z_h(z)
+        let t_numerator = self.linear_combination(
+            &[
+                (BlsScalar::one(), evaluations.lin_poly_eval),
+                (BlsScalar::one(), pi_eval),
+                (-BlsScalar::one(), b),
+                (-BlsScalar::one(), c),
+                (-BlsScalar::one(), d),
+            ],
+            BlsScalar::zero(),
+        );
+        let t_eval = self.quotient(t_numerator, z_h_eval);
+
+        // Compute commitment to quotient polynomial
+        let z_two_n = self.product(z_n, z_n);
+        let z_three_n = self.product(z_two_n, z_n);
+        let t_comm = vec![
+            (one, proof.t_1_comm),
+            (z_n, proof.t_2_comm),
+            (z_two_n, proof.t_3_comm),
+            (z_three_n, proof.t_4_comm),
+        ];
+
+        // Add evaluations to transcript
+        let mut append = |label: &'static [u8], eval| transcript.append_scalar(self, label, eval);
+        append(b"a_eval", evaluations.a_eval);
+        append(b"b_eval", evaluations.b_eval);
+        append(b"c_eval", evaluations.c_eval);
+        append(b"d_eval", evaluations.d_eval);
+        append(b"a_next_eval", evaluations.a_next_eval);
+        append(b"b_next_eval", evaluations.b_next_eval);
+        append(b"d_next_eval", evaluations.d_next_eval);
+        append(b"left_sig_eval", evaluations.left_sigma_eval);
+        append(b"right_sig_eval", evaluations.right_sigma_eval);
+        append(b"out_sig_eval", evaluations.out_sigma_eval);
+        append(b"q_arith_eval", evaluations.q_arith_eval);
+        append(b"q_c_eval", evaluations.q_c_eval);
+        append(b"perm_eval", evaluations.perm_eval);
+        append(b"f_eval", evaluations.f_eval);
+        append(b"h_1_eval", evaluations.h_1_eval);
+        append(b"h_1_next_eval", evaluations.h_1_next_eval);
+        append(b"h_2_eval", evaluations.h_2_eval);
+        append(b"table_eval", evaluations.table_eval);
+        append(b"table_next_eval", evaluations.table_next_eval);
+        append(b"lookup_perm_eval", evaluations.lookup_perm_eval);
+        append(b"t_eval", t_eval);
+        append(b"r_eval", evaluations.lin_poly_eval);
+
+        // Compute linearisation commitment
+        let mut r_comm = CommitmentTerms::new();
+        self.arithmetic_linearisation_terms(&mut r_comm, verifier_key, evaluations);
+        self.range_linearisation_terms(&mut r_comm, range_sep_challenge, verifier_key, evaluations);
+        self.logic_linearisation_terms(&mut r_comm, logic_sep_challenge, verifier_key, evaluations);
+        self.variable_base_linearisation_terms(
+            &mut r_comm,
+            var_base_sep_challenge,
+            verifier_key,
+            evaluations,
+        );
+        self.sbox_linearisation_terms(&mut r_comm, sbox_sep_challenge, verifier_key, evaluations);
+        if let (Some(selectors), Some(challenge)) =
+            (verifier_key.fixed_base.as_ref(), fixed_base_sep_challenge)
+        {
+            self.fixed_base_linearisation_terms(&mut r_comm, challenge, selectors, evaluations);
+        }
+
+        // Permutation
+        // (a_eval + beta * z + gamma)(b_eval + beta * z * k1 + gamma)(c_eval + beta * k2 * z + gamma)(d_eval + beta * k3 * z + gamma) * alpha
+        // + l1(z) * alpha^2
+        let q_0 = self.perm_factor(
+            evaluations.a_eval,
+            (BlsScalar::one(), beta, z_challenge),
+            gamma,
+        );
+        let q_1 = self.perm_factor(evaluations.b_eval, (K1, beta, z_challenge), gamma);
+        let q_2 = self.perm_factor(evaluations.c_eval, (K2, beta, z_challenge), gamma);
+        let q_3 = self.perm_factor(evaluations.d_eval, (K3, beta, z_challenge), gamma);
+        let x = self.product(q_0, q_1);
+        let x = self.product(x, q_2);
+        let x = self.product(x, q_3);
+        let x = self.product_add(x, alpha, c);
+        r_comm.push((x, proof.z_comm));
+        // -(a_eval + beta * sigma_1_eval + gamma)(b_eval + beta * sigma_2_eval + gamma)(c_eval + beta * sigma_3_eval + gamma) * beta * z_hat * alpha
+        let y = self.product(sigma_product, evaluations.perm_eval);
+        let y = self.product(y, alpha);
+        let y = self.mul(
+            -BlsScalar::one(),
+            y,
+            beta,
+            BlsScalar::zero(),
+            BlsScalar::zero(),
+        );
+        r_comm.push((y, verifier_key.fourth_sigma));
+
+        // Lookup
+        // (compress(a, b, c, q_c) - f) * lookup_sep
+        let compressed = self.product_add(zeta, evaluations.q_c_eval, evaluations.c_eval);
+        let compressed = self.product_add(zeta, compressed, evaluations.b_eval);
+        let compressed = self.product_add(zeta, compressed, evaluations.a_eval);
+        let compressed = self.linear_combination(
+            &[
+                (BlsScalar::one(), compressed),
+                (-BlsScalar::one(), evaluations.f_eval),
+            ],
+            BlsScalar::zero(),
+        );
+        let q_lookup_scalar = self.product(compressed, lookup_sep_challenge);
+        r_comm.push((q_lookup_scalar, verifier_key.q_lookup));
+        // ((1 + delta)(epsilon + f)(epsilon(1 + delta) + t + delta * t_next) * kappa + l1(z) * kappa^2) * lookup_sep
+        let one_plus_delta =
+            self.linear_combination(&[(BlsScalar::one(), delta)], BlsScalar::one());
+        let epsilon_f = self.linear_combination(
+            &[
+                (BlsScalar::one(), epsilon),
+                (BlsScalar::one(), evaluations.f_eval),
+            ],
+            BlsScalar::zero(),
+        );
+        let table = self.product_add(delta, evaluations.table_next_eval, evaluations.table_eval);
+        let table = self.linear_combination(
+            &[
+                (BlsScalar::one(), table),
+                (BlsScalar::one(), epsilon_one_plus_delta),
+            ],
+            BlsScalar::zero(),
+        );
+        let numerator = self.product(one_plus_delta, epsilon_f);
+        let numerator = self.product(numerator, table);
+        let p_scalar = self.product_add(numerator, lookup_kappa, l1_lookup_kappa_sq);
+        let p_scalar = self.product(p_scalar, lookup_sep_challenge);
+        r_comm.push((p_scalar, proof.p_comm));
+
+        // Compute commitment to the compressed lookup table
+        let zeta_sq = self.product(zeta, zeta);
+        let zeta_cu = self.product(zeta_sq, zeta);
+        let table_comm = vec![
+            (one, verifier_key.table_1),
+            (zeta, verifier_key.table_2),
+            (zeta_sq, verifier_key.table_3),
+            (zeta_cu, verifier_key.table_4),
+        ];
+
+        // Compose the aggregated proof and flatten it with the opening challenge
+        let (eval_a, c_a) = self.flatten(
+            &mut transcript,
+            vec![
+                (t_eval, t_comm),
+                (evaluations.lin_poly_eval, r_comm),
+                (evaluations.a_eval, vec![(one, proof.a_comm)]),
+                (evaluations.b_eval, vec![(one, proof.b_comm)]),
+                (evaluations.c_eval, vec![(one, proof.c_comm)]),
+                (evaluations.d_eval, vec![(one, proof.d_comm)]),
+                (
+                    evaluations.left_sigma_eval,
+                    vec![(one, verifier_key.left_sigma)],
+                ),
+                (
+                    evaluations.right_sigma_eval,
+                    vec![(one, verifier_key.right_sigma)],
+                ),
+                (
+                    evaluations.out_sigma_eval,
+                    vec![(one, verifier_key.out_sigma)],
+                ),
+                (evaluations.f_eval, vec![(one, proof.f_comm)]),
+                (evaluations.h_1_eval, vec![(one, proof.h_1_comm)]),
+                (evaluations.h_2_eval, vec![(one, proof.h_2_comm)]),
+                (evaluations.table_eval, table_comm.clone()),
+                // The selector evaluations consumed by the linearisation
+                (evaluations.q_arith_eval, vec![(one, verifier_key.q_arith)]),
+                (evaluations.q_c_eval, vec![(one, verifier_key.q_c)]),
+            ],
+        );
+
+        // Compose the shifted aggregate proof
+        let (eval_b, c_b) = self.flatten(
+            &mut transcript,
+            vec![
+                (evaluations.perm_eval, vec![(one, proof.z_comm)]),
+                (evaluations.a_next_eval, vec![(one, proof.a_comm)]),
+                (evaluations.b_next_eval, vec![(one, proof.b_comm)]),
+                (evaluations.d_next_eval, vec![(one, proof.d_comm)]),
+                (evaluations.lookup_perm_eval, vec![(one, proof.p_comm)]),
+                (evaluations.h_1_next_eval, vec![(one, proof.h_1_comm)]),
+                (evaluations.table_next_eval, table_comm),
+            ],
+        );
+
+        // Add commitment to openings to transcript
+        transcript.append_commitment(self, b"w_z", &proof.w_z_comm);
+        transcript.append_commitment(self, b"w_z_w", &proof.w_zw_comm);
+
+        // Fold the openings at `z_challenge` and at its shift
+        let u = transcript.challenge_scalar(self, b"batch");
+        let z_omega = self.mul(
+            domain.group_gen,
+            z_challenge,
+            one,
+            BlsScalar::zero(),
+            BlsScalar::zero(),
+        );
+        let u_z_omega = self.product(u, z_omega);
+
+        let mut c_terms = c_a;
+        c_terms.push((z_challenge, proof.w_z_comm));
+        for (scalar, commitment) in c_b {
+            c_terms.push((self.product(scalar, u), commitment));
+        }
+        c_terms.push((u_z_omega, proof.w_zw_comm));
+
+        KzgAccumulator {
+            w_terms: vec![(one, proof.w_z_comm), (u, proof.w_zw_comm)],
+            c_terms,
+            g_scalar: self.product_add(u, eval_b, eval_a),
+        }
+    }
+
+    /// Mirrors `AggregateProof::flatten` on the `parts` of an aggregate proof,
+    /// returning the flattened evaluation and commitment.
+    fn flatten(
+        &mut self,
+        transcript: &mut TranscriptGadget,
+        parts: Vec<(Variable, CommitmentTerms)>,
+    ) -> (Variable, CommitmentTerms) {
+        let challenge = transcript.challenge_scalar(self, b"aggregate_witness");
+
+        let mut eval = self.zero_var;
+        let mut commitment = CommitmentTerms::new();
+        let mut power = None;
+        for (part_eval, part_commitment) in parts {
+            match power {
+                // The first part is not scaled
+                None => {
+                    eval = part_eval;
+                    commitment.extend(part_commitment);
+                    power = Some(challenge);
+                }
+                Some(challenge_power) => {
+                    eval = self.product_add(challenge_power, part_eval, eval);
+                    for (scalar, point) in part_commitment {
+                        commitment.push((self.product(scalar, challenge_power), point));
+                    }
+                    power = Some(self.product(challenge_power, challenge));
+                }
+            }
+        }
+        (eval, commitment)
+    }
+
+    fn arithmetic_linearisation_terms(
+        &mut self,
+        terms: &mut CommitmentTerms,
+        verifier_key: &VerifierKeyVariables,
+        evaluations: &EvaluationVariables,
+    ) {
+        let q_arith_eval = evaluations.q_arith_eval;
+
+        let a_b = self.product(evaluations.a_eval, evaluations.b_eval);
+        terms.push((self.product(a_b, q_arith_eval), verifier_key.q_m));
+        terms.push((
+            self.product(evaluations.a_eval, q_arith_eval),
+            verifier_key.q_l,
+        ));
+        terms.push((
+            self.product(evaluations.b_eval, q_arith_eval),
+            verifier_key.q_r,
+        ));
+        terms.push((
+            self.product(evaluations.c_eval, q_arith_eval),
+            verifier_key.q_o,
+        ));
+        terms.push((
+            self.product(evaluations.d_eval, q_arith_eval),
+            verifier_key.q_4,
+        ));
+        terms.push((q_arith_eval, verifier_key.q_c));
+    }
+
+    fn range_linearisation_terms(
+        &mut self,
+        terms: &mut CommitmentTerms,
+        range_separation_challenge: Variable,
+        verifier_key: &VerifierKeyVariables,
+        evaluations: &EvaluationVariables,
+    ) {
+        let four = BlsScalar::from(4);
+
+        let kappa = self.product(range_separation_challenge, range_separation_challenge);
+        let kappa_sq = self.product(kappa, kappa);
+        let kappa_cu = self.product(kappa_sq, kappa);
+
+        let quads = [
+            (evaluations.c_eval, evaluations.d_eval),
+            (evaluations.b_eval, evaluations.c_eval),
+            (evaluations.a_eval, evaluations.b_eval),
+            (evaluations.d_next_eval, evaluations.a_eval),
+        ];
+        let mut deltas = [self.zero_var; 4];
+        for (delta, (high, low)) in deltas.iter_mut().zip(quads.iter()) {
+            let quad = self.linear_combination(
+                &[(BlsScalar::one(), *high), (-four, *low)],
+                BlsScalar::zero(),
+            );
+            *delta = self.quad_delta(quad);
+        }
+
+        let b_2 = self.product(deltas[1], kappa);
+        let b_3 = self.product(deltas[2], kappa_sq);
+        let b_4 = self.product(deltas[3], kappa_cu);
+        let sum = self.linear_combination(
+            &[
+                (BlsScalar::one(), deltas[0]),
+                (BlsScalar::one(), b_2),
+                (BlsScalar::one(), b_3),
+                (BlsScalar::one(), b_4),
+            ],
+            BlsScalar::zero(),
+        );
+        terms.push((
+            self.product(sum, range_separation_challenge),
+            verifier_key.q_range,
+        ));
+    }
+
+    fn logic_linearisation_terms(
+        &mut self,
+        terms: &mut CommitmentTerms,
+        logic_separation_challenge: Variable,
+        verifier_key: &VerifierKeyVariables,
+        evaluations: &EvaluationVariables,
+    ) {
+        let one = BlsScalar::one();
+        let four = BlsScalar::from(4);
+
+        let kappa = self.product(logic_separation_challenge, logic_separation_challenge);
+        let kappa_sq = self.product(kappa, kappa);
+        let kappa_cu = self.product(kappa_sq, kappa);
+        let kappa_qu = self.product(kappa_cu, kappa);
+
+        let a = self.linear_combination(
+            &[(one, evaluations.a_next_eval), (-four, evaluations.a_eval)],
+            BlsScalar::zero(),
+        );
+        let c_0 = self.quad_delta(a);
+
+        let b = self.linear_combination(
+            &[(one, evaluations.b_next_eval), (-four, evaluations.b_eval)],
+            BlsScalar::zero(),
+        );
+        let c_1 = self.quad_delta(b);
+        let c_1 = self.product(c_1, kappa);
+
+        let d = self.linear_combination(
+            &[(one, evaluations.d_next_eval), (-four, evaluations.d_eval)],
+            BlsScalar::zero(),
+        );
+        let c_2 = self.quad_delta(d);
+        let c_2 = self.product(c_2, kappa_sq);
+
+        let w = evaluations.c_eval;
+        let a_b = self.product(a, b);
+        let c_3 = self.linear_combination(&[(one, w), (-one, a_b)], BlsScalar::zero());
+        let c_3 = self.product(c_3, kappa_cu);
+
+        // F = w[w(4w - 18(a+b) + 81) + 18(a^2 + b^2) - 81(a+b) + 83]
+        let eighteen = BlsScalar::from(18);
+        let eighty_one = BlsScalar::from(81);
+        let f = self.linear_combination(&[(four, w), (-eighteen, a), (-eighteen, b)], eighty_one);
+        let f = self.product(w, f);
+        let a_sq = self.product(a, a);
+        let b_sq = self.product(b, b);
+        let f = self.linear_combination(
+            &[
+                (one, f),
+                (eighteen, a_sq),
+                (eighteen, b_sq),
+                (-eighty_one, a),
+                (-eighty_one, b),
+            ],
+            BlsScalar::from(83),
+        );
+        let f = self.product(w, f);
+        // B = q_c * [9d - 3(a+b)]
+        let three = BlsScalar::from(3);
+        let b_term = self.linear_combination(
+            &[(BlsScalar::from(9), d), (-three, a), (-three, b)],
+            BlsScalar::zero(),
+        );
+        let b_term = self.product(evaluations.q_c_eval, b_term);
+        // B + E, with E = 3(a + b + d) - 2F
+        let c_4 = self.linear_combination(
+            &[
+                (one, b_term),
+                (three, a),
+                (three, b),
+                (three, d),
+                (-BlsScalar::from(2), f),
+            ],
+            BlsScalar::zero(),
+        );
+        let c_4 = self.product(c_4, kappa_qu);
+
+        let sum = self.linear_combination(
+            &[(one, c_0), (one, c_1), (one, c_2), (one, c_3), (one, c_4)],
+            BlsScalar::zero(),
+        );
+        terms.push((
+            self.product(sum, logic_separation_challenge),
+            verifier_key.q_logic,
+        ));
+    }
+
+    /// Mirrors the coefficients of `FixedBaseGate`, pushing them against the
+    /// `selectors` of the gate.
+    fn fixed_base_linearisation_terms(
+        &mut self,
+        terms: &mut CommitmentTerms,
+        ecc_separation_challenge: Variable,
+        selectors: &[CommitmentVariable],
+        evaluations: &EvaluationVariables,
+    ) {
+        let one = BlsScalar::one();
+
+        let kappa = self.product(ecc_separation_challenge, ecc_separation_challenge);
+        let kappa_sq = self.product(kappa, kappa);
+        let kappa_cu = self.product(kappa_sq, kappa);
+
+        let acc_x = evaluations.a_eval;
+        let acc_x_next = evaluations.a_next_eval;
+        let acc_y = evaluations.b_eval;
+        let acc_y_next = evaluations.b_next_eval;
+        let xy_alpha = evaluations.c_eval;
+
+        // bit = next - 4 * current
+        let bit = self.linear_combination(
+            &[
+                (one, evaluations.d_next_eval),
+                (-BlsScalar::from(4), evaluations.d_eval),
+            ],
+            BlsScalar::zero(),
+        );
+        let bit_sq = self.product(bit, bit);
+        let bit_cu = self.product(bit_sq, bit);
+
+        // Check bit consistency: (bit^2 - 1) * (bit^2 - 9)
+        let bit_consistency = self.big_mul(
+            one,
+            bit_sq,
+            bit_sq,
+            Some((-BlsScalar::from(10), bit_sq)),
+            BlsScalar::from(9),
+            BlsScalar::zero(),
+        );
+
+        let xy_acc = self.product(xy_alpha, acc_x);
+        let d_xy_acc = self.mul(
+            EDWARDS_D,
+            xy_acc,
+            acc_y,
+            BlsScalar::zero(),
+            BlsScalar::zero(),
+        );
+
+        // Parts of the accumulator consistency checks which do not depend on
+        // the added point
+        let x_acc_consistency = self.product_add(acc_x_next, d_xy_acc, acc_x_next);
+        let x_acc_consistency = self.product(x_acc_consistency, kappa_sq);
+        let y_acc_consistency = self.big_mul(
+            -one,
+            acc_y_next,
+            d_xy_acc,
+            Some((one, acc_y_next)),
+            BlsScalar::zero(),
+            BlsScalar::zero(),
+        );
+        let y_acc_consistency = self.product(y_acc_consistency, kappa_cu);
+        let xy_consistency = self.product(xy_alpha, kappa);
+
+        let flag = self.linear_combination(
+            &[
+                (one, bit_consistency),
+                (-one, xy_consistency),
+                (one, x_acc_consistency),
+                (one, y_acc_consistency),
+            ],
+            BlsScalar::zero(),
+        );
+
+        // Factors of the coordinates of the added point, scaled by the
+        // separation challenge
+        let x_alpha_factor = self.product(kappa_sq, acc_y);
+        let x_alpha_factor = self.product_add(kappa_cu, acc_x, x_alpha_factor);
+        let x_alpha_factor = self.mul(
+            -one,
+            x_alpha_factor,
+            ecc_separation_challenge,
+            BlsScalar::zero(),
+            BlsScalar::zero(),
+        );
+        let y_alpha_factor = self.product(kappa_sq, acc_x);
+        let y_alpha_factor = self.product_add(kappa_cu, acc_y, y_alpha_factor);
+        let y_alpha_factor = self.mul(
+            -one,
+            y_alpha_factor,
+            ecc_separation_challenge,
+            BlsScalar::zero(),
+            BlsScalar::zero(),
+        );
+        let kappa_sep = self.product(kappa, ecc_separation_challenge);
+
+        let coefficients = [
+            self.product(flag, ecc_separation_challenge),
+            self.product(bit, x_alpha_factor),
+            y_alpha_factor,
+            self.product(bit, kappa_sep),
+            self.product(bit_cu, x_alpha_factor),
+            self.product(bit_sq, y_alpha_factor),
+            self.product(bit_cu, kappa_sep),
+        ];
+        terms.extend(coefficients.iter().copied().zip(selectors.iter().copied()));
+    }
+
+    fn variable_base_linearisation_terms(
+        &mut self,
+        terms: &mut CommitmentTerms,
+        curve_add_separation_challenge: Variable,
+        verifier_key: &VerifierKeyVariables,
+        evaluations: &EvaluationVariables,
+    ) {
+        let one = BlsScalar::one();
+
+        let kappa = self.product(
+            curve_add_separation_challenge,
+            curve_add_separation_challenge,
+        );
+        let kappa_sq = self.product(kappa, kappa);
+
+        let x_1 = evaluations.a_eval;
+        let x_3 = evaluations.a_next_eval;
+        let y_1 = evaluations.b_eval;
+        let y_3 = evaluations.b_next_eval;
+        let x_2 = evaluations.c_eval;
+        let y_2 = evaluations.d_eval;
+        let x1_y2 = evaluations.d_next_eval;
+
+        // Check x1 * y2 is correct
+        let xy_consistency = self.big_mul(
+            one,
+            x_1,
+            y_2,
+            Some((-one, x1_y2)),
+            BlsScalar::zero(),
+            BlsScalar::zero(),
+        );
+
+        let y1_x2 = self.product(y_1, x_2);
+        let y1_y2 = self.product(y_1, y_2);
+        let x1_x2 = self.product(x_1, x_2);
+        let d_x1_y2_y1_x2 = self.mul(
+            EDWARDS_D,
+            x1_y2,
+            y1_x2,
+            BlsScalar::zero(),
+            BlsScalar::zero(),
+        );
+
+        // Check x_3 is correct
+        let x3_rhs = self.product_add(x_3, d_x1_y2_y1_x2, x_3);
+        let x3_consistency = self.linear_combination(
+            &[(one, x1_y2), (one, y1_x2), (-one, x3_rhs)],
+            BlsScalar::zero(),
+        );
+        let x3_consistency = self.product(x3_consistency, kappa);
+
+        // Check y_3 is correct
+        let y3_rhs = self.big_mul(
+            -one,
+            y_3,
+            d_x1_y2_y1_x2,
+            Some((one, y_3)),
+            BlsScalar::zero(),
+            BlsScalar::zero(),
+        );
+        let y3_consistency = self.linear_combination(
+            &[(one, y1_y2), (one, x1_x2), (-one, y3_rhs)],
+            BlsScalar::zero(),
+        );
+        let y3_consistency = self.product(y3_consistency, kappa_sq);
+
+        let sum = self.linear_combination(
+            &[
+                (one, xy_consistency),
+                (one, x3_consistency),
+                (one, y3_consistency),
+            ],
+            BlsScalar::zero(),
+        );
+        terms.push((
+            self.product(sum, curve_add_separation_challenge),
+            verifier_key.q_variable_group_add,
+        ));
+    }
+
+    fn sbox_linearisation_terms(
+        &mut self,
+        terms: &mut CommitmentTerms,
+        sbox_separation_challenge: Variable,
+        verifier_key: &VerifierKeyVariables,
+        evaluations: &EvaluationVariables,
+    ) {
+        let one = BlsScalar::one();
+
+        let kappa = self.product(sbox_separation_challenge, sbox_separation_challenge);
+
+        let x = self.linear_combination(
+            &[(one, evaluations.a_eval), (one, evaluations.q_c_eval)],
+            BlsScalar::zero(),
+        );
+
+        // Check the fourth wire holds the square of the shifted input
+        let square_consistency = self.big_mul(
+            -one,
+            x,
+            x,
+            Some((one, evaluations.d_eval)),
+            BlsScalar::zero(),
+            BlsScalar::zero(),
+        );
+
+        // Check the output holds the fifth power of the shifted input
+        let d_sq = self.product(evaluations.d_eval, evaluations.d_eval);
+        let quint_consistency = self.big_mul(
+            -one,
+            d_sq,
+            x,
+            Some((one, evaluations.c_eval)),
+            BlsScalar::zero(),
+            BlsScalar::zero(),
+        );
+        let sum = self.product_add(quint_consistency, kappa, square_consistency);
+
+        terms.push((
+            self.product(sum, sbox_separation_challenge),
+            verifier_key.q_sbox,
+        ));
+    }
+
+    /// Returns `a * b`.
+    fn product(&mut self, a: Variable, b: Variable) -> Variable {
+        self.mul(BlsScalar::one(), a, b, BlsScalar::zero(), BlsScalar::zero())
+    }
+
+    /// Returns `a * b + c`.
+    fn product_add(&mut self, a: Variable, b: Variable, c: Variable) -> Variable {
+        self.big_mul(
+            BlsScalar::one(),
+            a,
+            b,
+            Some((BlsScalar::one(), c)),
+            BlsScalar::zero(),
+            BlsScalar::zero(),
+        )
+    }
+
+    /// Returns `w + k * beta * s + gamma`.
+    fn perm_factor(
+        &mut self,
+        w: Variable,
+        (k, beta, s): (BlsScalar, Variable, Variable),
+        gamma: Variable,
+    ) -> Variable {
+        let w_beta_s = self.big_mul(
+            k,
+            beta,
+            s,
+            Some((BlsScalar::one(), w)),
+            BlsScalar::zero(),
+            BlsScalar::zero(),
+        );
+        self.linear_combination(
+            &[(BlsScalar::one(), w_beta_s), (BlsScalar::one(), gamma)],
+            BlsScalar::zero(),
+        )
+    }
+
+    /// Returns `f(f - 1)(f - 2)(f - 3)`, which is zero iff `f` is a quad.
+    fn quad_delta(&mut self, f: Variable) -> Variable {
+        // f^2 - f
+        let f_f_1 = self.big_mul(
+            BlsScalar::one(),
+            f,
+            f,
+            Some((-BlsScalar::one(), f)),
+            BlsScalar::zero(),
+            BlsScalar::zero(),
+        );
+        // (f - 2)(f - 3) = f^2 - f - 4f + 6
+        let f_2_f_3 = self.linear_combination(
+            &[(BlsScalar::one(), f_f_1), (-BlsScalar::from(4), f)],
+            BlsScalar::from(6),
+        );
+        self.product(f_f_1, f_2_f_3)
+    }
+
+    /// Returns the sum of the `terms` and the `constant`, using a gate for
+    /// every two terms after the first three.
+    fn linear_combination(
+        &mut self,
+        terms: &[(BlsScalar, Variable)],
+        constant: BlsScalar,
+    ) -> Variable {
+        let zero_term = (BlsScalar::zero(), self.zero_var);
+        let mut terms = terms.iter().copied();
+
+        let first = terms.next().unwrap_or(zero_term);
+        let second = terms.next().unwrap_or(zero_term);
+        let mut sum = self.big_add(first, second, terms.next(), constant, BlsScalar::zero());
+
+        let rest: Vec<_> = terms.collect();
+        for pair in rest.chunks(2) {
+            sum = self.big_add(
+                (BlsScalar::one(), sum),
+                pair[0],
+                pair.get(1).copied(),
+                BlsScalar::zero(),
+                BlsScalar::zero(),
+            );
+        }
+        sum
+    }
+
+    /// Returns `numerator / denominator`, constraining it with a single gate.
+    /// The quotient is left unconstrained if the denominator is zero.
+    fn quotient(&mut self, numerator: Variable, denominator: Variable) -> Variable {
+        let quotient = self.add_input(BlsScalar::zero());
+        self.add_witness_generator(move |composer| {
+            let denominator_inv: Option<BlsScalar> =
+                composer.variables[&denominator].invert().into();
+            let value =
+                composer.variables[&numerator] * denominator_inv.unwrap_or_else(BlsScalar::zero);
+            composer.variables.insert(quotient, value);
+        });
+        self.poly_gate(
+            quotient,
+            denominator,
+            numerator,
+            BlsScalar::one(),
+            BlsScalar::zero(),
+            BlsScalar::zero(),
+            -BlsScalar::one(),
+            BlsScalar::zero(),
+            BlsScalar::zero(),
+        );
+        quotient
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::commitment_scheme::kzg10::PublicParameters;
+    use crate::constraint_system::ecc::scalar_mul::fixed_base::scalar_mul;
+    use crate::constraint_system::helper::{dummy_gadget, gadget_tester};
+    use crate::proof_system::{Prover, Verifier};
+    use crate::transcript::{PoseidonTranscript, TranscriptProtocol};
+    use dusk_jubjub::GENERATOR;
+    use rand::rngs::StdRng;
+    use rand::SeedableRng;
+
+    // Inner circuit with a public input
+    fn inner_gadget(composer: &mut StandardComposer) {
+        dummy_gadget(5, composer);
+        let var_one = composer.add_input(BlsScalar::one());
+        composer.constrain_to_constant(var_one, BlsScalar::zero(), -BlsScalar::one());
+
+        // Fixed base gates, held by the built-in custom gate
+        let scalar = composer.add_input(BlsScalar::from(123456789u64));
+        scalar_mul(composer, scalar, GENERATOR.into());
+    }
+
+    // Proves the inner circuit with the parameters of a fixed seed, and
+    // returns the proof along with its public inputs, the verifier of the
+    // circuit and the opening key
+    fn inner_proof() -> (
+        Proof,
+        Vec<(usize, BlsScalar)>,
+        Verifier<PoseidonTranscript>,
+        OpeningKey,
+    ) {
+        let public_parameters =
+            PublicParameters::setup(2 * 512, &mut StdRng::seed_from_u64(0)).unwrap();
+        let (ck, opening_key) = public_parameters.trim(2 * 256).unwrap();
+
+        let mut prover = Prover::with_transcript(PoseidonTranscript::new(b"demo"));
+        inner_gadget(prover.mut_cs());
+        prover.preprocess(&ck).unwrap();
+        let public_inputs = prover.cs.sparse_public_inputs();
+        let proof = prover.prove(&ck).unwrap();
+
+        let mut verifier = Verifier::with_transcript(PoseidonTranscript::new(b"demo"));
+        inner_gadget(verifier.mut_cs());
+        verifier.preprocess(&ck).unwrap();
+
+        (proof, public_inputs, verifier, opening_key)
+    }
+
+    #[test]
+    fn test_verify_proof_gadget() {
+        let (proof, public_inputs, verifier, opening_key) = inner_proof();
+        let verifier_key = verifier.verifier_key.as_ref().unwrap();
+
+        let verify_in_circuit = |proof: &Proof| {
+            let mut composer = StandardComposer::new();
+            let transcript = TranscriptGadget::new(&mut composer, b"demo");
+            let verifier_key = VerifierKeyVariables::new(&mut composer, verifier_key).unwrap();
+            let proof = ProofVariables::new(&mut composer, proof);
+            let pub_inputs: Vec<_> = public_inputs
+                .iter()
+                .map(|(index, pi)| (*index, composer.add_input(*pi)))
+                .collect();
+            let accumulator = composer.verify_proof(transcript, &verifier_key, &proof, &pub_inputs);
+            (composer, accumulator)
+        };
+
+        let (composer, accumulator) = verify_in_circuit(&proof);
+        assert!(composer.check_satisfied().is_ok());
+        assert!(accumulator.check(&composer, &opening_key).is_ok());
+
+        // The accumulator matches the pairing inputs of the native verifier
+        let (total_w, total_c) = proof
+            .fold_openings(
+                verifier_key,
+                &mut verifier.preprocessed_transcript.clone(),
+                &opening_key,
+                &public_inputs,
+            )
+            .unwrap();
+        let sum = |terms: &[(Variable, CommitmentVariable)]| -> G1Projective {
+            terms
+                .iter()
+                .map(|(scalar, commitment)| {
+                    commitment.value(&composer).unwrap() * composer.variables[scalar]
+                })
+                .sum()
+        };
+        assert_eq!(sum(accumulator.w_terms()), total_w);
+        assert_eq!(
+            sum(accumulator.c_terms())
+                - opening_key.g * composer.variables[&accumulator.g_scalar()],
+            total_c
+        );
+
+        // A proof with a wrong evaluation yields an accumulator that fails the check
+        let mut bad_proof = proof;
+        bad_proof.evaluations.a_eval += BlsScalar::one();
+        let (composer, accumulator) = verify_in_circuit(&bad_proof);
+        assert!(composer.check_satisfied().is_ok());
+        assert!(accumulator.check(&composer, &opening_key).is_err());
+    }
+
+    #[test]
+    fn test_prove_verify_proof_gadget() {
+        // The outer circuit verifying the inner proof can itself be proven
+        let res = gadget_tester(
+            |composer| {
+                let (proof, public_inputs, verifier, _) = inner_proof();
+                let verifier_key = verifier.verifier_key.as_ref().unwrap();
+                let transcript = TranscriptGadget::new(composer, b"demo");
+                let verifier_key = VerifierKeyVariables::new(composer, verifier_key).unwrap();
+                let proof = ProofVariables::new(composer, &proof);
+                let pub_inputs: Vec<_> = public_inputs
+                    .iter()
+                    .map(|(index, pi)| (*index, composer.add_input(*pi)))
+                    .collect();
+                composer.verify_proof(transcript, &verifier_key, &proof, &pub_inputs);
+            },
+            1 << 16,
+        );
+        assert!(res.is_ok());
+    }
+
+    #[test]
+    #[should_panic(expected = "distinct gates")]
+    fn test_verify_proof_gadget_duplicate_pub_inputs() {
+        let (proof, public_inputs, verifier, _) = inner_proof();
+        let verifier_key = verifier.verifier_key.as_ref().unwrap();
+
+        let mut composer = StandardComposer::new();
+        let transcript = TranscriptGadget::new(&mut composer, b"demo");
+        let verifier_key = VerifierKeyVariables::new(&mut composer, verifier_key).unwrap();
+        let proof = ProofVariables::new(&mut composer, &proof);
+        let (index, pi) = public_inputs[0];
+        let pi = composer.add_input(pi);
+        composer.verify_proof(
+            transcript,
+            &verifier_key,
+            &proof,
+            &[(index, pi), (index, pi)],
+        );
+    }
+
+    #[test]
+    #[should_panic(expected = "public input gates")]
+    fn test_verify_proof_gadget_invalid_pub_input_gate() {
+        let (proof, public_inputs, verifier, _) = inner_proof();
+        let verifier_key = verifier.verifier_key.as_ref().unwrap();
+
+        let mut composer = StandardComposer::new();
+        let transcript = TranscriptGadget::new(&mut composer, b"demo");
+        let verifier_key = VerifierKeyVariables::new(&mut composer, verifier_key).unwrap();
+        let proof = ProofVariables::new(&mut composer, &proof);
+        let (index, pi) = public_inputs[0];
+        let pi = composer.add_input(pi);
+        composer.verify_proof(transcript, &verifier_key, &proof, &[(index + 1, pi)]);
+    }
+}
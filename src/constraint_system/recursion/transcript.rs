@@ -0,0 +1,187 @@
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this
+// file, You can obtain one at http://mozilla.org/MPL/2.0/.
+//
+// Copyright (c) DUSK NETWORK. All rights reserved.
+
+use super::CommitmentVariable;
+use crate::constraint_system::{StandardComposer, Variable};
+use crate::poseidon::{Poseidon, RATE, WIDTH};
+use dusk_bls12_381::BlsScalar;
+
+/// Bytes of a message absorbed in each scalar.
+pub(super) const CHUNK_SIZE: usize = 31;
+
+/// In-circuit version of the `PoseidonTranscript`.
+///
+/// Every method mirrors the `TranscriptProtocol` method of the same name, so
+/// a circuit replaying the operations of a verifier derives the same
+/// challenges as the `PoseidonTranscript` does.
+#[derive(Debug, Clone)]
+pub struct TranscriptGadget {
+    poseidon: Poseidon,
+    state: [Variable; WIDTH],
+    // Number of elements absorbed since the last permutation
+    absorbed: usize,
+}
+
+impl TranscriptGadget {
+    /// Creates a new transcript with the given `label`.
+    pub fn new(composer: &mut StandardComposer, label: &'static [u8]) -> Self {
+        let mut transcript = TranscriptGadget {
+            poseidon: Poseidon::new(),
+            state: [composer.zero_var; WIDTH],
+            absorbed: 0,
+        };
+        transcript.append_message(composer, b"dom-sep", label);
+        transcript
+    }
+
+    /// Append a constant `message` with the given `label`.
+    pub fn append_message(
+        &mut self,
+        composer: &mut StandardComposer,
+        label: &'static [u8],
+        message: &[u8],
+    ) {
+        self.absorb_bytes(composer, label);
+        self.absorb_bytes(composer, message);
+    }
+
+    /// Append a `commitment` with the given `label`.
+    pub fn append_commitment(
+        &mut self,
+        composer: &mut StandardComposer,
+        label: &'static [u8],
+        commitment: &CommitmentVariable,
+    ) {
+        self.absorb_bytes(composer, label);
+        self.absorb_constant(composer, BlsScalar::from(CommitmentVariable::SIZE as u64));
+        for chunk in commitment.chunks.iter() {
+            self.absorb(composer, *chunk);
+        }
+    }
+
+    /// Append a scalar `Variable` with the given `label`.
+    pub fn append_scalar(
+        &mut self,
+        composer: &mut StandardComposer,
+        label: &'static [u8],
+        s: Variable,
+    ) {
+        self.absorb_bytes(composer, label);
+        self.absorb(composer, s);
+    }
+
+    /// Append a constant scalar with the given `label`.
+    pub fn append_constant(
+        &mut self,
+        composer: &mut StandardComposer,
+        label: &'static [u8],
+        s: BlsScalar,
+    ) {
+        self.absorb_bytes(composer, label);
+        self.absorb_constant(composer, s);
+    }
+
+    /// Compute a `label`ed challenge variable.
+    pub fn challenge_scalar(
+        &mut self,
+        composer: &mut StandardComposer,
+        label: &'static [u8],
+    ) -> Variable {
+        self.absorb_bytes(composer, label);
+        self.permute(composer);
+        self.state[1]
+    }
+
+    /// Append domain separator for the circuit size.
+    pub fn circuit_domain_sep(&mut self, composer: &mut StandardComposer, n: u64) {
+        self.append_message(composer, b"dom-sep", b"circuit_size");
+        self.absorb_bytes(composer, b"n");
+        self.absorb_constant(composer, BlsScalar::from(n));
+    }
+
+    fn absorb(&mut self, composer: &mut StandardComposer, var: Variable) {
+        self.absorb_term(composer, (BlsScalar::one(), var), BlsScalar::zero());
+    }
+
+    fn absorb_constant(&mut self, composer: &mut StandardComposer, constant: BlsScalar) {
+        let zero_var = composer.zero_var;
+        self.absorb_term(composer, (BlsScalar::zero(), zero_var), constant);
+    }
+
+    /// Adds `q * var + constant` to the rate part of the state.
+    fn absorb_term(
+        &mut self,
+        composer: &mut StandardComposer,
+        term: (BlsScalar, Variable),
+        constant: BlsScalar,
+    ) {
+        if self.absorbed == RATE {
+            self.permute(composer);
+        }
+        let state = &mut self.state[1 + self.absorbed];
+        *state = composer.add(
+            (BlsScalar::one(), *state),
+            term,
+            constant,
+            BlsScalar::zero(),
+        );
+        self.absorbed += 1;
+    }
+
+    /// Absorbs the length of the constant `bytes` followed by its chunks.
+    fn absorb_bytes(&mut self, composer: &mut StandardComposer, bytes: &[u8]) {
+        self.absorb_constant(composer, BlsScalar::from(bytes.len() as u64));
+        for chunk in bytes.chunks(CHUNK_SIZE) {
+            self.absorb_constant(composer, chunk_to_scalar(chunk));
+        }
+    }
+
+    fn permute(&mut self, composer: &mut StandardComposer) {
+        self.state = composer.poseidon_permutation(&self.poseidon, self.state);
+        self.absorbed = 0;
+    }
+}
+
+/// Reads a chunk of at most `CHUNK_SIZE` bytes as a scalar.
+pub(super) fn chunk_to_scalar(chunk: &[u8]) -> BlsScalar {
+    let mut buf = [0u8; 32];
+    buf[..chunk.len()].copy_from_slice(chunk);
+    // 31 bytes are always below the modulus
+    BlsScalar::from_bytes(&buf).unwrap()
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::transcript::{PoseidonTranscript, TranscriptProtocol};
+
+    #[test]
+    fn test_challenges_match_poseidon_transcript() {
+        let mut composer = StandardComposer::new();
+        let mut transcript = PoseidonTranscript::new(b"test");
+        let mut gadget = TranscriptGadget::new(&mut composer, b"test");
+
+        transcript.append_message(b"message", b"a message longer than a single chunk");
+        gadget.append_message(
+            &mut composer,
+            b"message",
+            b"a message longer than a single chunk",
+        );
+        transcript.append_scalar(b"s", &BlsScalar::from(42));
+        let s = composer.add_input(BlsScalar::from(42));
+        gadget.append_scalar(&mut composer, b"s", s);
+        transcript.circuit_domain_sep(8);
+        gadget.circuit_domain_sep(&mut composer, 8);
+
+        for _ in 0..2 {
+            let challenge = gadget.challenge_scalar(&mut composer, b"challenge");
+            assert_eq!(
+                composer.variables[&challenge],
+                transcript.challenge_scalar(b"challenge")
+            );
+        }
+    }
+}
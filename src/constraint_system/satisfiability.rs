@@ -0,0 +1,481 @@
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this
+// file, You can obtain one at http://mozilla.org/MPL/2.0/.
+//
+// Copyright (c) DUSK NETWORK. All rights reserved.
+
+//! Checks that the witness of a `StandardComposer` satisfies its gates,
+//! before going through the whole proving process.
+
+use crate::constraint_system::{StandardComposer, Variable, WireData};
+use crate::proof_system::widget::logic::{delta, delta_xor_and};
+use crate::proof_system::GateWires;
+use crate::util;
+use dusk_bls12_381::BlsScalar;
+use dusk_jubjub::EDWARDS_D;
+use std::collections::HashSet;
+use std::fmt;
+use thiserror::Error;
+
+/// The kind of constraint a gate failed to satisfy.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum GateKind {
+    /// The wires of the gate do not hold the variables the copy constraints
+    /// expect, or hold a variable which has no value.
+    Copy,
+    /// Arithmetic gate, including the public input of the gate.
+    Arithmetic,
+    /// Range gate.
+    Range,
+    /// Logic (XOR and AND) gate.
+    Logic,
+    /// Variable base curve addition gate.
+    VariableBaseAdd,
+    /// Poseidon S-box gate.
+    Sbox,
+    /// Lookup gate, whose wires are not a row of its table.
+    Lookup,
+    /// Custom gate, with its name.
+    Custom(&'static str),
+}
+
+impl fmt::Display for GateKind {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            GateKind::Copy => write!(f, "copy constraint"),
+            GateKind::Arithmetic => write!(f, "arithmetic gate"),
+            GateKind::Range => write!(f, "range gate"),
+            GateKind::Logic => write!(f, "logic gate"),
+            GateKind::VariableBaseAdd => write!(f, "variable base addition gate"),
+            GateKind::Sbox => write!(f, "S-box gate"),
+            GateKind::Lookup => write!(f, "lookup gate"),
+            GateKind::Custom(name) => write!(f, "custom gate {}", name),
+        }
+    }
+}
+
+/// The first gate of a circuit which is not satisfied by its witness.
+#[derive(Error, Debug, Clone, PartialEq, Eq)]
+#[error(
+    "{kind} at index {index}{} is not satisfied by the wires {wires:?}",
+    in_namespace(.namespace)
+)]
+pub struct UnsatisfiedGate {
+    /// Index of the gate in the circuit.
+    pub index: usize,
+    /// Kind of the constraint that failed.
+    pub kind: GateKind,
+    /// Path of the innermost namespace the gate was added in, if any.
+    pub namespace: Option<String>,
+    /// Values of the wires of the gate.
+    pub wires: GateWires,
+}
+
+fn in_namespace(namespace: &Option<String>) -> String {
+    match namespace {
+        Some(path) => format!(" in {}", path),
+        None => String::new(),
+    }
+}
+
+impl StandardComposer {
+    /// Checks that the witness satisfies every gate identity and copy
+    /// constraint of the circuit, and returns the first gate that does not.
+    ///
+    /// A failing circuit is otherwise only reported by the verifier, which
+    /// can not tell which gate caused the failure.
+    #[allow(clippy::result_large_err)]
+    pub fn check_satisfied(&self) -> Result<(), UnsatisfiedGate> {
+        let wire_variables = self.wire_variables();
+        let lookup_rows: HashSet<_> = self.lookup_table_rows().iter().map(row_bytes).collect();
+        // Custom gates combine their identities with a separation challenge
+        let custom_challenge = util::random_scalar(&mut rand::thread_rng());
+
+        for index in 0..self.n {
+            let unsatisfied = |kind, wires| {
+                Err(UnsatisfiedGate {
+                    index,
+                    kind,
+                    namespace: self.gate_namespace(index),
+                    wires,
+                })
+            };
+
+            let wires = match self.gate_wires(index) {
+                Some(wires) => wires,
+                None => return unsatisfied(GateKind::Copy, GateWires::default()),
+            };
+            if let Some(kind) =
+                self.failing_gate_kind(index, &wires, &lookup_rows, &custom_challenge)
+            {
+                return unsatisfied(kind, wires);
+            }
+
+            // Wires which are not part of any copy constraint are left free,
+            // while the others hold a variable of the class the copy
+            // constraints expect, with the value of its representative
+            let expected = [
+                self.w_l[index],
+                self.w_r[index],
+                self.w_o[index],
+                self.w_4[index],
+            ];
+            if wire_variables
+                .iter()
+                .zip(expected.iter())
+                .any(|(variables, var)| match variables[index] {
+                    Some(v) => {
+                        v != self.perm.representative(*var)
+                            || self.variables.get(&v) != self.variables.get(var)
+                    }
+                    None => false,
+                })
+            {
+                return unsatisfied(GateKind::Copy, wires);
+            }
+        }
+
+        // Variables merged by `assert_equal` which are not placed at any wire
+        // are only checked against the value of their class
+        if let Some(index) = self.first_unequal_class() {
+            return Err(UnsatisfiedGate {
+                index,
+                kind: GateKind::Copy,
+                namespace: self.gate_namespace(index),
+                wires: self.gate_wires(index).unwrap_or_default(),
+            });
+        }
+
+        Ok(())
+    }
+
+    /// Returns the index of the first gate holding a wire of an equivalence
+    /// class whose variables, asserted equal, do not all have the same value.
+    ///
+    /// The classes which are not placed at any wire do not constrain the
+    /// circuit and are left out.
+    pub(crate) fn first_unequal_class(&self) -> Option<usize> {
+        self.variables
+            .iter()
+            .filter_map(|(var, value)| {
+                let representative = self.perm.representative(*var);
+                if self.variables.get(&representative) == Some(value) {
+                    return None;
+                }
+                self.perm.variable_map[&representative]
+                    .iter()
+                    .map(|wire| match *wire {
+                        WireData::Left(index)
+                        | WireData::Right(index)
+                        | WireData::Output(index)
+                        | WireData::Fourth(index) => index,
+                    })
+                    .min()
+            })
+            .min()
+    }
+
+    /// Returns the kind of the first identity the gate at `index` fails.
+    fn failing_gate_kind(
+        &self,
+        index: usize,
+        wires: &GateWires,
+        lookup_rows: &HashSet<[[u8; 32]; 4]>,
+        custom_challenge: &BlsScalar,
+    ) -> Option<GateKind> {
+        let zero = BlsScalar::zero();
+        let four = BlsScalar::from(4);
+        let GateWires {
+            a,
+            b,
+            c,
+            d,
+            a_next,
+            b_next,
+            d_next,
+        } = *wires;
+        let q_c = self.q_c[index];
+
+        let q_arith = self.q_arith[index];
+        let arithmetic = q_arith
+            * (self.q_m[index] * a * b
+                + self.q_l[index] * a
+                + self.q_r[index] * b
+                + self.q_o[index] * c
+                + self.q_4[index] * d
+                + q_c)
+            + self.public_inputs[index];
+        if arithmetic != zero {
+            return Some(GateKind::Arithmetic);
+        }
+
+        if self.q_range[index] != zero {
+            let range = [
+                delta(c - four * d),
+                delta(b - four * c),
+                delta(a - four * b),
+                delta(d_next - four * a),
+            ];
+            if range.iter().any(|identity| identity != &zero) {
+                return Some(GateKind::Range);
+            }
+        }
+
+        if self.q_logic[index] != zero {
+            let a_quad = a_next - four * a;
+            let b_quad = b_next - four * b;
+            let d_quad = d_next - four * d;
+            let logic = [
+                delta(a_quad),
+                delta(b_quad),
+                delta(d_quad),
+                c - a_quad * b_quad,
+                delta_xor_and(&a_quad, &b_quad, &c, &d_quad, &q_c),
+            ];
+            if logic.iter().any(|identity| identity != &zero) {
+                return Some(GateKind::Logic);
+            }
+        }
+
+        if self.q_variable_group_add[index] != zero {
+            // (x_1, y_1) + (x_2, y_2) = (x_3, y_3), with x_1 * y_2 in the next row
+            let (x_1, y_1, x_2, y_2) = (a, b, c, d);
+            let (x_3, y_3, x1_y2) = (a_next, b_next, d_next);
+            let y1_x2 = y_1 * x_2;
+            let variable_base = [
+                x_1 * y_2 - x1_y2,
+                x1_y2 + y1_x2 - (x_3 + x_3 * EDWARDS_D * x1_y2 * y1_x2),
+                y_1 * y_2 + x_1 * x_2 - (y_3 - y_3 * EDWARDS_D * x1_y2 * y1_x2),
+            ];
+            if variable_base.iter().any(|identity| identity != &zero) {
+                return Some(GateKind::VariableBaseAdd);
+            }
+        }
+
+        if self.q_sbox[index] != zero {
+            let x = a + q_c;
+            if d != x.square() || c != d.square() * x {
+                return Some(GateKind::Sbox);
+            }
+        }
+
+        if self.q_lookup[index] != zero && !lookup_rows.contains(&row_bytes(&[a, b, c, q_c])) {
+            return Some(GateKind::Lookup);
+        }
+
+        for custom in self.custom_gates.iter() {
+            let coefficients = custom.gate.coefficients(wires, custom_challenge);
+            if coefficients.len() != custom.columns.len() {
+                return Some(GateKind::Custom(custom.gate.name()));
+            }
+            let identity: BlsScalar = custom
+                .columns
+                .iter()
+                .zip(coefficients.iter())
+                .map(|(column, coefficient)| {
+                    column.get(index).copied().unwrap_or_default() * coefficient
+                })
+                .sum();
+            if identity != zero {
+                return Some(GateKind::Custom(custom.gate.name()));
+            }
+        }
+
+        None
+    }
+
+    /// Returns the values of the wires of the gate at `index`, or `None` if
+    /// one of them holds a variable without a value.
+    fn gate_wires(&self, index: usize) -> Option<GateWires> {
+        let value = |var: &Variable| self.variables.get(var).copied();
+
+        // The next row of the last gate is either a padding gate, whose
+        // wires are zero, or the first gate if the circuit is not padded
+        let next = if index + 1 < self.n {
+            Some(index + 1)
+        } else if self.domain_size() > self.n {
+            None
+        } else {
+            Some(0)
+        };
+        let next_value = |wires: &[Variable]| match next {
+            Some(next) => value(&wires[next]),
+            None => Some(BlsScalar::zero()),
+        };
+
+        Some(GateWires {
+            a: value(&self.w_l[index])?,
+            b: value(&self.w_r[index])?,
+            c: value(&self.w_o[index])?,
+            d: value(&self.w_4[index])?,
+            a_next: next_value(&self.w_l)?,
+            b_next: next_value(&self.w_r)?,
+            d_next: next_value(&self.w_4)?,
+        })
+    }
+
+    /// Returns, for each of the four wires of every gate, the variable which
+    /// the copy constraints place there.
+    fn wire_variables(&self) -> [Vec<Option<Variable>>; 4] {
+        let mut wire_variables = [
+            vec![None; self.n],
+            vec![None; self.n],
+            vec![None; self.n],
+            vec![None; self.n],
+        ];
+        for (var, wire_data) in self.perm.variable_map.iter() {
+            for wire in wire_data.iter() {
+                let (column, index) = match *wire {
+                    WireData::Left(index) => (0, index),
+                    WireData::Right(index) => (1, index),
+                    WireData::Output(index) => (2, index),
+                    WireData::Fourth(index) => (3, index),
+                };
+                if let Some(slot) = wire_variables[column].get_mut(index) {
+                    *slot = Some(*var);
+                }
+            }
+        }
+        wire_variables
+    }
+}
+
+fn row_bytes(row: &[BlsScalar; 4]) -> [[u8; 32]; 4] {
+    [
+        row[0].to_bytes(),
+        row[1].to_bytes(),
+        row[2].to_bytes(),
+        row[3].to_bytes(),
+    ]
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::constraint_system::ecc::scalar_mul::fixed_base::scalar_mul;
+    use crate::constraint_system::ecc::Point;
+    use crate::constraint_system::LookupTable;
+    use crate::poseidon::Poseidon;
+    use dusk_jubjub::{JubJubExtended, GENERATOR};
+
+    #[test]
+    fn test_check_satisfied() {
+        let mut composer = StandardComposer::new();
+
+        let a = composer.add_input(BlsScalar::from(0b1010));
+        let b = composer.add_input(BlsScalar::from(0b0110));
+        composer.big_add(
+            a.into(),
+            b.into(),
+            None,
+            BlsScalar::zero(),
+            BlsScalar::zero(),
+        );
+        composer.range_gate(a, 8);
+        composer.xor_gate(a, b, 4);
+        composer.and_gate(a, b, 4);
+        composer.poseidon_hash(&Poseidon::new(), &[a, b]);
+
+        let xor_table = composer.add_lookup_table(LookupTable::xor_table(4));
+        composer.lookup(xor_table, a, b);
+
+        let scalar = composer.add_input(BlsScalar::from(42));
+        let point = scalar_mul(&mut composer, scalar, JubJubExtended::from(GENERATOR));
+        let generator = Point::from_private_affine(&mut composer, GENERATOR);
+        point.point().fast_add(&mut composer, generator);
+
+        assert_eq!(composer.check_satisfied(), Ok(()));
+    }
+
+    #[test]
+    fn test_unsatisfied_gates() {
+        // 1 + 2 != 4
+        let mut composer = StandardComposer::new();
+        let one = composer.add_input(BlsScalar::one());
+        let two = composer.add_input(BlsScalar::from(2));
+        let four = composer.add_input(BlsScalar::from(4));
+        let index = composer.circuit_size();
+        composer.poly_gate(
+            one,
+            two,
+            four,
+            BlsScalar::zero(),
+            BlsScalar::one(),
+            BlsScalar::one(),
+            -BlsScalar::one(),
+            BlsScalar::zero(),
+            BlsScalar::zero(),
+        );
+        let unsatisfied = composer.check_satisfied().unwrap_err();
+        assert_eq!(unsatisfied.index, index);
+        assert_eq!(unsatisfied.kind, GateKind::Arithmetic);
+        assert_eq!(unsatisfied.wires.a, BlsScalar::one());
+        assert_eq!(unsatisfied.wires.c, BlsScalar::from(4));
+
+        // The S-box output does not match its input
+        let mut composer = StandardComposer::new();
+        let x = composer.add_input(BlsScalar::from(3));
+        let index = composer.circuit_size();
+        let quint = composer.sbox_gate(x, BlsScalar::zero());
+        composer.variables.insert(quint, BlsScalar::from(242));
+        let unsatisfied = composer.check_satisfied().unwrap_err();
+        assert_eq!(unsatisfied.index, index);
+        assert_eq!(unsatisfied.kind, GateKind::Sbox);
+
+        // (1, 2, 4) is not a row of the XOR table
+        let mut composer = StandardComposer::new();
+        let xor_table = composer.add_lookup_table(LookupTable::xor_table(4));
+        let one = composer.add_input(BlsScalar::one());
+        let two = composer.add_input(BlsScalar::from(2));
+        let four = composer.add_input(BlsScalar::from(4));
+        let index = composer.circuit_size();
+        composer.lookup_gate(xor_table, one, two, four);
+        let unsatisfied = composer.check_satisfied().unwrap_err();
+        assert_eq!(unsatisfied.index, index);
+        assert_eq!(unsatisfied.kind, GateKind::Lookup);
+
+        // The sum of two points is not on the curve
+        let mut composer = StandardComposer::new();
+        let generator = Point::from_private_affine(&mut composer, GENERATOR);
+        let index = composer.circuit_size();
+        let sum = generator.fast_add(&mut composer, generator);
+        composer.variables.insert(*sum.x(), BlsScalar::one());
+        let unsatisfied = composer.check_satisfied().unwrap_err();
+        assert_eq!(unsatisfied.index, index);
+        assert_eq!(unsatisfied.kind, GateKind::VariableBaseAdd);
+
+        // 256 does not fit in 8 bits
+        let mut composer = StandardComposer::new();
+        let x = composer.add_input(BlsScalar::from(256));
+        composer.range_gate(x, 8);
+        assert!(composer.check_satisfied().is_err());
+    }
+
+    #[test]
+    fn test_unsatisfied_copy_constraint() {
+        let mut composer = StandardComposer::new();
+        let one = composer.add_input(BlsScalar::one());
+        let two = composer.add_input(BlsScalar::from(2));
+        let index = composer.circuit_size();
+        composer.big_add(
+            one.into(),
+            one.into(),
+            None,
+            BlsScalar::zero(),
+            BlsScalar::zero(),
+        );
+        assert_eq!(composer.check_satisfied(), Ok(()));
+
+        // The wire holds another variable than the one its copy constraints
+        // were computed for
+        composer.w_l[index] = two;
+        let unsatisfied = composer.check_satisfied().unwrap_err();
+        assert_eq!(unsatisfied.index, index);
+        assert_eq!(unsatisfied.kind, GateKind::Arithmetic);
+        composer.w_r[index] = two;
+        composer.w_l[index] = composer.zero_var;
+        let unsatisfied = composer.check_satisfied().unwrap_err();
+        assert_eq!(unsatisfied.index, index);
+        assert_eq!(unsatisfied.kind, GateKind::Copy);
+    }
+}
@@ -0,0 +1,164 @@
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this
+// file, You can obtain one at http://mozilla.org/MPL/2.0/.
+//
+// Copyright (c) DUSK NETWORK. All rights reserved.
+
+//! Assignment of the witness of a circuit synthesized beforehand.
+//!
+//! A circuit can be synthesized once with its inputs holding any value,
+//! such as zero, and a witness assigned to it afterwards: the inputs are
+//! given their values with `StandardComposer::assign`, and
+//! `StandardComposer::generate_witness` computes the values of the
+//! variables the gadgets derived from them.
+//!
+//! To that end, the built-in gadgets compute the variables they derive from
+//! their inputs in a witness generator, which is recorded by the composer
+//! and run again when the witness is generated. The recursion gadget, which
+//! allocates the proof it verifies when it is synthesized, is the only one
+//! that can not be assigned this way.
+
+use crate::constraint_system::{StandardComposer, Variable};
+use dusk_bls12_381::BlsScalar;
+use std::fmt;
+use std::sync::Arc;
+
+type Generator = Arc<dyn Fn(&mut StandardComposer) + Send + Sync>;
+
+/// The witness generators recorded by the gadgets of a circuit, in the order
+/// they were added.
+#[derive(Clone, Default)]
+pub(crate) struct WitnessGenerators(Vec<Generator>);
+
+impl fmt::Debug for WitnessGenerators {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "WitnessGenerators({})", self.0.len())
+    }
+}
+
+impl StandardComposer {
+    /// Sets the value of `var`, allocated when the circuit was synthesized.
+    ///
+    /// The variables derived from it keep their previous value until
+    /// `generate_witness` is called.
+    pub fn assign(&mut self, var: Variable, value: BlsScalar) {
+        assert!(
+            self.variables.contains_key(&var),
+            "variable {:?} is not part of the circuit",
+            var
+        );
+        self.variables.insert(var, value);
+    }
+
+    /// Computes the values of the variables derived by the gadgets of the
+    /// circuit and of the public inputs declared with `public_input`, from
+    /// the values assigned to their inputs.
+    pub fn generate_witness(&mut self) {
+        let generators = std::mem::take(&mut self.witness_generators);
+        for generator in generators.0.iter() {
+            generator(self);
+        }
+        self.witness_generators = generators;
+        for (_, position) in self.public_input_layout.iter() {
+            self.public_inputs[position] = -self.variables[&self.w_l[position]];
+        }
+    }
+
+    /// Computes the variables a gadget derives from its inputs with
+    /// `generator`, and records it to compute them again when the witness
+    /// is generated.
+    ///
+    /// The generator must only set the values of the variables the gadget
+    /// allocated, from the values of the variables allocated before them.
+    pub(crate) fn add_witness_generator<F>(&mut self, generator: F)
+    where
+        F: Fn(&mut StandardComposer) + Send + Sync + 'static,
+    {
+        generator(self);
+        self.witness_generators.0.push(Arc::new(generator));
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::commitment_scheme::kzg10::PublicParameters;
+    use crate::proof_system::{Prover, Verifier};
+
+    // Synthesizes `c = a * b + a` with `a` and `b` in range and `c` public,
+    // and returns the handles of `a`, `b` and `c`
+    fn synthesize(composer: &mut StandardComposer) -> [Variable; 3] {
+        let a = composer.add_input(BlsScalar::zero());
+        let b = composer.add_input(BlsScalar::zero());
+        composer.range_gate(a, 8);
+        composer.range_gate(b, 8);
+        let ab = composer.mul(BlsScalar::one(), a, b, BlsScalar::zero(), BlsScalar::zero());
+        let c = composer.add(
+            (BlsScalar::one(), ab),
+            (BlsScalar::one(), a),
+            BlsScalar::zero(),
+            BlsScalar::zero(),
+        );
+        let public_c = composer.public_input("c", BlsScalar::zero());
+        composer.assert_equal(c, public_c);
+        [a, b, public_c]
+    }
+
+    #[test]
+    fn test_generate_witness() {
+        let mut composer = StandardComposer::new();
+        let [a, b, c] = synthesize(&mut composer);
+
+        composer.assign(a, BlsScalar::from(7));
+        composer.assign(b, BlsScalar::from(9));
+        composer.assign(c, BlsScalar::from(70));
+        // The derived variables still hold the values of the synthesis
+        assert!(composer.check_satisfied().is_err());
+
+        composer.generate_witness();
+        assert!(composer.check_satisfied().is_ok());
+        assert_eq!(
+            composer.sparse_public_inputs(),
+            vec![(
+                composer.public_input_layout.position("c").unwrap(),
+                -BlsScalar::from(70)
+            )]
+        );
+
+        // An input out of range is caught by the gates
+        composer.assign(a, BlsScalar::from(256));
+        composer.generate_witness();
+        assert!(composer.check_satisfied().is_err());
+    }
+
+    #[test]
+    fn test_prove_assigned_witness() {
+        let public_parameters = PublicParameters::setup(1 << 8, &mut rand::thread_rng()).unwrap();
+        let (ck, vk) = public_parameters.trim(1 << 7).unwrap();
+
+        let mut composer = StandardComposer::new();
+        let [a, b, c] = synthesize(&mut composer);
+        let mut shape = composer.clone();
+
+        let mut prover = Prover::new(b"witness");
+        *prover.mut_cs() = composer;
+        prover.preprocess(&ck).unwrap();
+        let mut verifier = Verifier::new(b"witness");
+        *verifier.mut_cs() = shape.clone();
+        verifier.preprocess(&ck).unwrap();
+
+        shape.assign(a, BlsScalar::from(3));
+        shape.assign(b, BlsScalar::from(5));
+        shape.assign(c, BlsScalar::from(18));
+        shape.generate_witness();
+        *prover.mut_cs() = shape;
+        let proof = prover.prove(&ck).unwrap();
+
+        verifier
+            .verify(&proof, &vk, &[BlsScalar::from(18)])
+            .unwrap();
+        assert!(verifier
+            .verify(&proof, &vk, &[BlsScalar::from(17)])
+            .is_err());
+    }
+}
@@ -16,13 +16,22 @@ use super::{fft_errors::FFTErrors, Evaluations};
 use anyhow::{Error, Result};
 use core::fmt;
 use dusk_bls12_381::{BlsScalar, GENERATOR, ROOT_OF_UNITY, TWO_ADACITY};
+use once_cell::sync::OnceCell;
 use rayon::iter::{IndexedParallelIterator, IntoParallelRefMutIterator, ParallelIterator};
-use std::ops::MulAssign;
+use rayon::slice::ParallelSliceMut;
+use std::sync::Arc;
+
+/// Number of elements below which the loops of the FFT are not split
+/// between threads.
+const MIN_PARALLEL_LEN: usize = 1 << 10;
 
 /// Defines a domain over which finite field (I)FFTs can be performed. Works
 /// only for fields that have a large multiplicative subgroup of size that is
 /// a power-of-2.
-#[derive(Copy, Clone, Eq, PartialEq)]
+///
+/// The twiddle factors of the FFTs are computed on the first FFT over the
+/// domain and shared by its clones.
+#[derive(Clone)]
 pub struct EvaluationDomain {
     /// The size of the domain.
     pub size: u64,
@@ -38,8 +47,26 @@ pub struct EvaluationDomain {
     pub group_gen_inv: BlsScalar,
     /// Multiplicative generator of the finite field.
     pub generator_inv: BlsScalar,
+    twiddles: Arc<Twiddles>,
+}
+
+/// The powers `\omega^i` and `\omega^{-i}` for `i` in `0..size / 2`, where
+/// `\omega` generates the domain.
+#[derive(Default)]
+struct Twiddles {
+    forward: OnceCell<Vec<BlsScalar>>,
+    inverse: OnceCell<Vec<BlsScalar>>,
+}
+
+impl PartialEq for EvaluationDomain {
+    fn eq(&self, other: &Self) -> bool {
+        // Every other field is derived from the size
+        self.size == other.size
+    }
 }
 
+impl Eq for EvaluationDomain {}
+
 impl fmt::Debug for EvaluationDomain {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         write!(f, "Multiplicative subgroup of size {}", self.size)
@@ -80,6 +107,7 @@ impl EvaluationDomain {
             group_gen,
             group_gen_inv: group_gen.invert().unwrap(),
             generator_inv: GENERATOR.invert().unwrap(),
+            twiddles: Arc::new(Twiddles::default()),
         })
     }
     /// Return the size of a domain that is large enough for evaluations of a
@@ -98,9 +126,23 @@ impl EvaluationDomain {
         self.size as usize
     }
 
+    /// Returns the powers of the generator used by the FFTs.
+    fn twiddles(&self) -> &[BlsScalar] {
+        self.twiddles
+            .forward
+            .get_or_init(|| powers(self.group_gen, self.size() / 2))
+    }
+
+    /// Returns the powers of the inverse of the generator used by the IFFTs.
+    fn inverse_twiddles(&self) -> &[BlsScalar] {
+        self.twiddles
+            .inverse
+            .get_or_init(|| powers(self.group_gen_inv, self.size() / 2))
+    }
+
     /// Compute a FFT.
     pub fn fft(&self, coeffs: &[BlsScalar]) -> Vec<BlsScalar> {
-        let mut coeffs = coeffs.to_vec();
+        let mut coeffs = self.copy_padded(coeffs);
         self.fft_in_place(&mut coeffs);
         coeffs
     }
@@ -108,12 +150,12 @@ impl EvaluationDomain {
     /// Compute a FFT, modifying the vector in place.
     pub fn fft_in_place(&self, coeffs: &mut Vec<BlsScalar>) {
         coeffs.resize(self.size(), BlsScalar::zero());
-        best_fft(coeffs, self.group_gen, self.log_size_of_group)
+        parallel_fft(coeffs, self.twiddles(), self.log_size_of_group)
     }
 
     /// Compute an IFFT.
     pub fn ifft(&self, evals: &[BlsScalar]) -> Vec<BlsScalar> {
-        let mut evals = evals.to_vec();
+        let mut evals = self.copy_padded(evals);
         self.ifft_in_place(&mut evals);
         evals
     }
@@ -122,22 +164,35 @@ impl EvaluationDomain {
     #[inline]
     pub fn ifft_in_place(&self, evals: &mut Vec<BlsScalar>) {
         evals.resize(self.size(), BlsScalar::zero());
-        best_fft(evals, self.group_gen_inv, self.log_size_of_group);
-        // cfg_iter_mut!(evals).for_each(|val| *val *= &self.size_inv);
+        parallel_fft(evals, self.inverse_twiddles(), self.log_size_of_group);
         evals.par_iter_mut().for_each(|val| *val *= &self.size_inv);
     }
 
-    fn distribute_powers(coeffs: &mut [BlsScalar], g: BlsScalar) {
-        let mut pow = BlsScalar::one();
-        coeffs.iter_mut().for_each(|c| {
-            *c *= &pow;
-            pow *= &g
-        })
+    /// Copies `values` into a vector of the size of the domain, so that it is
+    /// not reallocated when padded.
+    fn copy_padded(&self, values: &[BlsScalar]) -> Vec<BlsScalar> {
+        let mut copy = Vec::with_capacity(std::cmp::max(values.len(), self.size()));
+        copy.extend_from_slice(values);
+        copy
+    }
+
+    /// Multiplies the `i`-th coefficient by `c g^i`.
+    fn distribute_powers(coeffs: &mut [BlsScalar], c: BlsScalar, g: BlsScalar) {
+        coeffs
+            .par_chunks_mut(MIN_PARALLEL_LEN)
+            .enumerate()
+            .for_each(|(i, chunk)| {
+                let mut pow = c * g.pow(&[(i * MIN_PARALLEL_LEN) as u64, 0, 0, 0]);
+                chunk.iter_mut().for_each(|coeff| {
+                    *coeff *= &pow;
+                    pow *= &g
+                })
+            })
     }
 
     /// Compute a FFT over a coset of the domain.
     pub fn coset_fft(&self, coeffs: &[BlsScalar]) -> Vec<BlsScalar> {
-        let mut coeffs = coeffs.to_vec();
+        let mut coeffs = self.copy_padded(coeffs);
         self.coset_fft_in_place(&mut coeffs);
         coeffs
     }
@@ -145,13 +200,13 @@ impl EvaluationDomain {
     /// Compute a FFT over a coset of the domain, modifying the input vector
     /// in place.
     pub fn coset_fft_in_place(&self, coeffs: &mut Vec<BlsScalar>) {
-        Self::distribute_powers(coeffs, GENERATOR);
+        Self::distribute_powers(coeffs, BlsScalar::one(), GENERATOR);
         self.fft_in_place(coeffs);
     }
 
     /// Compute an IFFT over a coset of the domain.
     pub fn coset_ifft(&self, evals: &[BlsScalar]) -> Vec<BlsScalar> {
-        let mut evals = evals.to_vec();
+        let mut evals = self.copy_padded(evals);
         self.coset_ifft_in_place(&mut evals);
         evals
     }
@@ -159,8 +214,10 @@ impl EvaluationDomain {
     /// Compute an IFFT over a coset of the domain, modifying the input vector in
     /// place.
     pub fn coset_ifft_in_place(&self, evals: &mut Vec<BlsScalar>) {
-        self.ifft_in_place(evals);
-        Self::distribute_powers(evals, self.generator_inv);
+        evals.resize(self.size(), BlsScalar::zero());
+        parallel_fft(evals, self.inverse_twiddles(), self.log_size_of_group);
+        // Scale by the inverse of the size while shifting back from the coset
+        Self::distribute_powers(evals, self.size_inv, self.generator_inv);
     }
 
     #[allow(clippy::needless_range_loop)]
@@ -228,7 +285,7 @@ impl EvaluationDomain {
                     - BlsScalar::one()
             })
             .collect();
-        Evaluations::from_vec_and_domain(v_h, *self)
+        Evaluations::from_vec_and_domain(v_h, self.clone())
     }
 
     /// Return an iterator over the elements of the domain.
@@ -236,7 +293,7 @@ impl EvaluationDomain {
         Elements {
             cur_elem: BlsScalar::one(),
             cur_pow: 0,
-            domain: *self,
+            domain: self.clone(),
         }
     }
 
@@ -306,8 +363,62 @@ impl EvaluationDomain {
     }
 }
 
-fn best_fft(a: &mut [BlsScalar], omega: BlsScalar, log_n: u32) {
-    serial_fft(a, omega, log_n)
+/// Returns the powers `g^i` for `i` in `0..len`.
+fn powers(g: BlsScalar, len: usize) -> Vec<BlsScalar> {
+    let mut powers = Vec::with_capacity(len);
+    let mut pow = BlsScalar::one();
+    for _ in 0..len {
+        powers.push(pow);
+        pow *= &g;
+    }
+    powers
+}
+
+/// Radix-2 FFT of `a`, whose size is `2^log_n`, where `twiddles` holds the
+/// first `2^{log_n - 1}` powers of the root of unity.
+///
+/// Each layer of butterflies is split between threads, first by the blocks
+/// of the layer and then, once the blocks are large, within each block.
+fn parallel_fft(a: &mut [BlsScalar], twiddles: &[BlsScalar], log_n: u32) {
+    let n = a.len();
+    assert_eq!(n, 1 << log_n);
+    assert_eq!(twiddles.len(), n / 2);
+
+    for k in 0..n as u32 {
+        let rk = bitreverse(k, log_n);
+        if k < rk {
+            a.swap(rk as usize, k as usize);
+        }
+    }
+
+    let mut m = 1;
+    while m < n {
+        // The twiddles of the blocks of size `2m` are the powers of `\omega^{n / 2m}`
+        let stride = n / (2 * m);
+        a.par_chunks_mut(2 * m)
+            .with_min_len(std::cmp::max(1, MIN_PARALLEL_LEN / (2 * m)))
+            .for_each(|block| {
+                let (lo, hi) = block.split_at_mut(m);
+                let butterfly = |(j, (lo, hi)): (usize, (&mut BlsScalar, &mut BlsScalar))| {
+                    let t = *hi * twiddles[j * stride];
+                    *hi = *lo - t;
+                    *lo += t;
+                };
+                if m >= MIN_PARALLEL_LEN {
+                    lo.par_iter_mut()
+                        .zip(hi.par_iter_mut())
+                        .enumerate()
+                        .with_min_len(MIN_PARALLEL_LEN)
+                        .for_each(butterfly);
+                } else {
+                    lo.iter_mut()
+                        .zip(hi.iter_mut())
+                        .enumerate()
+                        .for_each(butterfly);
+                }
+            });
+        m *= 2;
+    }
 }
 
 #[inline]
@@ -320,7 +431,9 @@ fn bitreverse(mut n: u32, l: u32) -> u32 {
     r
 }
 
-pub(crate) fn serial_fft(a: &mut [BlsScalar], omega: BlsScalar, log_n: u32) {
+/// Textbook radix-2 FFT, against which `parallel_fft` is tested.
+#[cfg(test)]
+fn serial_fft(a: &mut [BlsScalar], omega: BlsScalar, log_n: u32) {
     let n = a.len() as u32;
     assert_eq!(n, 1 << log_n);
 
@@ -345,7 +458,7 @@ pub(crate) fn serial_fft(a: &mut [BlsScalar], omega: BlsScalar, log_n: u32) {
                 tmp -= &t;
                 a[(k + j + m) as usize] = tmp;
                 a[(k + j) as usize] += &t;
-                w.mul_assign(&w_m);
+                w *= &w_m;
             }
 
             k += 2 * m;
@@ -379,7 +492,10 @@ impl Iterator for Elements {
 
 #[cfg(test)]
 mod tests {
-    use super::EvaluationDomain;
+    use super::*;
+    use proptest::prelude::*;
+    use rand::rngs::StdRng;
+    use rand::SeedableRng;
 
     #[test]
     fn size_of_elements() {
@@ -401,4 +517,86 @@ mod tests {
             }
         }
     }
+
+    #[test]
+    fn twiddles_shared_by_clones() {
+        let domain = EvaluationDomain::new(1 << 6).unwrap();
+        let clone = domain.clone();
+        let same_size = EvaluationDomain::new((1 << 6) - 3).unwrap();
+        assert!(Arc::ptr_eq(&domain.twiddles, &clone.twiddles));
+        assert!(!Arc::ptr_eq(&domain.twiddles, &same_size.twiddles));
+
+        // The twiddles computed through the clone are used by the domain
+        assert_eq!(clone.twiddles(), powers(domain.group_gen, 1 << 5));
+        assert!(domain.twiddles.forward.get().is_some());
+        assert!(same_size.twiddles.forward.get().is_none());
+    }
+
+    fn random_scalars(len: usize, seed: u64) -> Vec<BlsScalar> {
+        let mut rng = StdRng::seed_from_u64(seed);
+        (0..len).map(|_| BlsScalar::random(&mut rng)).collect()
+    }
+
+    fn serial_coset_fft(domain: &EvaluationDomain, coeffs: &[BlsScalar]) -> Vec<BlsScalar> {
+        let mut coeffs = coeffs.to_vec();
+        coeffs.resize(domain.size(), BlsScalar::zero());
+        let mut pow = BlsScalar::one();
+        for coeff in coeffs.iter_mut() {
+            *coeff *= &pow;
+            pow *= &GENERATOR;
+        }
+        serial_fft(&mut coeffs, domain.group_gen, domain.log_size_of_group);
+        coeffs
+    }
+
+    proptest! {
+        #![proptest_config(ProptestConfig::with_cases(32))]
+
+        #[test]
+        fn fft_matches_serial_fft(len in 1usize..5000, seed in any::<u64>()) {
+            let domain = EvaluationDomain::new(len).unwrap();
+            let coeffs = random_scalars(len, seed);
+
+            let mut expected = coeffs.clone();
+            expected.resize(domain.size(), BlsScalar::zero());
+            serial_fft(&mut expected, domain.group_gen, domain.log_size_of_group);
+            prop_assert_eq!(&domain.fft(&coeffs), &expected);
+
+            let mut expected_inverse = coeffs.clone();
+            expected_inverse.resize(domain.size(), BlsScalar::zero());
+            serial_fft(&mut expected_inverse, domain.group_gen_inv, domain.log_size_of_group);
+            expected_inverse.iter_mut().for_each(|eval| *eval *= &domain.size_inv);
+            prop_assert_eq!(&domain.ifft(&coeffs), &expected_inverse);
+        }
+
+        #[test]
+        fn coset_fft_matches_serial_fft(len in 1usize..5000, seed in any::<u64>()) {
+            let domain = EvaluationDomain::new(len).unwrap();
+            let coeffs = random_scalars(len, seed);
+
+            let evals = domain.coset_fft(&coeffs);
+            prop_assert_eq!(&evals, &serial_coset_fft(&domain, &coeffs));
+
+            let mut padded = coeffs;
+            padded.resize(domain.size(), BlsScalar::zero());
+            prop_assert_eq!(&domain.coset_ifft(&evals), &padded);
+        }
+
+        #[test]
+        fn ifft_inverts_fft(len in 1usize..5000, seed in any::<u64>()) {
+            let domain = EvaluationDomain::new(len).unwrap();
+            let mut values = random_scalars(domain.size(), seed);
+            let expected = values.clone();
+
+            domain.fft_in_place(&mut values);
+            domain.ifft_in_place(&mut values);
+            prop_assert_eq!(&values, &expected);
+
+            // The twiddles are shared with the domains of the same size
+            let clone = domain.clone();
+            clone.coset_fft_in_place(&mut values);
+            domain.coset_ifft_in_place(&mut values);
+            prop_assert_eq!(&values, &expected);
+        }
+    }
 }
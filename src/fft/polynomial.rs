@@ -363,7 +363,8 @@ impl<'a, 'b> Mul<&'a Polynomial> for &'b Polynomial {
         } else {
             let domain = EvaluationDomain::new(self.coeffs.len() + other.coeffs.len())
                 .expect("field is not smooth enough to construct domain");
-            let mut self_evals = Evaluations::from_vec_and_domain(domain.fft(&self.coeffs), domain);
+            let mut self_evals =
+                Evaluations::from_vec_and_domain(domain.fft(&self.coeffs), domain.clone());
             let other_evals = Evaluations::from_vec_and_domain(domain.fft(&other.coeffs), domain);
             self_evals *= &other_evals;
             self_evals.interpolate()
@@ -62,7 +62,9 @@ pub mod circuit_builder;
 pub mod commitment_scheme;
 pub mod constraint_system;
 pub mod fft;
+pub mod merkle;
 mod permutation;
+pub mod poseidon;
 pub mod prelude;
 pub mod proof_system;
 mod serialisation;
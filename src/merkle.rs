@@ -0,0 +1,183 @@
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this
+// file, You can obtain one at http://mozilla.org/MPL/2.0/.
+//
+// Copyright (c) DUSK NETWORK. All rights reserved.
+
+//! Native binary Merkle tree over `BlsScalar` using the Poseidon hash.
+//!
+//! The parent of two nodes is `Poseidon::hash(&[left, right])` and unused
+//! leaves are zero, so a tree only stores the nodes above its leaves while
+//! the empty subtrees are represented by their precomputed roots.
+//! The openings it produces are checked in the circuit by
+//! `StandardComposer::merkle_opening`.
+
+use crate::poseidon::Poseidon;
+use anyhow::{Error, Result};
+use dusk_bls12_381::BlsScalar;
+use thiserror::Error;
+
+/// A Merkle tree of fixed depth holding `2^depth` leaves.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct MerkleTree {
+    // Nodes of each level, starting from the leaves. Nodes past the end of a
+    // level are roots of empty subtrees
+    levels: Vec<Vec<BlsScalar>>,
+    // Root of an empty subtree at each level
+    empty_roots: Vec<BlsScalar>,
+}
+
+impl MerkleTree {
+    /// Builds a tree of the given `depth` whose first leaves are `leaves`,
+    /// the remaining ones being zero.
+    pub fn new(depth: usize, leaves: &[BlsScalar]) -> Result<MerkleTree, Error> {
+        if depth >= 64 || leaves.len() as u64 > 1 << depth {
+            return Err(MerkleTreeErrors::TooManyLeaves.into());
+        }
+        let poseidon = Poseidon::new();
+
+        let mut empty_roots = vec![BlsScalar::zero()];
+        for level in 0..depth {
+            let empty = empty_roots[level];
+            empty_roots.push(poseidon.hash(&[empty, empty]));
+        }
+
+        let mut levels = vec![leaves.to_vec()];
+        for level in 0..depth {
+            let parents = levels[level]
+                .chunks(2)
+                .map(|pair| {
+                    let right = pair.get(1).copied().unwrap_or(empty_roots[level]);
+                    poseidon.hash(&[pair[0], right])
+                })
+                .collect();
+            levels.push(parents);
+        }
+
+        Ok(MerkleTree {
+            levels,
+            empty_roots,
+        })
+    }
+
+    /// Returns the depth of the tree.
+    pub fn depth(&self) -> usize {
+        self.levels.len() - 1
+    }
+
+    /// Returns the leaves the tree was built with.
+    pub fn leaves(&self) -> &[BlsScalar] {
+        &self.levels[0]
+    }
+
+    /// Returns the root of the tree.
+    pub fn root(&self) -> BlsScalar {
+        self.node(self.depth(), 0)
+    }
+
+    /// Returns the opening of the leaf at `index`.
+    pub fn opening(&self, index: usize) -> Result<MerkleOpening, Error> {
+        if index as u64 >= 1 << self.depth() {
+            return Err(MerkleTreeErrors::IndexOutOfBounds.into());
+        }
+
+        let (siblings, directions) = (0..self.depth())
+            .map(|level| {
+                let position = index >> level;
+                (self.node(level, position ^ 1), position & 1 == 1)
+            })
+            .unzip();
+
+        Ok(MerkleOpening {
+            siblings,
+            directions,
+        })
+    }
+
+    fn node(&self, level: usize, position: usize) -> BlsScalar {
+        self.levels[level]
+            .get(position)
+            .copied()
+            .unwrap_or(self.empty_roots[level])
+    }
+}
+
+/// The path from a leaf to the root of a `MerkleTree`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct MerkleOpening {
+    siblings: Vec<BlsScalar>,
+    directions: Vec<bool>,
+}
+
+impl MerkleOpening {
+    /// Returns the siblings of the nodes on the path, starting from the leaf.
+    pub fn siblings(&self) -> &[BlsScalar] {
+        &self.siblings
+    }
+
+    /// Returns, for each node on the path, `true` if it is the right child
+    /// of its parent.
+    pub fn directions(&self) -> &[bool] {
+        &self.directions
+    }
+
+    /// Computes the root of the tree from the `leaf` and the path.
+    pub fn root(&self, poseidon: &Poseidon, leaf: BlsScalar) -> BlsScalar {
+        self.siblings
+            .iter()
+            .zip(self.directions.iter())
+            .fold(leaf, |node, (sibling, is_right)| {
+                if *is_right {
+                    poseidon.hash(&[*sibling, node])
+                } else {
+                    poseidon.hash(&[node, *sibling])
+                }
+            })
+    }
+}
+
+/// Represents an error in the creation or opening of a `MerkleTree`.
+#[derive(Error, Debug)]
+pub enum MerkleTreeErrors {
+    /// This error occurs when more leaves are given than the tree can hold.
+    #[error("too many leaves for the depth of the tree")]
+    TooManyLeaves,
+    /// This error occurs when opening a leaf past the end of the tree.
+    #[error("leaf index is out of the bounds of the tree")]
+    IndexOutOfBounds,
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_openings() {
+        let leaves: Vec<_> = (1..6).map(BlsScalar::from).collect();
+        let tree = MerkleTree::new(3, &leaves).unwrap();
+        let poseidon = Poseidon::new();
+
+        for index in 0..8 {
+            let leaf = leaves.get(index).copied().unwrap_or_default();
+            let opening = tree.opening(index).unwrap();
+            assert_eq!(opening.root(&poseidon, leaf), tree.root());
+            assert_ne!(
+                opening.root(&poseidon, leaf + BlsScalar::one()),
+                tree.root()
+            );
+        }
+        assert!(tree.opening(8).is_err());
+
+        // Unused leaves are zero
+        let mut padded = leaves.clone();
+        padded.resize(8, BlsScalar::zero());
+        assert_eq!(MerkleTree::new(3, &padded).unwrap().root(), tree.root());
+    }
+
+    #[test]
+    fn test_too_many_leaves() {
+        let leaves = vec![BlsScalar::one(); 5];
+        assert!(MerkleTree::new(2, &leaves).is_err());
+        assert!(MerkleTree::new(2, &leaves[..4]).is_ok());
+    }
+}
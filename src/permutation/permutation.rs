@@ -16,10 +16,19 @@ use std::collections::HashMap;
 /// Permutation provides the necessary state information and functions
 /// to create the permutation polynomial. In the literature, Z(X) is the "accumulator",
 /// this is what this codebase calls the permutation polynomial.  
-#[derive(Debug)]
+///
+/// Variables asserted equal are merged into equivalence classes with a
+/// union-find, and the wires of a class are all mapped to its representative,
+/// so that the permutation constrains them to the same value.
+#[derive(Debug, Clone)]
 pub struct Permutation {
     // Maps a variable to the wires that it is associated to
     pub(crate) variable_map: HashMap<Variable, Vec<WireData>>,
+    // Parent of each variable in the union-find of the equivalence classes,
+    // the representatives being their own parent
+    parents: Vec<Variable>,
+    // Upper bound of the height of the tree below each representative
+    ranks: Vec<u8>,
 }
 
 impl Permutation {
@@ -31,8 +40,40 @@ impl Permutation {
     pub fn with_capacity(expected_size: usize) -> Permutation {
         Permutation {
             variable_map: HashMap::with_capacity(expected_size),
+            parents: Vec::with_capacity(expected_size),
+            ranks: Vec::with_capacity(expected_size),
         }
     }
+
+    /// Rebuilds a permutation from the wires of each variable, where a
+    /// variable placed at the wires of another one was merged into its class
+    /// by `union`.
+    pub(crate) fn from_variable_wires(
+        variable_map: HashMap<Variable, Vec<WireData>>,
+        wire_variables: [&[Variable]; 4],
+    ) -> Permutation {
+        let len = variable_map.len();
+        let mut perm = Permutation {
+            variable_map,
+            parents: (0..len).map(Variable).collect(),
+            ranks: vec![0; len],
+        };
+        for (var, wire_data) in perm.variable_map.iter() {
+            for wire in wire_data.iter() {
+                let placed = match *wire {
+                    WireData::Left(index) => wire_variables[0][index],
+                    WireData::Right(index) => wire_variables[1][index],
+                    WireData::Output(index) => wire_variables[2][index],
+                    WireData::Fourth(index) => wire_variables[3][index],
+                };
+                if placed != *var {
+                    perm.parents[placed.0] = *var;
+                    perm.ranks[var.0] = 1;
+                }
+            }
+        }
+        perm
+    }
     /// Creates a new Variable by incrementing the index of the Variable Map
     /// This is correct as whenever we add a new Variable into the system
     /// It is always allocated in the Variable Map
@@ -44,10 +85,48 @@ impl Permutation {
         // Each vector is initialised with a capacity of 16.
         // This number is a best guess estimate.
         self.variable_map.insert(var, Vec::with_capacity(16usize));
+        self.parents.push(var);
+        self.ranks.push(0);
 
         var
     }
 
+    /// Returns the representative of the equivalence class of `var`, which
+    /// holds the wires of the whole class.
+    pub fn representative(&self, mut var: Variable) -> Variable {
+        while self.parents[var.0] != var {
+            var = self.parents[var.0];
+        }
+        var
+    }
+
+    /// Merges the equivalence classes of `a` and `b`, so that the permutation
+    /// constrains all of their wires to the same value.
+    pub fn union(&mut self, a: Variable, b: Variable) {
+        assert!(self.valid_variables(&[a, b]));
+
+        let (a, b) = (self.representative(a), self.representative(b));
+        if a == b {
+            return;
+        }
+        // Attach the shorter tree below the taller one
+        let (root, child) = if self.ranks[a.0] < self.ranks[b.0] {
+            (b, a)
+        } else {
+            (a, b)
+        };
+        if self.ranks[a.0] == self.ranks[b.0] {
+            self.ranks[root.0] += 1;
+        }
+        self.parents[child.0] = root;
+
+        let child_wires = std::mem::take(self.variable_map.get_mut(&child).unwrap());
+        self.variable_map
+            .get_mut(&root)
+            .unwrap()
+            .extend(child_wires);
+    }
+
     /// Checks that the variables are valid by determining if they have been added to the system
     fn valid_variables(&self, variables: &[Variable]) -> bool {
         let results: Vec<bool> = variables
@@ -86,6 +165,7 @@ impl Permutation {
 
         // Since we always allocate space for the Vec of WireData when a
         // Variable is added to the variable_map, this should never fail
+        let var = self.representative(var);
         let vec_wire_data = self.variable_map.get_mut(&var).unwrap();
         vec_wire_data.push(wire_data);
     }
@@ -0,0 +1,209 @@
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this
+// file, You can obtain one at http://mozilla.org/MPL/2.0/.
+//
+// Copyright (c) DUSK NETWORK. All rights reserved.
+
+//! Native implementation of the Poseidon permutation over `BlsScalar`.
+//!
+//! The permutation works over a state of `WIDTH` scalars and uses the
+//! `x^5` S-box, `FULL_ROUNDS` full rounds split around `PARTIAL_ROUNDS`
+//! partial rounds, and a Cauchy MDS matrix.
+//! The round constants are derived deterministically from a fixed seed, so
+//! every party computes the same permutation.
+//!
+//! `Poseidon::hash` builds a sponge over the permutation, which is mirrored in
+//! the circuit by `StandardComposer::poseidon_hash`.
+
+use dusk_bls12_381::BlsScalar;
+use rand_chacha::ChaChaRng;
+use rand_core::SeedableRng;
+
+/// Number of scalars in the state of the permutation.
+pub const WIDTH: usize = 3;
+/// Number of scalars absorbed by the sponge between permutations.
+pub const RATE: usize = WIDTH - 1;
+/// Number of full rounds, half of them happen before the partial rounds.
+pub const FULL_ROUNDS: usize = 8;
+/// Number of partial rounds, where the S-box is only applied to the first
+/// element of the state.
+pub const PARTIAL_ROUNDS: usize = 57;
+
+/// Seed of the RNG used to derive the round constants.
+const ROUND_CONSTANTS_SEED: [u8; 32] = *b"dusk-plonk poseidon constants 01";
+
+/// The Poseidon permutation parameters.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Poseidon {
+    round_constants: Vec<[BlsScalar; WIDTH]>,
+    mds: [[BlsScalar; WIDTH]; WIDTH],
+}
+
+impl Default for Poseidon {
+    fn default() -> Self {
+        Poseidon::new()
+    }
+}
+
+impl Poseidon {
+    /// Derives the round constants and MDS matrix of the permutation.
+    pub fn new() -> Self {
+        let mut rng = ChaChaRng::from_seed(ROUND_CONSTANTS_SEED);
+        let round_constants = (0..FULL_ROUNDS + PARTIAL_ROUNDS)
+            .map(|_| {
+                [
+                    BlsScalar::random(&mut rng),
+                    BlsScalar::random(&mut rng),
+                    BlsScalar::random(&mut rng),
+                ]
+            })
+            .collect();
+
+        // Cauchy matrix M[i][j] = 1 / (x_i + y_j) with x_i = i and y_j = WIDTH + j
+        let mut mds = [[BlsScalar::zero(); WIDTH]; WIDTH];
+        for (i, row) in mds.iter_mut().enumerate() {
+            for (j, entry) in row.iter_mut().enumerate() {
+                *entry = BlsScalar::from((i + WIDTH + j) as u64).invert().unwrap();
+            }
+        }
+
+        Poseidon {
+            round_constants,
+            mds,
+        }
+    }
+
+    /// Returns the constants added to the state at the beginning of each round.
+    pub fn round_constants(&self) -> &[[BlsScalar; WIDTH]] {
+        &self.round_constants
+    }
+
+    /// Returns the MDS matrix applied to the state at the end of each round.
+    pub fn mds(&self) -> &[[BlsScalar; WIDTH]; WIDTH] {
+        &self.mds
+    }
+
+    /// Returns `true` if the round with the given index is a full round.
+    pub fn is_full_round(round: usize) -> bool {
+        !(FULL_ROUNDS / 2..FULL_ROUNDS / 2 + PARTIAL_ROUNDS).contains(&round)
+    }
+
+    /// Applies the permutation to the `state`.
+    pub fn permute(&self, state: &mut [BlsScalar; WIDTH]) {
+        for (round, constants) in self.round_constants.iter().enumerate() {
+            state
+                .iter_mut()
+                .zip(constants.iter())
+                .for_each(|(s, c)| *s += c);
+
+            if Poseidon::is_full_round(round) {
+                state.iter_mut().for_each(|s| *s = sbox(s));
+            } else {
+                state[0] = sbox(&state[0]);
+            }
+
+            *state = self.apply_mds(state);
+        }
+    }
+
+    /// Hashes `inputs` into a single scalar.
+    ///
+    /// The capacity element is set to the number of inputs, which are then
+    /// absorbed `RATE` at a time, permuting the state after each chunk.
+    pub fn hash(&self, inputs: &[BlsScalar]) -> BlsScalar {
+        let mut state = [BlsScalar::zero(); WIDTH];
+        state[0] = BlsScalar::from(inputs.len() as u64);
+
+        if inputs.is_empty() {
+            self.permute(&mut state);
+        }
+        for chunk in inputs.chunks(RATE) {
+            state[1..]
+                .iter_mut()
+                .zip(chunk.iter())
+                .for_each(|(s, x)| *s += x);
+            self.permute(&mut state);
+        }
+
+        state[1]
+    }
+
+    fn apply_mds(&self, state: &[BlsScalar; WIDTH]) -> [BlsScalar; WIDTH] {
+        let mut result = [BlsScalar::zero(); WIDTH];
+        for (r, row) in result.iter_mut().zip(self.mds.iter()) {
+            *r = row
+                .iter()
+                .zip(state.iter())
+                .fold(BlsScalar::zero(), |acc, (m, s)| acc + m * s);
+        }
+        result
+    }
+}
+
+/// The `x^5` S-box.
+pub(crate) fn sbox(x: &BlsScalar) -> BlsScalar {
+    x.square().square() * x
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_permutation() {
+        let poseidon = Poseidon::new();
+        assert_eq!(poseidon, Poseidon::new());
+
+        let mut state_a = [BlsScalar::zero(), BlsScalar::one(), BlsScalar::from(2)];
+        let mut state_b = [BlsScalar::zero(), BlsScalar::one(), BlsScalar::from(3)];
+        poseidon.permute(&mut state_a);
+        poseidon.permute(&mut state_b);
+
+        // Changing a single input element changes every output element
+        state_a
+            .iter()
+            .zip(state_b.iter())
+            .for_each(|(a, b)| assert_ne!(a, b));
+    }
+
+    #[test]
+    fn test_hash_vectors() {
+        // Pins the parameters of the permutation and the padding of the sponge
+        let vectors: [(&[u64], [u8; 32]); 4] = [
+            (
+                &[],
+                [
+                    92, 247, 154, 166, 165, 208, 92, 165, 80, 169, 129, 222, 206, 234, 89, 43, 245,
+                    228, 60, 206, 204, 219, 173, 125, 198, 254, 195, 202, 183, 78, 231, 60,
+                ],
+            ),
+            (
+                &[1],
+                [
+                    139, 197, 78, 71, 169, 213, 66, 183, 79, 78, 208, 145, 149, 230, 95, 249, 124,
+                    240, 208, 200, 192, 37, 233, 220, 211, 136, 99, 8, 24, 202, 138, 46,
+                ],
+            ),
+            (
+                &[1, 2],
+                [
+                    151, 226, 39, 174, 231, 128, 119, 128, 76, 90, 35, 199, 173, 32, 179, 238, 129,
+                    30, 207, 226, 221, 38, 14, 50, 134, 155, 132, 254, 229, 34, 173, 36,
+                ],
+            ),
+            (
+                &[1, 2, 3],
+                [
+                    154, 52, 97, 128, 221, 255, 174, 117, 221, 48, 110, 207, 184, 21, 43, 112, 74,
+                    23, 115, 30, 107, 178, 182, 106, 224, 177, 19, 108, 105, 112, 153, 80,
+                ],
+            ),
+        ];
+
+        let poseidon = Poseidon::new();
+        for (inputs, expected) in vectors.iter() {
+            let inputs: Vec<_> = inputs.iter().map(|x| BlsScalar::from(*x)).collect();
+            assert_eq!(poseidon.hash(&inputs).to_bytes(), *expected);
+        }
+    }
+}
@@ -10,16 +10,21 @@
 //! with the principal data structures of the plonk library.
 //!
 
-pub use crate::circuit_builder::{Circuit, CircuitErrors, PublicInput};
+pub use crate::circuit_builder::{Circuit, CircuitErrors, CircuitShape};
 pub use crate::commitment_scheme::kzg10::{
     key::{CommitKey, OpeningKey},
     PublicParameters,
 };
-pub use crate::constraint_system::{StandardComposer, Variable};
+pub use crate::constraint_system::{
+    CircuitDescription, GateCountReport, LookupTable, StandardComposer, Variable,
+};
+pub use crate::merkle::{MerkleOpening, MerkleTree};
+pub use crate::poseidon::Poseidon;
 pub use crate::proof_system::{
     widget::{ProverKey, VerifierKey},
-    Proof, Prover, Verifier,
+    CustomGate, GateWires, Proof, Prover, PublicInputLayout, Verifier,
 };
+pub use crate::transcript::{PoseidonTranscript, TranscriptProtocol};
 
 /// Re-exported `dusk-bls12_381::BlsScalar`.
 pub use dusk_bls12_381::BlsScalar;
@@ -30,7 +35,9 @@ pub use dusk_jubjub::JubJubScalar;
 /// Collection of errors that the library exposes/uses.
 pub mod plonk_errors {
     pub use crate::commitment_scheme::kzg10::errors::KZG10Errors;
-    pub use crate::constraint_system::cs_errors::PreProcessingError;
+    pub use crate::constraint_system::cs_errors::{CircuitDescriptionError, PreProcessingError};
+    pub use crate::constraint_system::{GateKind, UnsatisfiedGate};
     pub use crate::fft::fft_errors::FFTErrors;
+    pub use crate::merkle::MerkleTreeErrors;
     pub use crate::proof_system::proof_system_errors::ProofErrors;
 }
@@ -5,7 +5,7 @@
 // Copyright (c) DUSK NETWORK. All rights reserved.
 
 use crate::fft::{EvaluationDomain, Polynomial};
-use crate::proof_system::widget::ProverKey;
+use crate::proof_system::widget::{custom::GateWires, ProverKey};
 use anyhow::{Error, Result};
 use dusk_bls12_381::BlsScalar;
 /// Evaluations at points `z` or and `z * root of unity`
@@ -36,10 +36,6 @@ pub struct ProofEvaluations {
     pub q_arith_eval: BlsScalar,
     //
     pub q_c_eval: BlsScalar,
-    //
-    pub q_l_eval: BlsScalar,
-    //
-    pub q_r_eval: BlsScalar,
     // Evaluation of the left sigma polynomial at `z`
     pub left_sigma_eval: BlsScalar,
     // Evaluation of the right sigma polynomial at `z`
@@ -52,6 +48,21 @@ pub struct ProofEvaluations {
 
     // (Shifted) Evaluation of the permutation polynomial at `z * root of unity`
     pub perm_eval: BlsScalar,
+
+    // Evaluation of the lookup query polynomial at `z`
+    pub f_eval: BlsScalar,
+    // Evaluation of the first half of the sorted lookup vector at `z`
+    pub h_1_eval: BlsScalar,
+    // Evaluation of the first half of the sorted lookup vector at `z * root of unity`
+    pub h_1_next_eval: BlsScalar,
+    // Evaluation of the second half of the sorted lookup vector at `z`
+    pub h_2_eval: BlsScalar,
+    // Evaluation of the compressed lookup table polynomial at `z`
+    pub table_eval: BlsScalar,
+    // Evaluation of the compressed lookup table polynomial at `z * root of unity`
+    pub table_next_eval: BlsScalar,
+    // (Shifted) Evaluation of the lookup grand product polynomial at `z * root of unity`
+    pub lookup_perm_eval: BlsScalar,
 }
 
 impl ProofEvaluations {
@@ -68,13 +79,18 @@ impl ProofEvaluations {
         bytes[192..224].copy_from_slice(&self.d_next_eval.to_bytes()[..]);
         bytes[224..256].copy_from_slice(&self.q_arith_eval.to_bytes()[..]);
         bytes[256..288].copy_from_slice(&self.q_c_eval.to_bytes()[..]);
-        bytes[288..320].copy_from_slice(&self.q_l_eval.to_bytes()[..]);
-        bytes[320..352].copy_from_slice(&self.q_r_eval.to_bytes()[..]);
-        bytes[352..384].copy_from_slice(&self.left_sigma_eval.to_bytes()[..]);
-        bytes[384..416].copy_from_slice(&self.right_sigma_eval.to_bytes()[..]);
-        bytes[416..448].copy_from_slice(&self.out_sigma_eval.to_bytes()[..]);
-        bytes[448..480].copy_from_slice(&self.lin_poly_eval.to_bytes()[..]);
-        bytes[480..512].copy_from_slice(&self.perm_eval.to_bytes()[..]);
+        bytes[288..320].copy_from_slice(&self.left_sigma_eval.to_bytes()[..]);
+        bytes[320..352].copy_from_slice(&self.right_sigma_eval.to_bytes()[..]);
+        bytes[352..384].copy_from_slice(&self.out_sigma_eval.to_bytes()[..]);
+        bytes[384..416].copy_from_slice(&self.lin_poly_eval.to_bytes()[..]);
+        bytes[416..448].copy_from_slice(&self.perm_eval.to_bytes()[..]);
+        bytes[448..480].copy_from_slice(&self.f_eval.to_bytes()[..]);
+        bytes[480..512].copy_from_slice(&self.h_1_eval.to_bytes()[..]);
+        bytes[512..544].copy_from_slice(&self.h_1_next_eval.to_bytes()[..]);
+        bytes[544..576].copy_from_slice(&self.h_2_eval.to_bytes()[..]);
+        bytes[576..608].copy_from_slice(&self.table_eval.to_bytes()[..]);
+        bytes[608..640].copy_from_slice(&self.table_next_eval.to_bytes()[..]);
+        bytes[640..672].copy_from_slice(&self.lookup_perm_eval.to_bytes()[..]);
 
         bytes
     }
@@ -95,13 +111,18 @@ impl ProofEvaluations {
         let (d_next_eval, rest) = read_scalar(rest)?;
         let (q_arith_eval, rest) = read_scalar(rest)?;
         let (q_c_eval, rest) = read_scalar(rest)?;
-        let (q_l_eval, rest) = read_scalar(rest)?;
-        let (q_r_eval, rest) = read_scalar(rest)?;
         let (left_sigma_eval, rest) = read_scalar(rest)?;
         let (right_sigma_eval, rest) = read_scalar(rest)?;
         let (out_sigma_eval, rest) = read_scalar(rest)?;
         let (lin_poly_eval, rest) = read_scalar(rest)?;
-        let (perm_eval, _) = read_scalar(rest)?;
+        let (perm_eval, rest) = read_scalar(rest)?;
+        let (f_eval, rest) = read_scalar(rest)?;
+        let (h_1_eval, rest) = read_scalar(rest)?;
+        let (h_1_next_eval, rest) = read_scalar(rest)?;
+        let (h_2_eval, rest) = read_scalar(rest)?;
+        let (table_eval, rest) = read_scalar(rest)?;
+        let (table_next_eval, rest) = read_scalar(rest)?;
+        let (lookup_perm_eval, _) = read_scalar(rest)?;
 
         let proof_evals = ProofEvaluations {
             a_eval,
@@ -113,19 +134,24 @@ impl ProofEvaluations {
             d_next_eval,
             q_arith_eval,
             q_c_eval,
-            q_l_eval,
-            q_r_eval,
             left_sigma_eval,
             right_sigma_eval,
             out_sigma_eval,
             lin_poly_eval,
             perm_eval,
+            f_eval,
+            h_1_eval,
+            h_1_next_eval,
+            h_2_eval,
+            table_eval,
+            table_next_eval,
+            lookup_perm_eval,
         };
         Ok(proof_evals)
     }
 
     pub const fn serialised_size() -> usize {
-        const NUM_SCALARS: usize = 16;
+        const NUM_SCALARS: usize = 21;
         const SCALAR_SIZE: usize = 32;
         NUM_SCALARS * SCALAR_SIZE
     }
@@ -142,8 +168,9 @@ pub fn compute(
         gamma,
         range_separation_challenge,
         logic_separation_challenge,
-        fixed_base_separation_challenge,
         var_base_separation_challenge,
+        lookup_separation_challenge,
+        sbox_separation_challenge,
         z_challenge,
     ): &(
         BlsScalar,
@@ -154,14 +181,18 @@ pub fn compute(
         BlsScalar,
         BlsScalar,
         BlsScalar,
+        BlsScalar,
     ),
+    (zeta, delta, epsilon): &(BlsScalar, BlsScalar, BlsScalar),
+    custom_challenges: &[BlsScalar],
     w_l_poly: &Polynomial,
     w_r_poly: &Polynomial,
     w_o_poly: &Polynomial,
     w_4_poly: &Polynomial,
     t_x_poly: &Polynomial,
     z_poly: &Polynomial,
-) -> (Polynomial, Evaluations) {
+    (f_poly, h_1_poly, h_2_poly, p_poly): (&Polynomial, &Polynomial, &Polynomial, &Polynomial),
+) -> Result<(Polynomial, Evaluations), Error> {
     // Compute evaluations
     let quot_eval = t_x_poly.evaluate(z_challenge);
     let a_eval = w_l_poly.evaluate(z_challenge);
@@ -173,21 +204,29 @@ pub fn compute(
     let out_sigma_eval = prover_key.permutation.out_sigma.0.evaluate(z_challenge);
     let q_arith_eval = prover_key.arithmetic.q_arith.0.evaluate(z_challenge);
     let q_c_eval = prover_key.logic.q_c.0.evaluate(z_challenge);
-    let q_l_eval = prover_key.fixed_base.q_l.0.evaluate(z_challenge);
-    let q_r_eval = prover_key.fixed_base.q_r.0.evaluate(z_challenge);
 
     let a_next_eval = w_l_poly.evaluate(&(z_challenge * domain.group_gen));
     let b_next_eval = w_r_poly.evaluate(&(z_challenge * domain.group_gen));
     let d_next_eval = w_4_poly.evaluate(&(z_challenge * domain.group_gen));
     let perm_eval = z_poly.evaluate(&(z_challenge * domain.group_gen));
 
+    let table_poly = prover_key.lookup.compute_table_poly(zeta);
+    let f_eval = f_poly.evaluate(z_challenge);
+    let h_1_eval = h_1_poly.evaluate(z_challenge);
+    let h_2_eval = h_2_poly.evaluate(z_challenge);
+    let table_eval = table_poly.evaluate(z_challenge);
+    let h_1_next_eval = h_1_poly.evaluate(&(z_challenge * domain.group_gen));
+    let table_next_eval = table_poly.evaluate(&(z_challenge * domain.group_gen));
+    let lookup_perm_eval = p_poly.evaluate(&(z_challenge * domain.group_gen));
+
     let f_1 = compute_circuit_satisfiability(
         (
             range_separation_challenge,
             logic_separation_challenge,
-            fixed_base_separation_challenge,
             var_base_separation_challenge,
+            sbox_separation_challenge,
         ),
+        custom_challenges,
         &a_eval,
         &b_eval,
         &c_eval,
@@ -197,12 +236,11 @@ pub fn compute(
         &d_next_eval,
         &q_arith_eval,
         &q_c_eval,
-        &q_l_eval,
-        &q_r_eval,
         prover_key,
-    );
+    )?;
 
     let f_2 = prover_key.permutation.compute_linearisation(
+        domain,
         z_challenge,
         (alpha, beta, gamma),
         (&a_eval, &b_eval, &c_eval, &d_eval),
@@ -211,12 +249,25 @@ pub fn compute(
         z_poly,
     );
 
-    let lin_poly = &f_1 + &f_2;
+    let f_3 = prover_key.lookup.compute_linearisation(
+        domain,
+        lookup_separation_challenge,
+        (zeta, delta, epsilon),
+        z_challenge,
+        (&a_eval, &b_eval, &c_eval),
+        &q_c_eval,
+        &f_eval,
+        (&table_eval, &table_next_eval),
+        p_poly,
+    );
+
+    let mut lin_poly = &f_1 + &f_2;
+    lin_poly += &f_3;
 
     // Evaluate linearisation polynomial at z_challenge
     let lin_poly_eval = lin_poly.evaluate(z_challenge);
 
-    (
+    Ok((
         lin_poly,
         Evaluations {
             proof: ProofEvaluations {
@@ -229,17 +280,22 @@ pub fn compute(
                 d_next_eval,
                 q_arith_eval,
                 q_c_eval,
-                q_l_eval,
-                q_r_eval,
                 left_sigma_eval,
                 right_sigma_eval,
                 out_sigma_eval,
                 lin_poly_eval,
                 perm_eval,
+                f_eval,
+                h_1_eval,
+                h_1_next_eval,
+                h_2_eval,
+                table_eval,
+                table_next_eval,
+                lookup_perm_eval,
             },
             quot_eval,
         },
-    )
+    ))
 }
 
 #[allow(clippy::too_many_arguments)]
@@ -247,9 +303,10 @@ fn compute_circuit_satisfiability(
     (
         range_separation_challenge,
         logic_separation_challenge,
-        fixed_base_separation_challenge,
         var_base_separation_challenge,
+        sbox_separation_challenge,
     ): (&BlsScalar, &BlsScalar, &BlsScalar, &BlsScalar),
+    custom_challenges: &[BlsScalar],
     a_eval: &BlsScalar,
     b_eval: &BlsScalar,
     c_eval: &BlsScalar,
@@ -259,10 +316,8 @@ fn compute_circuit_satisfiability(
     d_next_eval: &BlsScalar,
     q_arith_eval: &BlsScalar,
     q_c_eval: &BlsScalar,
-    q_l_eval: &BlsScalar,
-    q_r_eval: &BlsScalar,
     prover_key: &ProverKey,
-) -> Polynomial {
+) -> Result<Polynomial, Error> {
     let a =
         prover_key
             .arithmetic
@@ -289,8 +344,8 @@ fn compute_circuit_satisfiability(
         q_c_eval,
     );
 
-    let d = prover_key.fixed_base.compute_linearisation(
-        fixed_base_separation_challenge,
+    let d = prover_key.variable_base.compute_linearisation(
+        var_base_separation_challenge,
         a_eval,
         a_next_eval,
         b_eval,
@@ -298,20 +353,14 @@ fn compute_circuit_satisfiability(
         c_eval,
         d_eval,
         d_next_eval,
-        q_l_eval,
-        q_r_eval,
-        q_c_eval,
     );
 
-    let e = prover_key.variable_base.compute_linearisation(
-        var_base_separation_challenge,
+    let e = prover_key.sbox.compute_linearisation(
+        sbox_separation_challenge,
         a_eval,
-        a_next_eval,
-        b_eval,
-        b_next_eval,
         c_eval,
         d_eval,
-        d_next_eval,
+        q_c_eval,
     );
 
     let mut linearisation_poly = &a + &b;
@@ -319,5 +368,18 @@ fn compute_circuit_satisfiability(
     linearisation_poly += &d;
     linearisation_poly += &e;
 
-    linearisation_poly
+    let wires = GateWires {
+        a: *a_eval,
+        b: *b_eval,
+        c: *c_eval,
+        d: *d_eval,
+        a_next: *a_next_eval,
+        b_next: *b_next_eval,
+        d_next: *d_next_eval,
+    };
+    for (custom, challenge) in prover_key.custom.iter().zip(custom_challenges.iter()) {
+        linearisation_poly += &custom.compute_linearisation(challenge, &wires)?;
+    }
+
+    Ok(linearisation_poly)
 }
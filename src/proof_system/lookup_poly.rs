@@ -0,0 +1,137 @@
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this
+// file, You can obtain one at http://mozilla.org/MPL/2.0/.
+//
+// Copyright (c) DUSK NETWORK. All rights reserved.
+
+//! Polynomials of the lookup argument
+//!
+//! The query values `f` must all be contained in the compressed table `t`.
+//! Following plookup, `f` and `t` are merged into a vector `s` sorted by `t`,
+//! which is split in an alternating fashion into `h_1` and `h_2`, and a grand
+//! product `p` checks that `s` is made of the values of `f` and `t`.
+
+use crate::fft::{EvaluationDomain, Polynomial};
+use crate::proof_system::widget::lookup::{lookup_denominator, lookup_numerator};
+use crate::util::batch_inversion;
+use dusk_bls12_381::BlsScalar;
+use std::collections::BTreeMap;
+
+/// Computes the evaluations of `h_1` and `h_2` over the domain, given the query
+/// values `f` and the table values `t`.
+///
+/// Query values which are not in the table are appended at the end of the sorted
+/// vector, so that the grand product check fails.
+pub(crate) fn compute_sorted_evals(
+    domain: &EvaluationDomain,
+    f: &[BlsScalar],
+    t: &[BlsScalar],
+) -> (Vec<BlsScalar>, Vec<BlsScalar>) {
+    let n = domain.size();
+    assert_eq!(f.len(), n);
+    assert_eq!(t.len(), n);
+
+    // Count how many times each query value appears
+    let mut queries: BTreeMap<BlsScalar, usize> = BTreeMap::new();
+    for value in f {
+        *queries.entry(*value).or_insert(0) += 1;
+    }
+
+    // Sort `f` by `t`, placing every query next to the first occurrence of its value
+    let mut s = Vec::with_capacity(2 * n);
+    for value in t {
+        s.push(*value);
+        if let Some(count) = queries.remove(value) {
+            s.resize(s.len() + count, *value);
+        }
+    }
+    for (value, count) in queries {
+        s.resize(s.len() + count, value);
+    }
+
+    // h_1 holds the even positions of `s` and h_2 the odd ones
+    let h_1 = s.iter().step_by(2).cloned().collect();
+    let h_2 = s.iter().skip(1).step_by(2).cloned().collect();
+    (h_1, h_2)
+}
+
+/// Computes the lookup grand product polynomial `p(X)`, such that `p(1) = 1` and
+/// `p(Xw) * D(X) = p(X) * N(X)` over the domain.
+pub(crate) fn compute_grand_product_poly(
+    domain: &EvaluationDomain,
+    (delta, epsilon): (&BlsScalar, &BlsScalar),
+    f: &[BlsScalar],
+    t: &[BlsScalar],
+    (h_1, h_2): (&[BlsScalar], &[BlsScalar]),
+) -> Polynomial {
+    let n = domain.size();
+
+    let mut denominators: Vec<BlsScalar> = (0..n)
+        .map(|i| lookup_denominator((delta, epsilon), &h_1[i], &h_2[i], &h_1[(i + 1) % n]))
+        .collect();
+    batch_inversion(&mut denominators);
+
+    let mut p = Vec::with_capacity(n);
+
+    // First element is one
+    let mut state = BlsScalar::one();
+    p.push(state);
+
+    // Accumulate the product, dropping the last (n+1'th) element, which is one
+    // when every query value is in the table
+    for i in 0..n - 1 {
+        state *=
+            lookup_numerator((delta, epsilon), &f[i], &t[i], &t[(i + 1) % n]) * denominators[i];
+        p.push(state);
+    }
+
+    Polynomial::from_coefficients_vec(domain.ifft(&p))
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn grand_product_closes(f: &[BlsScalar], t: &[BlsScalar]) -> bool {
+        let domain = EvaluationDomain::new(f.len()).unwrap();
+        let delta = BlsScalar::random(&mut rand::thread_rng());
+        let epsilon = BlsScalar::random(&mut rand::thread_rng());
+
+        let (h_1, h_2) = compute_sorted_evals(&domain, f, t);
+        let p = domain.fft(&compute_grand_product_poly(
+            &domain,
+            (&delta, &epsilon),
+            f,
+            t,
+            (&h_1, &h_2),
+        ));
+
+        let n = domain.size();
+        let last = p[n - 1]
+            * lookup_numerator((&delta, &epsilon), &f[n - 1], &t[n - 1], &t[0])
+            * lookup_denominator((&delta, &epsilon), &h_1[n - 1], &h_2[n - 1], &h_1[0])
+                .invert()
+                .unwrap();
+        last == BlsScalar::one()
+    }
+
+    #[test]
+    fn test_grand_product() {
+        let t: Vec<_> = [0u64, 3, 5, 7, 0, 0, 0, 0]
+            .iter()
+            .map(|v| BlsScalar::from(*v))
+            .collect();
+
+        let f: Vec<_> = [5u64, 0, 7, 7, 3, 0, 0, 5]
+            .iter()
+            .map(|v| BlsScalar::from(*v))
+            .collect();
+        assert!(grand_product_closes(&f, &t));
+
+        let f: Vec<_> = [5u64, 0, 7, 7, 4, 0, 0, 5]
+            .iter()
+            .map(|v| BlsScalar::from(*v))
+            .collect();
+        assert!(!grand_product_closes(&f, &t));
+    }
+}
@@ -7,6 +7,7 @@
 //! proving system
 
 pub(crate) mod linearisation_poly;
+pub(crate) mod lookup_poly;
 mod preprocess;
 
 /// Represents a PLONK Proof
@@ -14,6 +15,8 @@ pub mod proof;
 pub(crate) mod proof_system_errors;
 /// Represents a PLONK Prover
 pub mod prover;
+/// Names and positions of the public inputs of a circuit
+pub mod public_inputs;
 pub(crate) mod quotient_poly;
 /// Represents a PLONK Verifier
 pub mod verifier;
@@ -21,5 +24,7 @@ pub(crate) mod widget;
 
 pub use proof::Proof;
 pub use prover::Prover;
+pub use public_inputs::PublicInputLayout;
 pub use verifier::Verifier;
+pub use widget::custom::{CustomGate, GateWires};
 pub use widget::{ProverKey, VerifierKey};
@@ -14,7 +14,6 @@ use crate::fft::{EvaluationDomain, Evaluations, Polynomial};
 use crate::proof_system::widget;
 use anyhow::{Error, Result};
 use dusk_bls12_381::BlsScalar;
-use merlin::Transcript;
 
 /// Struct that contains all of the selector and permutation polynomials in PLONK
 /// These polynomials are in coefficient form
@@ -28,12 +27,18 @@ pub(crate) struct SelectorPolynomials {
     q_arith: Polynomial,
     q_range: Polynomial,
     q_logic: Polynomial,
-    q_fixed_group_add: Polynomial,
     q_variable_group_add: Polynomial,
+    q_lookup: Polynomial,
+    q_sbox: Polynomial,
+    table_1: Polynomial,
+    table_2: Polynomial,
+    table_3: Polynomial,
+    table_4: Polynomial,
     left_sigma: Polynomial,
     right_sigma: Polynomial,
     out_sigma: Polynomial,
     fourth_sigma: Polynomial,
+    custom: Vec<Vec<Polynomial>>,
 }
 
 impl StandardComposer {
@@ -56,14 +61,19 @@ impl StandardComposer {
         self.q_arith.extend(zeroes_scalar.iter());
         self.q_range.extend(zeroes_scalar.iter());
         self.q_logic.extend(zeroes_scalar.iter());
-        self.q_fixed_group_add.extend(zeroes_scalar.iter());
         self.q_variable_group_add.extend(zeroes_scalar.iter());
+        self.q_lookup.extend(zeroes_scalar.iter());
+        self.q_sbox.extend(zeroes_scalar.iter());
 
         self.w_l.extend(zeroes_var.iter());
         self.w_r.extend(zeroes_var.iter());
         self.w_o.extend(zeroes_var.iter());
         self.w_4.extend(zeroes_var.iter());
 
+        for custom in self.custom_gates.iter_mut() {
+            custom.pad(self.n + diff);
+        }
+
         self.n += diff;
     }
     /// Checks that all of the wires of the composer have the same
@@ -79,8 +89,9 @@ impl StandardComposer {
             && self.q_arith.len() == k
             && self.q_range.len() == k
             && self.q_logic.len() == k
-            && self.q_fixed_group_add.len() == k
             && self.q_variable_group_add.len() == k
+            && self.q_lookup.len() == k
+            && self.q_sbox.len() == k
             && self.w_l.len() == k
             && self.w_r.len() == k
             && self.w_o.len() == k
@@ -92,127 +103,194 @@ impl StandardComposer {
     }
     /// These are the parts of preprocessing that the prover must compute
     /// Although the prover does not need the verification key, he must compute the commitments
-    /// in order to seed the transcript, allowing both the prover and verifier to have the same view
+    /// in order to store its digest, allowing both the prover and verifier to have the same view
     pub fn preprocess_prover(
         &mut self,
         commit_key: &CommitKey,
-        transcript: &mut Transcript,
     ) -> Result<widget::ProverKey, Error> {
-        let (_, selectors, domain) = self.preprocess_shared(commit_key, transcript)?;
-
-        let domain_4n = EvaluationDomain::new(4 * domain.size())?;
-        let q_m_eval_4n =
-            Evaluations::from_vec_and_domain(domain_4n.coset_fft(&selectors.q_m), domain_4n);
-        let q_l_eval_4n =
-            Evaluations::from_vec_and_domain(domain_4n.coset_fft(&selectors.q_l), domain_4n);
-        let q_r_eval_4n =
-            Evaluations::from_vec_and_domain(domain_4n.coset_fft(&selectors.q_r), domain_4n);
-        let q_o_eval_4n =
-            Evaluations::from_vec_and_domain(domain_4n.coset_fft(&selectors.q_o), domain_4n);
-        let q_c_eval_4n =
-            Evaluations::from_vec_and_domain(domain_4n.coset_fft(&selectors.q_c), domain_4n);
-        let q_4_eval_4n =
-            Evaluations::from_vec_and_domain(domain_4n.coset_fft(&selectors.q_4), domain_4n);
-        let q_arith_eval_4n =
-            Evaluations::from_vec_and_domain(domain_4n.coset_fft(&selectors.q_arith), domain_4n);
-        let q_range_eval_4n =
-            Evaluations::from_vec_and_domain(domain_4n.coset_fft(&selectors.q_range), domain_4n);
-        let q_logic_eval_4n =
-            Evaluations::from_vec_and_domain(domain_4n.coset_fft(&selectors.q_logic), domain_4n);
-        let q_fixed_group_add_eval_4n = Evaluations::from_vec_and_domain(
-            domain_4n.coset_fft(&selectors.q_fixed_group_add),
-            domain_4n,
-        );
-        let q_variable_group_add_eval_4n = Evaluations::from_vec_and_domain(
-            domain_4n.coset_fft(&selectors.q_variable_group_add),
-            domain_4n,
-        );
-
-        let left_sigma_eval_4n =
-            Evaluations::from_vec_and_domain(domain_4n.coset_fft(&selectors.left_sigma), domain_4n);
-        let right_sigma_eval_4n = Evaluations::from_vec_and_domain(
-            domain_4n.coset_fft(&selectors.right_sigma),
-            domain_4n,
-        );
-        let out_sigma_eval_4n =
-            Evaluations::from_vec_and_domain(domain_4n.coset_fft(&selectors.out_sigma), domain_4n);
-        let fourth_sigma_eval_4n = Evaluations::from_vec_and_domain(
-            domain_4n.coset_fft(&selectors.fourth_sigma),
-            domain_4n,
-        );
+        // The digest is taken before the circuit is padded
+        let circuit_digest = self.circuit_digest();
+        let (verifier_key, selectors, domain) = self.preprocess_shared(commit_key)?;
+        self.prover_key(
+            selectors,
+            &domain,
+            (verifier_key.digest(), circuit_digest),
+            true,
+        )
+    }
+
+    /// Computes the `ProverKey` of a low-memory `Prover`, which holds the
+    /// coefficients of the selector and permutation polynomials but not
+    /// their evaluations over the 8n coset.
+    pub(crate) fn preprocess_prover_low_memory(
+        &mut self,
+        commit_key: &CommitKey,
+    ) -> Result<widget::ProverKey, Error> {
+        let circuit_digest = self.circuit_digest();
+        let (verifier_key, selectors, domain) = self.preprocess_shared(commit_key)?;
+        self.prover_key(
+            selectors,
+            &domain,
+            (verifier_key.digest(), circuit_digest),
+            false,
+        )
+    }
+
+    /// Computes both the `ProverKey` and the `VerifierKey` of the circuit,
+    /// committing to its selectors and permutation polynomials only once.
+    pub fn preprocess(
+        &mut self,
+        commit_key: &CommitKey,
+    ) -> Result<(widget::ProverKey, widget::VerifierKey), Error> {
+        // The digest is taken before the circuit is padded
+        let circuit_digest = self.circuit_digest();
+        let (verifier_key, selectors, domain) = self.preprocess_shared(commit_key)?;
+        let prover_key = self.prover_key(
+            selectors,
+            &domain,
+            (verifier_key.digest(), circuit_digest),
+            true,
+        )?;
+        Ok((prover_key, verifier_key))
+    }
+
+    /// Computes the 8n evaluations of the selectors if `coset_evaluations` is
+    /// set, and builds the `ProverKey` from them.
+    fn prover_key(
+        &self,
+        selectors: SelectorPolynomials,
+        domain: &EvaluationDomain,
+        (verifier_key_digest, circuit_digest): (BlsScalar, BlsScalar),
+        coset_evaluations: bool,
+    ) -> Result<widget::ProverKey, Error> {
+        let domain_8n = EvaluationDomain::new(8 * domain.size())?;
+        let evaluate = |coeffs: &[BlsScalar]| {
+            let evals = if coset_evaluations {
+                domain_8n.coset_fft(coeffs)
+            } else {
+                vec![]
+            };
+            Evaluations::from_vec_and_domain(evals, domain_8n.clone())
+        };
+        let q_m_eval_8n = evaluate(&selectors.q_m);
+        let q_l_eval_8n = evaluate(&selectors.q_l);
+        let q_r_eval_8n = evaluate(&selectors.q_r);
+        let q_o_eval_8n = evaluate(&selectors.q_o);
+        let q_c_eval_8n = evaluate(&selectors.q_c);
+        let q_4_eval_8n = evaluate(&selectors.q_4);
+        let q_arith_eval_8n = evaluate(&selectors.q_arith);
+        let q_range_eval_8n = evaluate(&selectors.q_range);
+        let q_logic_eval_8n = evaluate(&selectors.q_logic);
+        let q_variable_group_add_eval_8n = evaluate(&selectors.q_variable_group_add);
+        let q_lookup_eval_8n = evaluate(&selectors.q_lookup);
+        let q_sbox_eval_8n = evaluate(&selectors.q_sbox);
+
+        let left_sigma_eval_8n = evaluate(&selectors.left_sigma);
+        let right_sigma_eval_8n = evaluate(&selectors.right_sigma);
+        let out_sigma_eval_8n = evaluate(&selectors.out_sigma);
+        let fourth_sigma_eval_8n = evaluate(&selectors.fourth_sigma);
         // XXX: Remove this and compute it on the fly
-        let linear_eval_4n = Evaluations::from_vec_and_domain(
-            domain_4n.coset_fft(&[BlsScalar::zero(), BlsScalar::one()]),
-            domain_4n,
-        );
+        let linear_eval_8n = evaluate(&[BlsScalar::zero(), BlsScalar::one()]);
 
         // Prover Key for arithmetic circuits
         let arithmetic_prover_key = widget::arithmetic::ProverKey {
-            q_m: (selectors.q_m, q_m_eval_4n),
-            q_l: (selectors.q_l.clone(), q_l_eval_4n.clone()),
-            q_r: (selectors.q_r.clone(), q_r_eval_4n.clone()),
-            q_o: (selectors.q_o, q_o_eval_4n),
-            q_c: (selectors.q_c.clone(), q_c_eval_4n.clone()),
-            q_4: (selectors.q_4, q_4_eval_4n),
-            q_arith: (selectors.q_arith, q_arith_eval_4n),
+            q_m: (selectors.q_m, q_m_eval_8n),
+            q_l: (selectors.q_l, q_l_eval_8n),
+            q_r: (selectors.q_r, q_r_eval_8n),
+            q_o: (selectors.q_o, q_o_eval_8n),
+            q_c: (selectors.q_c.clone(), q_c_eval_8n.clone()),
+            q_4: (selectors.q_4, q_4_eval_8n),
+            q_arith: (selectors.q_arith, q_arith_eval_8n),
         };
 
         // Prover Key for range circuits
         let range_prover_key = widget::range::ProverKey {
-            q_range: (selectors.q_range, q_range_eval_4n),
+            q_range: (selectors.q_range, q_range_eval_8n),
         };
 
         // Prover Key for logic circuits
         let logic_prover_key = widget::logic::ProverKey {
-            q_c: (selectors.q_c.clone(), q_c_eval_4n.clone()),
-            q_logic: (selectors.q_logic, q_logic_eval_4n),
-        };
-
-        // Prover Key for ecc circuits
-        let ecc_prover_key = widget::ecc::scalar_mul::fixed_base::ProverKey {
-            q_l: (selectors.q_l, q_l_eval_4n),
-            q_r: (selectors.q_r, q_r_eval_4n),
-            q_c: (selectors.q_c, q_c_eval_4n),
-            q_fixed_group_add: (selectors.q_fixed_group_add, q_fixed_group_add_eval_4n),
+            q_c: (selectors.q_c.clone(), q_c_eval_8n.clone()),
+            q_logic: (selectors.q_logic, q_logic_eval_8n),
         };
 
         // Prover Key for permutation argument
         let permutation_prover_key = widget::permutation::ProverKey {
-            left_sigma: (selectors.left_sigma, left_sigma_eval_4n),
-            right_sigma: (selectors.right_sigma, right_sigma_eval_4n),
-            out_sigma: (selectors.out_sigma, out_sigma_eval_4n),
-            fourth_sigma: (selectors.fourth_sigma, fourth_sigma_eval_4n),
-            linear_evaluations: linear_eval_4n,
+            left_sigma: (selectors.left_sigma, left_sigma_eval_8n),
+            right_sigma: (selectors.right_sigma, right_sigma_eval_8n),
+            out_sigma: (selectors.out_sigma, out_sigma_eval_8n),
+            fourth_sigma: (selectors.fourth_sigma, fourth_sigma_eval_8n),
+            linear_evaluations: linear_eval_8n,
         };
 
         // Prover Key for curve addition
         let curve_addition_prover_key = widget::ecc::curve_addition::ProverKey {
-            q_variable_group_add: (selectors.q_variable_group_add, q_variable_group_add_eval_4n),
+            q_variable_group_add: (selectors.q_variable_group_add, q_variable_group_add_eval_8n),
+        };
+
+        // Prover Key for lookup gates
+        let lookup_prover_key = widget::lookup::ProverKey {
+            q_lookup: (selectors.q_lookup, q_lookup_eval_8n),
+            q_c: (selectors.q_c.clone(), q_c_eval_8n.clone()),
+            table_1: selectors.table_1,
+            table_2: selectors.table_2,
+            table_3: selectors.table_3,
+            table_4: selectors.table_4,
+        };
+
+        // Prover Key for Poseidon S-box gates
+        let sbox_prover_key = widget::sbox::ProverKey {
+            q_c: (selectors.q_c, q_c_eval_8n),
+            q_sbox: (selectors.q_sbox, q_sbox_eval_8n),
         };
 
+        // Prover Keys for custom gates
+        let custom_prover_keys = self
+            .custom_gates
+            .iter()
+            .zip(selectors.custom)
+            .map(|(custom, polys)| widget::custom::ProverKey {
+                gate: custom.gate.clone(),
+                selectors: polys
+                    .into_iter()
+                    .map(|poly| {
+                        let evals = evaluate(&poly);
+                        (poly, evals)
+                    })
+                    .collect(),
+            })
+            .collect();
+
         let prover_key = widget::ProverKey {
             n: domain.size(),
+            verifier_key_digest,
+            circuit_digest,
             arithmetic: arithmetic_prover_key,
             logic: logic_prover_key,
             range: range_prover_key,
             permutation: permutation_prover_key,
             variable_base: curve_addition_prover_key,
-            fixed_base: ecc_prover_key,
-            // Compute 4n evaluations for X^n -1
-            v_h_coset_4n: domain_4n.compute_vanishing_poly_over_coset(domain.size() as u64),
+            lookup: lookup_prover_key,
+            sbox: sbox_prover_key,
+            custom: custom_prover_keys,
+            // Compute 8n evaluations for X^n -1
+            v_h_coset_8n: if coset_evaluations {
+                domain_8n.compute_vanishing_poly_over_coset(domain.size() as u64)
+            } else {
+                evaluate(&[])
+            },
         };
 
         Ok(prover_key)
     }
     /// The verifier only requires the commitments in order to verify a proof
     /// We can therefore speed up preprocessing for the verifier by skipping the FFTs
-    /// needed to compute the 4n evaluations
+    /// needed to compute the 8n evaluations
     pub fn preprocess_verifier(
         &mut self,
         commit_key: &CommitKey,
-        transcript: &mut Transcript,
     ) -> Result<widget::VerifierKey, Error> {
-        let (verifier_key, _, _) = self.preprocess_shared(commit_key, transcript)?;
+        let (verifier_key, _, _) = self.preprocess_shared(commit_key)?;
         Ok(verifier_key)
     }
     // Both the prover and verifier must perform IFFTs on the selector polynomials and permutation polynomials
@@ -220,9 +298,8 @@ impl StandardComposer {
     fn preprocess_shared(
         &mut self,
         commit_key: &CommitKey,
-        transcript: &mut Transcript,
     ) -> Result<(widget::VerifierKey, SelectorPolynomials, EvaluationDomain), Error> {
-        let domain = EvaluationDomain::new(self.circuit_size())?;
+        let domain = EvaluationDomain::new(self.domain_size())?;
 
         // Check that the length of the wires is consistent.
         self.check_poly_same_len()?;
@@ -239,10 +316,33 @@ impl StandardComposer {
         let q_arith_poly = Polynomial::from_coefficients_slice(&domain.ifft(&self.q_arith));
         let q_range_poly = Polynomial::from_coefficients_slice(&domain.ifft(&self.q_range));
         let q_logic_poly = Polynomial::from_coefficients_slice(&domain.ifft(&self.q_logic));
-        let q_fixed_group_add_poly =
-            Polynomial::from_coefficients_slice(&domain.ifft(&self.q_fixed_group_add));
         let q_variable_group_add_poly =
             Polynomial::from_coefficients_slice(&domain.ifft(&self.q_variable_group_add));
+        let q_lookup_poly = Polynomial::from_coefficients_slice(&domain.ifft(&self.q_lookup));
+        let q_sbox_poly = Polynomial::from_coefficients_slice(&domain.ifft(&self.q_sbox));
+        let custom_polys: Vec<Vec<Polynomial>> = self
+            .custom_gates
+            .iter()
+            .map(|custom| {
+                custom
+                    .columns
+                    .iter()
+                    .map(|column| Polynomial::from_coefficients_vec(domain.ifft(column)))
+                    .collect()
+            })
+            .collect();
+
+        // Interpolate the columns of the lookup table, padded with all-zero rows
+        let mut table_rows = self.lookup_table_rows();
+        table_rows.resize(domain.size(), [BlsScalar::zero(); 4]);
+        let table_column = |column: usize| {
+            let values: Vec<BlsScalar> = table_rows.iter().map(|row| row[column]).collect();
+            Polynomial::from_coefficients_vec(domain.ifft(&values))
+        };
+        let table_1_poly = table_column(0);
+        let table_2_poly = table_column(1);
+        let table_3_poly = table_column(2);
+        let table_4_poly = table_column(3);
 
         // 2. Compute the sigma polynomials
         let (left_sigma_poly, right_sigma_poly, out_sigma_poly, fourth_sigma_poly) =
@@ -257,12 +357,16 @@ impl StandardComposer {
         let q_arith_poly_commit = commit_key.commit(&q_arith_poly).unwrap_or_default();
         let q_range_poly_commit = commit_key.commit(&q_range_poly).unwrap_or_default();
         let q_logic_poly_commit = commit_key.commit(&q_logic_poly).unwrap_or_default();
-        let q_fixed_group_add_poly_commit = commit_key
-            .commit(&q_fixed_group_add_poly)
-            .unwrap_or_default();
         let q_variable_group_add_poly_commit = commit_key
             .commit(&q_variable_group_add_poly)
             .unwrap_or_default();
+        let q_lookup_poly_commit = commit_key.commit(&q_lookup_poly).unwrap_or_default();
+        let q_sbox_poly_commit = commit_key.commit(&q_sbox_poly).unwrap_or_default();
+
+        let table_1_poly_commit = commit_key.commit(&table_1_poly).unwrap_or_default();
+        let table_2_poly_commit = commit_key.commit(&table_2_poly).unwrap_or_default();
+        let table_3_poly_commit = commit_key.commit(&table_3_poly).unwrap_or_default();
+        let table_4_poly_commit = commit_key.commit(&table_4_poly).unwrap_or_default();
 
         let left_sigma_poly_commit = commit_key.commit(&left_sigma_poly)?;
         let right_sigma_poly_commit = commit_key.commit(&right_sigma_poly)?;
@@ -288,12 +392,6 @@ impl StandardComposer {
             q_c: q_c_poly_commit,
             q_logic: q_logic_poly_commit,
         };
-        // Verifier Key for ecc circuits
-        let ecc_verifier_key = widget::ecc::scalar_mul::fixed_base::VerifierKey {
-            q_l: q_l_poly_commit,
-            q_r: q_r_poly_commit,
-            q_fixed_group_add: q_fixed_group_add_poly_commit,
-        };
         // Verifier Key for curve addition circuits
         let curve_addition_verifier_key = widget::ecc::curve_addition::VerifierKey {
             q_variable_group_add: q_variable_group_add_poly_commit,
@@ -305,15 +403,44 @@ impl StandardComposer {
             out_sigma: out_sigma_poly_commit,
             fourth_sigma: fourth_sigma_poly_commit,
         };
+        // Verifier Key for lookup gates
+        let lookup_verifier_key = widget::lookup::VerifierKey {
+            q_lookup: q_lookup_poly_commit,
+            table_1: table_1_poly_commit,
+            table_2: table_2_poly_commit,
+            table_3: table_3_poly_commit,
+            table_4: table_4_poly_commit,
+        };
+        // Verifier Key for Poseidon S-box gates
+        let sbox_verifier_key = widget::sbox::VerifierKey {
+            q_sbox: q_sbox_poly_commit,
+        };
+        // Verifier Keys for custom gates
+        let custom_verifier_keys = self
+            .custom_gates
+            .iter()
+            .zip(custom_polys.iter())
+            .map(|(custom, polys)| widget::custom::VerifierKey {
+                gate: custom.gate.clone(),
+                selectors: polys
+                    .iter()
+                    .map(|poly| commit_key.commit(poly).unwrap_or_default())
+                    .collect(),
+            })
+            .collect();
 
         let verifier_key = widget::VerifierKey {
             n: self.circuit_size(),
             arithmetic: arithmetic_verifier_key,
             logic: logic_verifier_key,
             range: range_verifier_key,
-            fixed_base: ecc_verifier_key,
             variable_base: curve_addition_verifier_key,
             permutation: permutation_verifier_key,
+            lookup: lookup_verifier_key,
+            sbox: sbox_verifier_key,
+            custom: custom_verifier_keys,
+            public_inputs: self.public_input_layout.clone(),
+            public_input_gates: self.public_input_positions().into_iter().collect(),
         };
 
         let selectors = SelectorPolynomials {
@@ -326,17 +453,20 @@ impl StandardComposer {
             q_arith: q_arith_poly,
             q_range: q_range_poly,
             q_logic: q_logic_poly,
-            q_fixed_group_add: q_fixed_group_add_poly,
             q_variable_group_add: q_variable_group_add_poly,
+            q_lookup: q_lookup_poly,
+            q_sbox: q_sbox_poly,
+            table_1: table_1_poly,
+            table_2: table_2_poly,
+            table_3: table_3_poly,
+            table_4: table_4_poly,
             left_sigma: left_sigma_poly,
             right_sigma: right_sigma_poly,
             out_sigma: out_sigma_poly,
             fourth_sigma: fourth_sigma_poly,
+            custom: custom_polys,
         };
 
-        // Add the circuit description to the transcript
-        verifier_key.seed_transcript(transcript);
-
         Ok((verifier_key, selectors, domain))
     }
 }
@@ -366,8 +496,9 @@ mod test {
         assert!(composer.q_arith.len() == size);
         assert!(composer.q_range.len() == size);
         assert!(composer.q_logic.len() == size);
-        assert!(composer.q_fixed_group_add.len() == size);
         assert!(composer.q_variable_group_add.len() == size);
+        assert!(composer.q_lookup.len() == size);
+        assert!(composer.q_sbox.len() == size);
         assert!(composer.w_l.len() == size);
         assert!(composer.w_r.len() == size);
         assert!(composer.w_o.len() == size);
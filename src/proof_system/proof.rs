@@ -12,15 +12,17 @@
 
 use super::linearisation_poly::ProofEvaluations;
 use super::proof_system_errors::ProofErrors;
+use crate::commitment_scheme::kzg10::msm::pippenger;
 use crate::commitment_scheme::kzg10::{AggregateProof, Commitment, OpeningKey};
 use crate::fft::EvaluationDomain;
+use crate::proof_system::widget::lookup::lookup_denominator;
 use crate::proof_system::widget::VerifierKey;
-use crate::transcript::TranscriptProtocol;
+use crate::transcript::{TranscriptProtocol, PROTOCOL_VERSION};
 use anyhow::{Error, Result};
-use dusk_bls12_381::{multiscalar_mul::msm_variable_base, BlsScalar, G1Affine};
-use merlin::Transcript;
+use dusk_bls12_381::{BlsScalar, G1Affine, G1Projective};
 use serde::de::Visitor;
 use serde::{self, Deserialize, Deserializer, Serialize, Serializer};
+use std::collections::BTreeMap;
 
 #[cfg(feature = "canon")]
 use canonical::{Canon, InvalidEncoding, Sink, Source, Store};
@@ -49,6 +51,15 @@ pub struct Proof {
     /// Commitment to the permutation polynomial.
     pub z_comm: Commitment,
 
+    /// Commitment to the lookup query polynomial.
+    pub f_comm: Commitment,
+    /// Commitment to the first half of the sorted lookup vector.
+    pub h_1_comm: Commitment,
+    /// Commitment to the second half of the sorted lookup vector.
+    pub h_2_comm: Commitment,
+    /// Commitment to the lookup grand product polynomial.
+    pub p_comm: Commitment,
+
     /// Commitment to the quotient polynomial.
     pub t_1_comm: Commitment,
     /// Commitment to the quotient polynomial.
@@ -98,13 +109,17 @@ impl Proof {
         bytes[96..144].copy_from_slice(&self.c_comm.0.to_compressed()[..]);
         bytes[144..192].copy_from_slice(&self.d_comm.0.to_compressed()[..]);
         bytes[192..240].copy_from_slice(&self.z_comm.0.to_compressed()[..]);
-        bytes[240..288].copy_from_slice(&self.t_1_comm.0.to_compressed()[..]);
-        bytes[288..336].copy_from_slice(&self.t_2_comm.0.to_compressed()[..]);
-        bytes[336..384].copy_from_slice(&self.t_3_comm.0.to_compressed()[..]);
-        bytes[384..432].copy_from_slice(&self.t_4_comm.0.to_compressed()[..]);
-        bytes[432..480].copy_from_slice(&self.w_z_comm.0.to_compressed()[..]);
-        bytes[480..528].copy_from_slice(&self.w_zw_comm.0.to_compressed()[..]);
-        bytes[528..PROOF_SIZE].copy_from_slice(&self.evaluations.to_bytes()[..]);
+        bytes[240..288].copy_from_slice(&self.f_comm.0.to_compressed()[..]);
+        bytes[288..336].copy_from_slice(&self.h_1_comm.0.to_compressed()[..]);
+        bytes[336..384].copy_from_slice(&self.h_2_comm.0.to_compressed()[..]);
+        bytes[384..432].copy_from_slice(&self.p_comm.0.to_compressed()[..]);
+        bytes[432..480].copy_from_slice(&self.t_1_comm.0.to_compressed()[..]);
+        bytes[480..528].copy_from_slice(&self.t_2_comm.0.to_compressed()[..]);
+        bytes[528..576].copy_from_slice(&self.t_3_comm.0.to_compressed()[..]);
+        bytes[576..624].copy_from_slice(&self.t_4_comm.0.to_compressed()[..]);
+        bytes[624..672].copy_from_slice(&self.w_z_comm.0.to_compressed()[..]);
+        bytes[672..720].copy_from_slice(&self.w_zw_comm.0.to_compressed()[..]);
+        bytes[720..PROOF_SIZE].copy_from_slice(&self.evaluations.to_bytes()[..]);
 
         bytes
     }
@@ -118,6 +133,10 @@ impl Proof {
         let (c_comm, rest) = read_commitment(rest)?;
         let (d_comm, rest) = read_commitment(rest)?;
         let (z_comm, rest) = read_commitment(rest)?;
+        let (f_comm, rest) = read_commitment(rest)?;
+        let (h_1_comm, rest) = read_commitment(rest)?;
+        let (h_2_comm, rest) = read_commitment(rest)?;
+        let (p_comm, rest) = read_commitment(rest)?;
         let (t_1_comm, rest) = read_commitment(rest)?;
         let (t_2_comm, rest) = read_commitment(rest)?;
         let (t_3_comm, rest) = read_commitment(rest)?;
@@ -133,6 +152,10 @@ impl Proof {
             c_comm,
             d_comm,
             z_comm,
+            f_comm,
+            h_1_comm,
+            h_2_comm,
+            p_comm,
             t_1_comm,
             t_2_comm,
             t_3_comm,
@@ -146,23 +169,47 @@ impl Proof {
 
     /// Returns the serialised size of a [`Proof`] object.
     pub const fn serialised_size() -> usize {
-        const NUM_COMMITMENTS: usize = 11;
+        const NUM_COMMITMENTS: usize = 15;
         const COMMITMENT_SIZE: usize = 48;
         (NUM_COMMITMENTS * COMMITMENT_SIZE) + ProofEvaluations::serialised_size()
     }
 
     /// Performs the verification of a `Proof` returning a boolean result.
-    pub(crate) fn verify(
+    pub(crate) fn verify<T: TranscriptProtocol>(
         &self,
         verifier_key: &VerifierKey,
-        transcript: &mut Transcript,
+        transcript: &mut T,
         opening_key: &OpeningKey,
-        pub_inputs: &[BlsScalar],
+        pub_inputs: &[(usize, BlsScalar)],
     ) -> Result<(), Error> {
+        let (total_w, total_c) =
+            self.fold_openings(verifier_key, transcript, opening_key, pub_inputs)?;
+
+        if opening_key.check_folded(total_w, total_c).is_err() {
+            return Err(ProofErrors::ProofVerificationError.into());
+        }
+        Ok(())
+    }
+
+    /// Replays the transcript of a `Proof` and folds all of its KZG openings into
+    /// the pair of points which is checked by the final pairing.
+    /// The `Proof` is valid iff `opening_key.check_folded` accepts them.
+    pub(crate) fn fold_openings<T: TranscriptProtocol>(
+        &self,
+        verifier_key: &VerifierKey,
+        transcript: &mut T,
+        opening_key: &OpeningKey,
+        pub_inputs: &[(usize, BlsScalar)],
+    ) -> Result<(G1Projective, G1Projective), Error> {
         let domain = EvaluationDomain::new(verifier_key.n)?;
 
         // Subgroup checks are done when the proof is deserialised.
 
+        verifier_key.check_public_input_gates(pub_inputs)?;
+
+        // Bind the proof to the circuit and its public inputs
+        append_statement(transcript, &verifier_key.digest(), pub_inputs);
+
         // In order for the Verifier and Prover to have the same view in the non-interactive setting
         // Both parties must commit the same elements into the transcript
         // Below the verifier will simulate an interaction with the prover by adding the same elements
@@ -174,21 +221,38 @@ impl Proof {
         transcript.append_commitment(b"w_o", &self.c_comm);
         transcript.append_commitment(b"w_4", &self.d_comm);
 
+        // Compute table compression challenge
+        let zeta = transcript.challenge_scalar(b"zeta");
+        // Add commitment to lookup query and sorted polynomials to transcript
+        transcript.append_commitment(b"f", &self.f_comm);
+        transcript.append_commitment(b"h_1", &self.h_1_comm);
+        transcript.append_commitment(b"h_2", &self.h_2_comm);
+
         // Compute beta and gamma challenges
         let beta = transcript.challenge_scalar(b"beta");
         transcript.append_scalar(b"beta", &beta);
         let gamma = transcript.challenge_scalar(b"gamma");
-        // Add commitment to permutation polynomial to transcript
+        // Compute delta and epsilon challenges
+        let delta = transcript.challenge_scalar(b"delta");
+        transcript.append_scalar(b"delta", &delta);
+        let epsilon = transcript.challenge_scalar(b"epsilon");
+        // Add commitment to permutation polynomials to transcript
         transcript.append_commitment(b"z", &self.z_comm);
+        transcript.append_commitment(b"p", &self.p_comm);
 
         // Compute quotient challenge
         let alpha = transcript.challenge_scalar(b"alpha");
         let range_sep_challenge = transcript.challenge_scalar(b"range separation challenge");
         let logic_sep_challenge = transcript.challenge_scalar(b"logic separation challenge");
-        let fixed_base_sep_challenge =
-            transcript.challenge_scalar(b"fixed base separation challenge");
         let var_base_sep_challenge =
             transcript.challenge_scalar(b"variable base separation challenge");
+        let lookup_sep_challenge = transcript.challenge_scalar(b"lookup separation challenge");
+        let sbox_sep_challenge = transcript.challenge_scalar(b"sbox separation challenge");
+        let custom_sep_challenges: Vec<_> = verifier_key
+            .custom
+            .iter()
+            .map(|_| transcript.challenge_scalar(b"custom gate separation challenge"))
+            .collect();
 
         // Add commitment to quotient polynomial to transcript
         transcript.append_commitment(b"t_1", &self.t_1_comm);
@@ -216,6 +280,8 @@ impl Proof {
             &z_h_eval,
             &l1_eval,
             &self.evaluations.perm_eval,
+            &lookup_sep_challenge,
+            (&delta, &epsilon),
         );
 
         // Compute commitment to quotient polynomial
@@ -235,9 +301,14 @@ impl Proof {
         transcript.append_scalar(b"out_sig_eval", &self.evaluations.out_sigma_eval);
         transcript.append_scalar(b"q_arith_eval", &self.evaluations.q_arith_eval);
         transcript.append_scalar(b"q_c_eval", &self.evaluations.q_c_eval);
-        transcript.append_scalar(b"q_l_eval", &self.evaluations.q_l_eval);
-        transcript.append_scalar(b"q_r_eval", &self.evaluations.q_r_eval);
         transcript.append_scalar(b"perm_eval", &self.evaluations.perm_eval);
+        transcript.append_scalar(b"f_eval", &self.evaluations.f_eval);
+        transcript.append_scalar(b"h_1_eval", &self.evaluations.h_1_eval);
+        transcript.append_scalar(b"h_1_next_eval", &self.evaluations.h_1_next_eval);
+        transcript.append_scalar(b"h_2_eval", &self.evaluations.h_2_eval);
+        transcript.append_scalar(b"table_eval", &self.evaluations.table_eval);
+        transcript.append_scalar(b"table_next_eval", &self.evaluations.table_next_eval);
+        transcript.append_scalar(b"lookup_perm_eval", &self.evaluations.lookup_perm_eval);
         transcript.append_scalar(b"t_eval", &t_eval);
         transcript.append_scalar(b"r_eval", &self.evaluations.lin_poly_eval);
 
@@ -249,13 +320,19 @@ impl Proof {
             (
                 &range_sep_challenge,
                 &logic_sep_challenge,
-                &fixed_base_sep_challenge,
                 &var_base_sep_challenge,
+                &lookup_sep_challenge,
+                &sbox_sep_challenge,
             ),
+            &custom_sep_challenges,
             &z_challenge,
+            (&zeta, &delta, &epsilon),
             l1_eval,
             &verifier_key,
-        );
+        )?;
+
+        // Compute commitment to the compressed lookup table
+        let table_comm = verifier_key.lookup.compute_table_commitment(&zeta);
 
         // Commitment Scheme
         // Now we delegate computation to the commitment scheme by batch checking two proofs
@@ -283,6 +360,16 @@ impl Proof {
             self.evaluations.out_sigma_eval,
             verifier_key.permutation.out_sigma,
         ));
+        aggregate_proof.add_part((self.evaluations.f_eval, self.f_comm));
+        aggregate_proof.add_part((self.evaluations.h_1_eval, self.h_1_comm));
+        aggregate_proof.add_part((self.evaluations.h_2_eval, self.h_2_comm));
+        aggregate_proof.add_part((self.evaluations.table_eval, table_comm));
+        // The selector evaluations consumed by the linearisation
+        aggregate_proof.add_part((
+            self.evaluations.q_arith_eval,
+            verifier_key.arithmetic.q_arith,
+        ));
+        aggregate_proof.add_part((self.evaluations.q_c_eval, verifier_key.arithmetic.q_c));
         // Flatten proof with opening challenge
         let flattened_proof_a = aggregate_proof.flatten(transcript);
 
@@ -292,31 +379,28 @@ impl Proof {
         shifted_aggregate_proof.add_part((self.evaluations.a_next_eval, self.a_comm));
         shifted_aggregate_proof.add_part((self.evaluations.b_next_eval, self.b_comm));
         shifted_aggregate_proof.add_part((self.evaluations.d_next_eval, self.d_comm));
+        shifted_aggregate_proof.add_part((self.evaluations.lookup_perm_eval, self.p_comm));
+        shifted_aggregate_proof.add_part((self.evaluations.h_1_next_eval, self.h_1_comm));
+        shifted_aggregate_proof.add_part((self.evaluations.table_next_eval, table_comm));
         let flattened_proof_b = shifted_aggregate_proof.flatten(transcript);
 
         // Add commitment to openings to transcript
         transcript.append_commitment(b"w_z", &self.w_z_comm);
         transcript.append_commitment(b"w_z_w", &self.w_zw_comm);
 
-        // Batch check
-        if opening_key
-            .batch_check(
-                &[z_challenge, (z_challenge * domain.group_gen)],
-                &[flattened_proof_a, flattened_proof_b],
-                transcript,
-            )
-            .is_err()
-        {
-            return Err(ProofErrors::ProofVerificationError.into());
-        }
-        Ok(())
+        // Fold the openings at both points
+        Ok(opening_key.fold_batch(
+            &[z_challenge, (z_challenge * domain.group_gen)],
+            &[flattened_proof_a, flattened_proof_b],
+            transcript,
+        ))
     }
 
     #[allow(clippy::too_many_arguments)]
     fn compute_quotient_evaluation(
         &self,
         domain: &EvaluationDomain,
-        pub_inputs: &[BlsScalar],
+        pub_inputs: &[(usize, BlsScalar)],
         alpha: &BlsScalar,
         beta: &BlsScalar,
         gamma: &BlsScalar,
@@ -324,6 +408,8 @@ impl Proof {
         z_h_eval: &BlsScalar,
         l1_eval: &BlsScalar,
         z_hat_eval: &BlsScalar,
+        lookup_sep_challenge: &BlsScalar,
+        (delta, epsilon): (&BlsScalar, &BlsScalar),
     ) -> BlsScalar {
         // Compute the public input polynomial evaluated at `z_challenge`
         let pi_eval = compute_barycentric_eval(pub_inputs, z_challenge, domain);
@@ -352,8 +438,22 @@ impl Proof {
         // l_1(z) * alpha^2
         let c = l1_eval * alpha_sq;
 
+        // (p_hat * (epsilon(1 + delta) + h_1 + delta * h_2)(epsilon(1 + delta) + h_2 + delta * h_1_next) * kappa
+        // + l_1(z) * kappa^2) * lookup_sep
+        let kappa = lookup_sep_challenge.square();
+        let d_0 = self.evaluations.lookup_perm_eval
+            * lookup_denominator(
+                (delta, epsilon),
+                &self.evaluations.h_1_eval,
+                &self.evaluations.h_2_eval,
+                &self.evaluations.h_1_next_eval,
+            )
+            * kappa;
+        let d_1 = l1_eval * kappa.square();
+        let d = (d_0 + d_1) * lookup_sep_challenge;
+
         // Return t_eval
-        (a - b - c) * z_h_eval.invert().unwrap()
+        (a - b - c - d) * z_h_eval.invert().unwrap()
     }
 
     fn compute_quotient_commitment(&self, z_challenge: &BlsScalar, n: usize) -> Commitment {
@@ -377,13 +477,16 @@ impl Proof {
         (
             range_sep_challenge,
             logic_sep_challenge,
-            fixed_base_sep_challenge,
             var_base_sep_challenge,
-        ): (&BlsScalar, &BlsScalar, &BlsScalar, &BlsScalar),
+            lookup_sep_challenge,
+            sbox_sep_challenge,
+        ): (&BlsScalar, &BlsScalar, &BlsScalar, &BlsScalar, &BlsScalar),
+        custom_sep_challenges: &[BlsScalar],
         z_challenge: &BlsScalar,
+        (zeta, delta, epsilon): (&BlsScalar, &BlsScalar, &BlsScalar),
         l1_eval: BlsScalar,
         verifier_key: &VerifierKey,
-    ) -> Commitment {
+    ) -> Result<Commitment, Error> {
         let mut scalars: Vec<_> = Vec::with_capacity(6);
         let mut points: Vec<G1Affine> = Vec::with_capacity(6);
 
@@ -407,20 +510,29 @@ impl Proof {
             &self.evaluations,
         );
 
-        verifier_key.fixed_base.compute_linearisation_commitment(
-            &fixed_base_sep_challenge,
+        verifier_key.variable_base.compute_linearisation_commitment(
+            &var_base_sep_challenge,
             &mut scalars,
             &mut points,
             &self.evaluations,
         );
 
-        verifier_key.variable_base.compute_linearisation_commitment(
-            &var_base_sep_challenge,
+        verifier_key.sbox.compute_linearisation_commitment(
+            sbox_sep_challenge,
             &mut scalars,
             &mut points,
             &self.evaluations,
         );
 
+        for (custom, challenge) in verifier_key.custom.iter().zip(custom_sep_challenges.iter()) {
+            custom.compute_linearisation_commitment(
+                challenge,
+                &mut scalars,
+                &mut points,
+                &self.evaluations,
+            )?;
+        }
+
         verifier_key.permutation.compute_linearisation_commitment(
             &mut scalars,
             &mut points,
@@ -431,7 +543,44 @@ impl Proof {
             self.z_comm.0,
         );
 
-        Commitment::from_projective(msm_variable_base(&points, &scalars))
+        verifier_key.lookup.compute_linearisation_commitment(
+            lookup_sep_challenge,
+            (zeta, delta, epsilon),
+            &mut scalars,
+            &mut points,
+            &self.evaluations,
+            &l1_eval,
+            self.p_comm.0,
+        );
+
+        Ok(Commitment::from_projective(pippenger(&points, &scalars)))
+    }
+}
+
+/// Appends the statement of a proof to its transcript, before any of its
+/// commitments: the version of the protocol, the digest of the `VerifierKey`
+/// and the public inputs.
+///
+/// The public inputs are appended as the non-zero terms of the public input
+/// polynomial in the order of their gate, so that the transcript only depends
+/// on the polynomial and not on how the terms are listed.
+pub(crate) fn append_statement<T: TranscriptProtocol>(
+    transcript: &mut T,
+    verifier_key_digest: &BlsScalar,
+    pub_inputs: &[(usize, BlsScalar)],
+) {
+    let mut terms = BTreeMap::new();
+    for (index, pi) in pub_inputs.iter() {
+        *terms.entry(*index).or_insert_with(BlsScalar::zero) += pi;
+    }
+    terms.retain(|_, pi| *pi != BlsScalar::zero());
+
+    transcript.append_message(b"dom-sep", PROTOCOL_VERSION);
+    transcript.append_scalar(b"vk_digest", verifier_key_digest);
+    transcript.append_scalar(b"pi_count", &BlsScalar::from(terms.len() as u64));
+    for (index, pi) in terms.iter() {
+        transcript.append_scalar(b"pi_index", &BlsScalar::from(*index as u64));
+        transcript.append_scalar(b"pi", pi);
     }
 }
 
@@ -445,52 +594,37 @@ fn compute_first_lagrange_evaluation(
     z_h_eval * denom.invert().unwrap()
 }
 
-#[warn(clippy::needless_range_loop)]
+/// Evaluates the public input polynomial at `point` from the non-zero
+/// public inputs, along with the index of their gate, so the cost only
+/// depends on the number of public inputs and not on the circuit size.
 fn compute_barycentric_eval(
-    evaluations: &[BlsScalar],
+    pub_inputs: &[(usize, BlsScalar)],
     point: &BlsScalar,
     domain: &EvaluationDomain,
 ) -> BlsScalar {
     use crate::util::batch_inversion;
-    use rayon::iter::IntoParallelIterator;
-    use rayon::prelude::*;
 
     let numerator =
         (point.pow(&[domain.size() as u64, 0, 0, 0]) - BlsScalar::one()) * domain.size_inv;
 
-    // Indices with non-zero evaluations
-    let non_zero_evaluations: Vec<usize> = (0..evaluations.len())
-        .into_par_iter()
-        .filter(|&i| {
-            let evaluation = &evaluations[i];
-            evaluation != &BlsScalar::zero()
-        })
-        .collect();
-
-    // Only compute the denominators with non-zero evaluations
-    let mut denominators: Vec<BlsScalar> = (0..non_zero_evaluations.len())
-        .into_par_iter()
-        .map(|i| {
-            // index of non-zero evaluation
-            let index = non_zero_evaluations[i];
-
-            (domain.group_gen_inv.pow(&[index as u64, 0, 0, 0]) * point) - BlsScalar::one()
+    // L_i(z) = (z^n - 1) / (n (\omega^{-i} z - 1))
+    let mut denominators: Vec<BlsScalar> = pub_inputs
+        .iter()
+        .map(|(index, _)| {
+            (domain.group_gen_inv.pow(&[*index as u64, 0, 0, 0]) * point) - BlsScalar::one()
         })
         .collect();
     batch_inversion(&mut denominators);
 
-    let result: BlsScalar = (0..non_zero_evaluations.len())
-        .into_par_iter()
-        .map(|i| {
-            let eval_index = non_zero_evaluations[i];
-            let eval = evaluations[eval_index];
-
-            denominators[i] * eval
-        })
+    let result: BlsScalar = denominators
+        .iter()
+        .zip(pub_inputs.iter())
+        .map(|(denominator, (_, pi))| denominator * pi)
         .sum();
 
     result * numerator
 }
+
 #[cfg(test)]
 mod test {
     use super::*;
@@ -502,6 +636,10 @@ mod test {
             c_comm: Commitment::default(),
             d_comm: Commitment::default(),
             z_comm: Commitment::default(),
+            f_comm: Commitment::default(),
+            h_1_comm: Commitment::default(),
+            h_2_comm: Commitment::default(),
+            p_comm: Commitment::default(),
             t_1_comm: Commitment::default(),
             t_2_comm: Commitment::default(),
             t_3_comm: Commitment::default(),
@@ -518,13 +656,18 @@ mod test {
                 d_next_eval: BlsScalar::random(&mut rand::thread_rng()),
                 q_arith_eval: BlsScalar::random(&mut rand::thread_rng()),
                 q_c_eval: BlsScalar::random(&mut rand::thread_rng()),
-                q_l_eval: BlsScalar::random(&mut rand::thread_rng()),
-                q_r_eval: BlsScalar::random(&mut rand::thread_rng()),
                 left_sigma_eval: BlsScalar::random(&mut rand::thread_rng()),
                 right_sigma_eval: BlsScalar::random(&mut rand::thread_rng()),
                 out_sigma_eval: BlsScalar::random(&mut rand::thread_rng()),
                 lin_poly_eval: BlsScalar::random(&mut rand::thread_rng()),
                 perm_eval: BlsScalar::random(&mut rand::thread_rng()),
+                f_eval: BlsScalar::random(&mut rand::thread_rng()),
+                h_1_eval: BlsScalar::random(&mut rand::thread_rng()),
+                h_1_next_eval: BlsScalar::random(&mut rand::thread_rng()),
+                h_2_eval: BlsScalar::random(&mut rand::thread_rng()),
+                table_eval: BlsScalar::random(&mut rand::thread_rng()),
+                table_next_eval: BlsScalar::random(&mut rand::thread_rng()),
+                lookup_perm_eval: BlsScalar::random(&mut rand::thread_rng()),
             },
         };
 
@@ -532,4 +675,27 @@ mod test {
         let got_proof = Proof::from_bytes(&proof_bytes).unwrap();
         assert_eq!(got_proof, proof);
     }
+
+    #[test]
+    fn test_sparse_public_input_evaluation() {
+        use crate::fft::Polynomial;
+
+        let domain = EvaluationDomain::new(64).unwrap();
+        let pub_inputs = vec![(3, BlsScalar::from(7)), (40, -BlsScalar::from(11))];
+        let mut dense = vec![BlsScalar::zero(); domain.size()];
+        for (index, pi) in pub_inputs.iter() {
+            dense[*index] = *pi;
+        }
+        let pi_poly = Polynomial::from_coefficients_vec(domain.ifft(&dense));
+
+        let point = BlsScalar::random(&mut rand::thread_rng());
+        assert_eq!(
+            compute_barycentric_eval(&pub_inputs, &point, &domain),
+            pi_poly.evaluate(&point)
+        );
+        assert_eq!(
+            compute_barycentric_eval(&[], &point, &domain),
+            BlsScalar::zero()
+        );
+    }
 }
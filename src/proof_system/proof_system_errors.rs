@@ -15,8 +15,68 @@ pub enum ProofErrors {
     /// This error occurs when the verification of a `Proof` fails.
     #[error("proof verification failed")]
     ProofVerificationError,
+    /// This error occurs when a batch of `Proof`s is rejected, holding the
+    /// index of the first `Proof` of the batch that fails verification.
+    #[error("proof {0} of the batch failed verification")]
+    BatchVerificationError(usize),
     /// This error occurrs when the Prover structure already contains a
     /// preprocessed circuit inside, but you call preprocess again.
     #[error("circuit already preprocessed")]
     CircuitAlreadyPreprocessed,
+    /// This error occurs when verifying with a Verifier structure whose
+    /// circuit was not preprocessed yet.
+    #[error("circuit not preprocessed")]
+    CircuitNotPreprocessed,
+    /// This error occurs when deserialising a key which holds a custom gate
+    /// that was not provided.
+    #[error("custom gate {0} is not known")]
+    UnknownCustomGate(String),
+    /// This error occurs when verifying in a circuit a proof whose circuit
+    /// uses custom gates.
+    #[error("custom gates cannot be verified in a circuit")]
+    CustomGatesNotSupported,
+    /// This error occurs when a custom gate returns a number of coefficients
+    /// which differs from its number of selectors.
+    #[error("custom gate {name} returned {found} coefficients for {expected} selectors")]
+    CustomGateCoefficientCount {
+        /// Name of the gate
+        name: &'static str,
+        /// Number of selectors of the gate
+        expected: usize,
+        /// Number of coefficients returned
+        found: usize,
+    },
+    /// This error occurs when the number of values given for the public
+    /// inputs does not match the number of public inputs of the circuit.
+    #[error("expected {expected} public inputs, found {found}")]
+    PublicInputCountMismatch {
+        /// Number of public inputs of the circuit
+        expected: usize,
+        /// Number of values given
+        found: usize,
+    },
+    /// This error occurs when no value is given for a public input of the
+    /// circuit.
+    #[error("public input {0} is missing")]
+    MissingPublicInput(String),
+    /// This error occurs when a value is given for a public input which is
+    /// not part of the circuit.
+    #[error("public input {0} is not part of the circuit")]
+    UnknownPublicInput(String),
+    /// This error occurs when deserialising a layout which declares the same
+    /// public input twice.
+    #[error("public input {0} is declared twice")]
+    DuplicatePublicInput(String),
+    /// This error occurs when a public input is given for a gate which is
+    /// out of the circuit or does not hold a public input.
+    #[error("gate {0} does not hold a public input")]
+    InvalidPublicInputGate(usize),
+    /// This error occurs when two public inputs are given for the same gate.
+    #[error("public input of gate {0} is given twice")]
+    DuplicatePublicInputGate(usize),
+    /// This error occurs when proving a circuit whose variables asserted
+    /// equal have different values, holding the index of the first gate
+    /// holding one of them.
+    #[error("variables asserted equal at gate {0} have different values")]
+    UnequalVariables(usize),
 }
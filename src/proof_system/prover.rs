@@ -8,27 +8,39 @@ use super::proof_system_errors::ProofErrors;
 use crate::commitment_scheme::kzg10::CommitKey;
 use crate::constraint_system::{StandardComposer, Variable};
 use crate::fft::{EvaluationDomain, Polynomial};
+use crate::proof_system::proof::{append_statement, Proof};
+use crate::proof_system::widget::lookup::compress;
 use crate::proof_system::widget::ProverKey;
-use crate::proof_system::{linearisation_poly, proof::Proof, quotient_poly};
+use crate::proof_system::{linearisation_poly, lookup_poly, quotient_poly};
 use crate::transcript::TranscriptProtocol;
 use anyhow::{Error, Result};
 use dusk_bls12_381::BlsScalar;
 use merlin::Transcript;
 use rayon::iter::{IntoParallelRefIterator, ParallelIterator};
+use rayon::ThreadPool;
+use std::sync::Arc;
 
 /// Prover composes a circuit and builds a proof
+///
+/// The Prover is generic over the transcript used to derive the challenges,
+/// which defaults to the Merlin `Transcript`.
 #[allow(missing_debug_implementations)]
-pub struct Prover {
+pub struct Prover<T: TranscriptProtocol = Transcript> {
     /// ProverKey which is used to create proofs about a specific PLONK circuit
     pub prover_key: Option<ProverKey>,
 
     pub(crate) cs: StandardComposer,
     /// Store the messages exchanged during the preprocessing stage
     /// This is copied each time, we make a proof
-    pub preprocessed_transcript: Transcript,
+    pub preprocessed_transcript: T,
+
+    // Pool in which the proofs are computed, instead of the global pool of rayon
+    thread_pool: Option<Arc<ThreadPool>>,
+    // Whether the quotient polynomial is computed over the 8n coset in chunks
+    low_memory: bool,
 }
 
-impl Prover {
+impl<T: TranscriptProtocol> Prover<T> {
     /// Returns a mutable copy of the underlying composer
     pub fn mut_cs(&mut self) -> &mut StandardComposer {
         &mut self.cs
@@ -38,14 +50,28 @@ impl Prover {
         if self.prover_key.is_some() {
             return Err(ProofErrors::CircuitAlreadyPreprocessed.into());
         }
-        let pk = self
-            .cs
-            .preprocess_prover(commit_key, &mut self.preprocessed_transcript)?;
+        let cs = &mut self.cs;
+        let low_memory = self.low_memory;
+        let pk = install(&self.thread_pool, || {
+            if low_memory {
+                cs.preprocess_prover_low_memory(commit_key)
+            } else {
+                cs.preprocess_prover(commit_key)
+            }
+        })?;
         self.prover_key = Some(pk);
         Ok(())
     }
 }
 
+// Runs `op` in the thread pool of a Prover, if it has one
+fn install<R: Send>(thread_pool: &Option<Arc<ThreadPool>>, op: impl FnOnce() -> R + Send) -> R {
+    match thread_pool {
+        Some(thread_pool) => thread_pool.install(op),
+        None => op(),
+    }
+}
+
 impl Default for Prover {
     fn default() -> Prover {
         Prover::new(b"plonk")
@@ -59,6 +85,8 @@ impl Prover {
             prover_key: None,
             cs: StandardComposer::new(),
             preprocessed_transcript: Transcript::new(label),
+            thread_pool: None,
+            low_memory: false,
         }
     }
 
@@ -68,6 +96,21 @@ impl Prover {
             prover_key: None,
             cs: StandardComposer::with_expected_size(size),
             preprocessed_transcript: Transcript::new(label),
+            thread_pool: None,
+            low_memory: false,
+        }
+    }
+}
+
+impl<T: TranscriptProtocol> Prover<T> {
+    /// Creates a new prover object which uses the given transcript backend
+    pub fn with_transcript(transcript: T) -> Prover<T> {
+        Prover {
+            prover_key: None,
+            cs: StandardComposer::new(),
+            preprocessed_transcript: transcript,
+            thread_pool: None,
+            low_memory: false,
         }
     }
 
@@ -76,7 +119,33 @@ impl Prover {
         self.cs.circuit_size()
     }
 
-    /// Split `t(X)` poly into 4 degree `n` polynomials.
+    /// Preprocesses the circuit and computes the proofs in the given rayon
+    /// `ThreadPool` instead of the global one, which bounds the number of
+    /// threads the Prover uses.
+    pub fn set_thread_pool(&mut self, thread_pool: Arc<ThreadPool>) {
+        self.thread_pool = Some(thread_pool);
+    }
+
+    /// Sets whether the quotient polynomial is computed in the low-memory
+    /// mode, which is disabled by default.
+    ///
+    /// The quotient polynomial is evaluated over a coset 8 times as large as
+    /// the circuit. In the low-memory mode, the evaluations of the witness,
+    /// selector and permutation polynomials over the coset are computed from
+    /// their coefficients for one eighth of it at a time instead of being kept
+    /// in memory all at once, at the cost of slower proving.
+    ///
+    /// The `ProverKey` preprocessed in the low-memory mode only holds the
+    /// coefficients of its polynomials, so the mode must be set before
+    /// calling `preprocess`. The evaluations of the quotient polynomial
+    /// itself are still kept in memory in full.
+    pub fn set_low_memory(&mut self, low_memory: bool) {
+        self.low_memory = low_memory;
+    }
+
+    /// Split `t(X)` poly into 4 polynomials.
+    /// The first three have degree `n - 1` while the last one takes the
+    /// remaining coefficients, which exceed `n` because of the blinding factors.
     pub(crate) fn split_tx_poly(
         &self,
         n: usize,
@@ -89,6 +158,31 @@ impl Prover {
             Polynomial::from_coefficients_vec(t_x[3 * n..].to_vec()),
         )
     }
+    /// Adds a random multiple `b(X) * Z_H(X)` of the vanishing polynomial to `poly`,
+    /// where `b(X)` has degree `hiding_degree`.
+    /// The blinded polynomial agrees with `poly` over the domain, so the constraints
+    /// still hold, but its commitment and `hiding_degree + 1` openings reveal nothing about it.
+    fn blind_poly(
+        poly: &Polynomial,
+        hiding_degree: usize,
+        domain: &EvaluationDomain,
+    ) -> Polynomial {
+        let mut rng = rand::thread_rng();
+        let blinders: Vec<BlsScalar> = (0..=hiding_degree)
+            .map(|_| BlsScalar::random(&mut rng))
+            .collect();
+
+        // b(X) * (X^n - 1) = b(X) * X^n - b(X)
+        let mut coeffs = poly.coeffs.clone();
+        coeffs.resize(domain.size(), BlsScalar::zero());
+        coeffs
+            .iter_mut()
+            .zip(blinders.iter())
+            .for_each(|(coeff, blinder)| *coeff -= blinder);
+        coeffs.extend(blinders);
+
+        Polynomial::from_coefficients_vec(coeffs)
+    }
     /// Computes the quotient opening polynomial.
     fn compute_quotient_opening_poly(
         n: usize,
@@ -112,7 +206,8 @@ impl Prover {
     }
     /// Convert variables to their actual witness values.
     pub(crate) fn to_scalars(&self, vars: &[Variable]) -> Vec<BlsScalar> {
-        vars.par_iter().map(|var| self.cs.variables[var]).collect()
+        let variables = &self.cs.variables;
+        vars.par_iter().map(|var| variables[var]).collect()
     }
     /// Resets the witnesses in the prover object.
     /// This function is used when the user wants to make multiple proofs with the same circuit.
@@ -126,7 +221,7 @@ impl Prover {
     pub fn clear(&mut self) {
         self.clear_witness();
         self.prover_key = None;
-        self.preprocessed_transcript = Transcript::new(b"plonk");
+        self.preprocessed_transcript = T::new(b"plonk");
     }
 
     /// Keys the transcript with additional seed information
@@ -138,17 +233,42 @@ impl Prover {
     /// Creates a Proof that a circuit is satisfied
     /// Note that if you intend to make multiple proofs, after calling this method, the user should then
     /// call `clear_witness`. This is automatically done when `prove` is called
+    ///
+    /// Because of the blinding factors, the `commit_key` must support polynomials of
+    /// degree `n + 6`, where `n` is the circuit size padded to the next power of two.
     pub fn prove_with_preprocessed(
         &self,
         commit_key: &CommitKey,
         prover_key: &ProverKey,
+    ) -> Result<Proof, Error>
+    where
+        T: Sync,
+    {
+        install(&self.thread_pool, || {
+            self.compute_proof(commit_key, prover_key)
+        })
+    }
+
+    fn compute_proof(
+        &self,
+        commit_key: &CommitKey,
+        prover_key: &ProverKey,
     ) -> Result<Proof, Error> {
-        let domain = EvaluationDomain::new(self.cs.circuit_size())?;
+        // The permutation only sees the variables placed at the wires, so the
+        // variables merged into their class are checked here
+        if let Some(index) = self.cs.first_unequal_class() {
+            return Err(ProofErrors::UnequalVariables(index).into());
+        }
+
+        let domain = EvaluationDomain::new(self.cs.domain_size())?;
 
-        // Since the caller is passing a pre-processed circuit
-        // We assume that the Transcript has been seeded with the preprocessed
-        // Commitments
+        // Bind the proof to the circuit and its public inputs
         let mut transcript = self.preprocessed_transcript.clone();
+        append_statement(
+            &mut transcript,
+            &prover_key.verifier_key_digest,
+            &self.cs.sparse_public_inputs(),
+        );
 
         //1. Compute witness Polynomials
         //
@@ -167,6 +287,14 @@ impl Prover {
         let w_o_poly = Polynomial::from_coefficients_vec(domain.ifft(w_o_scalar));
         let w_4_poly = Polynomial::from_coefficients_vec(domain.ifft(w_4_scalar));
 
+        // Blind the witness polynomials with a random degree-1 multiple of Z_H(X)
+        // so that their commitments and openings at `z` and `z * root of unity`
+        // do not leak the witness
+        let w_l_poly = Self::blind_poly(&w_l_poly, 1, &domain);
+        let w_r_poly = Self::blind_poly(&w_r_poly, 1, &domain);
+        let w_o_poly = Self::blind_poly(&w_o_poly, 1, &domain);
+        let w_4_poly = Self::blind_poly(&w_4_poly, 1, &domain);
+
         // Commit to witness polynomials
         let w_l_poly_commit = commit_key.commit(&w_l_poly)?;
         let w_r_poly_commit = commit_key.commit(&w_r_poly)?;
@@ -179,6 +307,49 @@ impl Prover {
         transcript.append_commitment(b"w_o", &w_o_poly_commit);
         transcript.append_commitment(b"w_4", &w_4_poly_commit);
 
+        // Compute the lookup query values and the sorted lookup vector
+        //
+        // Compute table compression challenge; `zeta`
+        let zeta = transcript.challenge_scalar(b"zeta");
+
+        let table_poly = prover_key.lookup.compute_table_poly(&zeta);
+        let table_scalar = domain.fft(&table_poly);
+
+        // Gates which are not lookup gates query the all-zero row of the table
+        let f_scalar: Vec<BlsScalar> = (0..domain.size())
+            .map(|i| match self.cs.q_lookup.get(i) {
+                Some(q_lookup) if *q_lookup == BlsScalar::one() => compress(
+                    &w_l_scalar[i],
+                    &w_r_scalar[i],
+                    &w_o_scalar[i],
+                    &self.cs.q_c[i],
+                    &zeta,
+                ),
+                _ => BlsScalar::zero(),
+            })
+            .collect();
+
+        let (h_1_scalar, h_2_scalar) =
+            lookup_poly::compute_sorted_evals(&domain, &f_scalar, &table_scalar);
+
+        let f_poly = Polynomial::from_coefficients_vec(domain.ifft(&f_scalar));
+        let h_1_poly = Polynomial::from_coefficients_vec(domain.ifft(&h_1_scalar));
+        let h_2_poly = Polynomial::from_coefficients_vec(domain.ifft(&h_2_scalar));
+
+        // `h_1` is opened at `z` and `z * root of unity`, while `f` and `h_2` are
+        // only opened at `z`
+        let f_poly = Self::blind_poly(&f_poly, 1, &domain);
+        let h_1_poly = Self::blind_poly(&h_1_poly, 2, &domain);
+        let h_2_poly = Self::blind_poly(&h_2_poly, 1, &domain);
+
+        let f_poly_commit = commit_key.commit(&f_poly)?;
+        let h_1_poly_commit = commit_key.commit(&h_1_poly)?;
+        let h_2_poly_commit = commit_key.commit(&h_2_poly)?;
+
+        transcript.append_commitment(b"f", &f_poly_commit);
+        transcript.append_commitment(b"h_1", &h_1_poly_commit);
+        transcript.append_commitment(b"h_2", &h_2_poly_commit);
+
         // 2. Compute permutation polynomial
         //
         //
@@ -187,7 +358,7 @@ impl Prover {
         transcript.append_scalar(b"beta", &beta);
         let gamma = transcript.challenge_scalar(b"gamma");
 
-        let z_poly = Polynomial::from_coefficients_slice(&self.cs.perm.compute_permutation_poly(
+        let z_poly = self.cs.perm.compute_permutation_poly(
             &domain,
             (&w_l_scalar, &w_r_scalar, &w_o_scalar, &w_4_scalar),
             &beta,
@@ -198,14 +369,37 @@ impl Prover {
                 &prover_key.permutation.out_sigma.0,
                 &prover_key.permutation.fourth_sigma.0,
             ),
-        ));
+        );
+
+        // Blind the permutation polynomial with a random degree-2 multiple of Z_H(X)
+        // It is opened at `z * root of unity` and is also committed to in the linearisation
+        let z_poly = Self::blind_poly(&z_poly, 2, &domain);
 
         // Commit to permutation polynomial
         //
         let z_poly_commit = commit_key.commit(&z_poly)?;
 
-        // Add permutation polynomial commitment to transcript
+        // Compute lookup challenges; `delta` and `epsilon`
+        let delta = transcript.challenge_scalar(b"delta");
+        transcript.append_scalar(b"delta", &delta);
+        let epsilon = transcript.challenge_scalar(b"epsilon");
+
+        let p_poly = lookup_poly::compute_grand_product_poly(
+            &domain,
+            (&delta, &epsilon),
+            &f_scalar,
+            &table_scalar,
+            (&h_1_scalar, &h_2_scalar),
+        );
+
+        // Blind the lookup grand product in the same way as the permutation polynomial
+        let p_poly = Self::blind_poly(&p_poly, 2, &domain);
+
+        let p_poly_commit = commit_key.commit(&p_poly)?;
+
+        // Add permutation polynomial commitments to transcript
         transcript.append_commitment(b"z", &z_poly_commit);
+        transcript.append_commitment(b"p", &p_poly_commit);
 
         // 3. Compute public inputs polynomial
         let pi_poly = Polynomial::from_coefficients_vec(domain.ifft(&self.cs.public_inputs));
@@ -216,16 +410,22 @@ impl Prover {
         let alpha = transcript.challenge_scalar(b"alpha");
         let range_sep_challenge = transcript.challenge_scalar(b"range separation challenge");
         let logic_sep_challenge = transcript.challenge_scalar(b"logic separation challenge");
-        let fixed_base_sep_challenge =
-            transcript.challenge_scalar(b"fixed base separation challenge");
         let var_base_sep_challenge =
             transcript.challenge_scalar(b"variable base separation challenge");
+        let lookup_sep_challenge = transcript.challenge_scalar(b"lookup separation challenge");
+        let sbox_sep_challenge = transcript.challenge_scalar(b"sbox separation challenge");
+        let custom_sep_challenges: Vec<_> = prover_key
+            .custom
+            .iter()
+            .map(|_| transcript.challenge_scalar(b"custom gate separation challenge"))
+            .collect();
 
         let t_poly = quotient_poly::compute(
             &domain,
             &prover_key,
             &z_poly,
             (&w_l_poly, &w_r_poly, &w_o_poly, &w_4_poly),
+            (&f_poly, &h_1_poly, &h_2_poly, &p_poly),
             &pi_poly,
             &(
                 alpha,
@@ -233,12 +433,16 @@ impl Prover {
                 gamma,
                 range_sep_challenge,
                 logic_sep_challenge,
-                fixed_base_sep_challenge,
                 var_base_sep_challenge,
+                lookup_sep_challenge,
+                sbox_sep_challenge,
             ),
+            &(zeta, delta, epsilon),
+            &custom_sep_challenges,
+            self.low_memory,
         )?;
 
-        // Split quotient polynomial into 4 degree `n` polynomials
+        // Split quotient polynomial into 4 polynomials
         let (t_1_poly, t_2_poly, t_3_poly, t_4_poly) = self.split_tx_poly(domain.size(), &t_poly);
 
         // Commit to splitted quotient polynomial
@@ -267,17 +471,21 @@ impl Prover {
                 gamma,
                 range_sep_challenge,
                 logic_sep_challenge,
-                fixed_base_sep_challenge,
                 var_base_sep_challenge,
+                lookup_sep_challenge,
+                sbox_sep_challenge,
                 z_challenge,
             ),
+            &(zeta, delta, epsilon),
+            &custom_sep_challenges,
             &w_l_poly,
             &w_r_poly,
             &w_o_poly,
             &w_4_poly,
             &t_poly,
             &z_poly,
-        );
+            (&f_poly, &h_1_poly, &h_2_poly, &p_poly),
+        )?;
 
         // Add evaluations to transcript
         transcript.append_scalar(b"a_eval", &evaluations.proof.a_eval);
@@ -292,9 +500,14 @@ impl Prover {
         transcript.append_scalar(b"out_sig_eval", &evaluations.proof.out_sigma_eval);
         transcript.append_scalar(b"q_arith_eval", &evaluations.proof.q_arith_eval);
         transcript.append_scalar(b"q_c_eval", &evaluations.proof.q_c_eval);
-        transcript.append_scalar(b"q_l_eval", &evaluations.proof.q_l_eval);
-        transcript.append_scalar(b"q_r_eval", &evaluations.proof.q_r_eval);
         transcript.append_scalar(b"perm_eval", &evaluations.proof.perm_eval);
+        transcript.append_scalar(b"f_eval", &evaluations.proof.f_eval);
+        transcript.append_scalar(b"h_1_eval", &evaluations.proof.h_1_eval);
+        transcript.append_scalar(b"h_1_next_eval", &evaluations.proof.h_1_next_eval);
+        transcript.append_scalar(b"h_2_eval", &evaluations.proof.h_2_eval);
+        transcript.append_scalar(b"table_eval", &evaluations.proof.table_eval);
+        transcript.append_scalar(b"table_next_eval", &evaluations.proof.table_next_eval);
+        transcript.append_scalar(b"lookup_perm_eval", &evaluations.proof.lookup_perm_eval);
         transcript.append_scalar(b"t_eval", &evaluations.quot_eval);
         transcript.append_scalar(b"r_eval", &evaluations.proof.lin_poly_eval);
 
@@ -322,6 +535,12 @@ impl Prover {
                 prover_key.permutation.left_sigma.0.clone(),
                 prover_key.permutation.right_sigma.0.clone(),
                 prover_key.permutation.out_sigma.0.clone(),
+                f_poly,
+                h_1_poly.clone(),
+                h_2_poly,
+                table_poly.clone(),
+                prover_key.arithmetic.q_arith.0.clone(),
+                prover_key.arithmetic.q_c.0.clone(),
             ],
             &z_challenge,
             &mut transcript,
@@ -330,7 +549,9 @@ impl Prover {
 
         // Compute aggregate witness to polynomials evaluated at the shifted evaluation challenge
         let shifted_aggregate_witness = commit_key.compute_aggregate_witness(
-            &[z_poly, w_l_poly, w_r_poly, w_4_poly],
+            &[
+                z_poly, w_l_poly, w_r_poly, w_4_poly, p_poly, h_1_poly, table_poly,
+            ],
             &(z_challenge * domain.group_gen),
             &mut transcript,
         );
@@ -345,6 +566,11 @@ impl Prover {
 
             z_comm: z_poly_commit,
 
+            f_comm: f_poly_commit,
+            h_1_comm: h_1_poly_commit,
+            h_2_comm: h_2_poly_commit,
+            p_comm: p_poly_commit,
+
             t_1_comm: t_1_commit,
             t_2_comm: t_2_commit,
             t_3_comm: t_3_commit,
@@ -360,14 +586,16 @@ impl Prover {
     /// Proves a circuit is satisfied, then clears the witness variables
     /// If the circuit is not pre-processed, then the preprocessed circuit will
     /// also be computed
-    pub fn prove(&mut self, commit_key: &CommitKey) -> Result<Proof, Error> {
+    pub fn prove(&mut self, commit_key: &CommitKey) -> Result<Proof, Error>
+    where
+        T: Sync,
+    {
         let prover_key: &ProverKey;
 
         if self.prover_key.is_none() {
             // Preprocess circuit
-            let prover_key = self
-                .cs
-                .preprocess_prover(commit_key, &mut self.preprocessed_transcript)?;
+            let cs = &mut self.cs;
+            let prover_key = install(&self.thread_pool, || cs.preprocess_prover(commit_key))?;
             // Store preprocessed circuit and transcript in the Prover
             self.prover_key = Some(prover_key);
         }
@@ -382,3 +610,147 @@ impl Prover {
         Ok(proof)
     }
 }
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::commitment_scheme::kzg10::PublicParameters;
+    use crate::constraint_system::helper::dummy_gadget;
+    use crate::proof_system::Verifier;
+
+    #[test]
+    fn test_proofs_of_same_witness_differ() {
+        let public_parameters = PublicParameters::setup(2 * 30, &mut rand::thread_rng()).unwrap();
+        let (ck, vk) = public_parameters.trim(2 * 20).unwrap();
+
+        let mut prover = Prover::new(b"demo");
+        dummy_gadget(10, prover.mut_cs());
+        prover.preprocess(&ck).unwrap();
+        let public_inputs = prover.cs.sparse_public_inputs();
+
+        let proof_a = prover.prove(&ck).unwrap();
+        dummy_gadget(10, prover.mut_cs());
+        let proof_b = prover.prove(&ck).unwrap();
+
+        // The witness is the same, but the blinding factors are not
+        assert_ne!(proof_a.a_comm, proof_b.a_comm);
+        assert_ne!(proof_a.b_comm, proof_b.b_comm);
+        assert_ne!(proof_a.c_comm, proof_b.c_comm);
+        assert_ne!(proof_a.d_comm, proof_b.d_comm);
+        assert_ne!(proof_a.z_comm, proof_b.z_comm);
+        assert_ne!(proof_a.evaluations, proof_b.evaluations);
+
+        let mut verifier = Verifier::new(b"demo");
+        dummy_gadget(10, verifier.mut_cs());
+        verifier.preprocess(&ck).unwrap();
+
+        assert!(verifier
+            .verify_sparse(&proof_a, &vk, &public_inputs)
+            .is_ok());
+        assert!(verifier
+            .verify_sparse(&proof_b, &vk, &public_inputs)
+            .is_ok());
+    }
+
+    #[test]
+    fn test_prove_in_thread_pool_with_low_memory() {
+        let public_parameters = PublicParameters::setup(2 * 70, &mut rand::thread_rng()).unwrap();
+        let (ck, vk) = public_parameters.trim(2 * 64).unwrap();
+        let gadget = |composer: &mut StandardComposer| {
+            dummy_gadget(10, composer);
+            let a = composer.add_input(BlsScalar::from(200));
+            composer.range_gate(a, 8);
+            composer.public_input("a", BlsScalar::from(200));
+        };
+
+        let mut prover = Prover::new(b"demo");
+        let thread_pool = rayon::ThreadPoolBuilder::new()
+            .num_threads(2)
+            .build()
+            .unwrap();
+        prover.set_thread_pool(Arc::new(thread_pool));
+        prover.set_low_memory(true);
+        gadget(prover.mut_cs());
+        prover.preprocess(&ck).unwrap();
+        let proof = prover.prove(&ck).unwrap();
+
+        let mut verifier = Verifier::new(b"demo");
+        gadget(verifier.mut_cs());
+        verifier.preprocess(&ck).unwrap();
+        assert!(verifier
+            .verify(&proof, &vk, &[BlsScalar::from(200)])
+            .is_ok());
+    }
+
+    #[test]
+    fn test_low_memory_prover_key() {
+        let public_parameters = PublicParameters::setup(2 * 70, &mut rand::thread_rng()).unwrap();
+        let (ck, vk) = public_parameters.trim(2 * 64).unwrap();
+        let gadget = |composer: &mut StandardComposer| {
+            dummy_gadget(10, composer);
+            let a = composer.add_input(BlsScalar::from(200));
+            composer.range_gate(a, 8);
+        };
+
+        let mut prover = Prover::new(b"demo");
+        gadget(prover.mut_cs());
+        prover.preprocess(&ck).unwrap();
+        let full_key = prover.prover_key.clone().unwrap();
+
+        let mut low_memory_prover = Prover::new(b"demo");
+        low_memory_prover.set_low_memory(true);
+        gadget(low_memory_prover.mut_cs());
+        low_memory_prover.preprocess(&ck).unwrap();
+        let key = low_memory_prover.prover_key.clone().unwrap();
+
+        // The key holds the coefficients of its polynomials, but none of
+        // their evaluations over the 8n coset
+        let evaluations = [
+            &key.arithmetic.q_m.1,
+            &key.arithmetic.q_l.1,
+            &key.arithmetic.q_r.1,
+            &key.arithmetic.q_o.1,
+            &key.arithmetic.q_c.1,
+            &key.arithmetic.q_4.1,
+            &key.arithmetic.q_arith.1,
+            &key.logic.q_c.1,
+            &key.logic.q_logic.1,
+            &key.range.q_range.1,
+            &key.permutation.left_sigma.1,
+            &key.permutation.right_sigma.1,
+            &key.permutation.out_sigma.1,
+            &key.permutation.fourth_sigma.1,
+            &key.permutation.linear_evaluations,
+            &key.variable_base.q_variable_group_add.1,
+            &key.lookup.q_lookup.1,
+            &key.lookup.q_c.1,
+            &key.sbox.q_c.1,
+            &key.sbox.q_sbox.1,
+            key.v_h_coset_8n(),
+        ];
+        assert!(evaluations.iter().all(|evals| evals.evals.is_empty()));
+        assert_eq!(key.arithmetic.q_m.0, full_key.arithmetic.q_m.0);
+        assert_eq!(
+            key.permutation.left_sigma.0,
+            full_key.permutation.left_sigma.0
+        );
+        assert_eq!(key.verifier_key_digest, full_key.verifier_key_digest);
+        assert_eq!(ProverKey::from_bytes(&key.to_bytes()).unwrap(), key);
+
+        // Either prover proves with either key
+        let proofs = [
+            prover.prove_with_preprocessed(&ck, &key).unwrap(),
+            low_memory_prover
+                .prove_with_preprocessed(&ck, &full_key)
+                .unwrap(),
+            low_memory_prover.prove(&ck).unwrap(),
+        ];
+
+        let mut verifier = Verifier::new(b"demo");
+        gadget(verifier.mut_cs());
+        verifier.preprocess(&ck).unwrap();
+        for proof in proofs.iter() {
+            assert!(verifier.verify(proof, &vk, &[]).is_ok());
+        }
+    }
+}
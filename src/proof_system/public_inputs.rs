@@ -0,0 +1,214 @@
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this
+// file, You can obtain one at http://mozilla.org/MPL/2.0/.
+//
+// Copyright (c) DUSK NETWORK. All rights reserved.
+
+//! Layout of the public inputs declared in a circuit.
+
+use crate::proof_system::proof_system_errors::ProofErrors;
+use crate::serialisation::{read_n, read_u64, write_u64, SerialisationErrors};
+use anyhow::{Error, Result};
+use dusk_bls12_381::BlsScalar;
+use std::collections::HashMap;
+
+/// The names of the public inputs declared with
+/// `StandardComposer::public_input`, in the order they were declared, along
+/// with the index of the gate holding each of them.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct PublicInputLayout {
+    inputs: Vec<(String, usize)>,
+}
+
+impl PublicInputLayout {
+    /// Declares the public input `name`, held by the gate at `position`.
+    ///
+    /// # Panics
+    ///
+    /// If a public input with the same name was already declared.
+    pub(crate) fn push(&mut self, name: &str, position: usize) {
+        assert!(
+            self.position(name).is_none(),
+            "public input {} is already declared",
+            name
+        );
+        self.inputs.push((name.to_string(), position));
+    }
+
+    /// Returns the number of public inputs.
+    pub fn len(&self) -> usize {
+        self.inputs.len()
+    }
+
+    /// Returns `true` if the circuit does not declare any public input.
+    pub fn is_empty(&self) -> bool {
+        self.inputs.is_empty()
+    }
+
+    /// Returns the names of the public inputs, in the order they were
+    /// declared.
+    pub fn names(&self) -> impl Iterator<Item = &str> {
+        self.inputs.iter().map(|(name, _)| name.as_str())
+    }
+
+    /// Returns the names of the public inputs along with the index of the gate
+    /// holding each of them, in the order they were declared.
+    pub fn iter(&self) -> impl Iterator<Item = (&str, usize)> {
+        self.inputs
+            .iter()
+            .map(|(name, position)| (name.as_str(), *position))
+    }
+
+    /// Returns the index of the gate holding the public input `name`.
+    pub fn position(&self, name: &str) -> Option<usize> {
+        self.inputs
+            .iter()
+            .find(|(input, _)| input == name)
+            .map(|(_, position)| *position)
+    }
+
+    /// Builds the public inputs of a proof from their `values`, given in the
+    /// order the public inputs were declared.
+    ///
+    /// The public inputs are returned along with the index of their gate, as
+    /// expected by `Verifier::verify_sparse`.
+    pub fn assign(&self, values: &[BlsScalar]) -> Result<Vec<(usize, BlsScalar)>, Error> {
+        if values.len() != self.len() {
+            return Err(ProofErrors::PublicInputCountMismatch {
+                expected: self.len(),
+                found: values.len(),
+            }
+            .into());
+        }
+        Ok(self.place(values.iter().copied()))
+    }
+
+    /// Builds the public inputs of a proof from their `values` by name.
+    ///
+    /// Fails if a public input of the layout has no value or if a value is
+    /// given for a name which is not part of the layout.
+    pub fn assign_named(
+        &self,
+        values: &HashMap<String, BlsScalar>,
+    ) -> Result<Vec<(usize, BlsScalar)>, Error> {
+        if let Some(name) = values.keys().find(|name| self.position(name).is_none()) {
+            return Err(ProofErrors::UnknownPublicInput(name.clone()).into());
+        }
+
+        let ordered = self
+            .names()
+            .map(|name| {
+                values
+                    .get(name)
+                    .copied()
+                    .ok_or_else(|| ProofErrors::MissingPublicInput(name.to_string()))
+            })
+            .collect::<Result<Vec<_>, _>>()?;
+        Ok(self.place(ordered.into_iter()))
+    }
+
+    /// Pairs the `values` with the index of their gate, with the sign the
+    /// gates of `StandardComposer::public_input` expect.
+    fn place(&self, values: impl Iterator<Item = BlsScalar>) -> Vec<(usize, BlsScalar)> {
+        self.inputs
+            .iter()
+            .zip(values)
+            .map(|((_, position), value)| (*position, -value))
+            .collect()
+    }
+
+    /// Writes the number of public inputs followed by the name and position
+    /// of each of them.
+    pub(crate) fn write(&self, bytes: &mut Vec<u8>) {
+        write_u64(self.len() as u64, bytes);
+        for (name, position) in self.inputs.iter() {
+            write_u64(name.len() as u64, bytes);
+            bytes.extend_from_slice(name.as_bytes());
+            write_u64(*position as u64, bytes);
+        }
+    }
+
+    /// Reads a layout written by `write`.
+    ///
+    /// Fails if a name is not valid UTF-8 or is declared twice.
+    pub(crate) fn read(bytes: &[u8]) -> Result<(PublicInputLayout, &[u8]), Error> {
+        let (len, mut rest) = read_u64(bytes)?;
+        let mut layout = PublicInputLayout::default();
+        for _ in 0..len {
+            let (name_len, remaining) = read_u64(rest)?;
+            let (name, remaining) = read_n(name_len as usize, remaining)?;
+            let (position, remaining) = read_u64(remaining)?;
+            let name =
+                std::str::from_utf8(name).map_err(|_| SerialisationErrors::StringMalformed)?;
+            if layout.position(name).is_some() {
+                return Err(ProofErrors::DuplicatePublicInput(name.to_string()).into());
+            }
+            layout.inputs.push((name.to_string(), position as usize));
+            rest = remaining;
+        }
+        Ok((layout, rest))
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_assign_public_inputs() {
+        let mut layout = PublicInputLayout::default();
+        layout.push("root", 4);
+        layout.push("nullifier", 1);
+
+        let (root, nullifier) = (BlsScalar::from(7), BlsScalar::from(9));
+        let expected = vec![(4, -root), (1, -nullifier)];
+        assert_eq!(layout.assign(&[root, nullifier]).unwrap(), expected);
+        assert!(layout.assign(&[root]).is_err());
+        assert!(layout.assign(&[root, nullifier, root]).is_err());
+
+        let mut values = HashMap::new();
+        values.insert("nullifier".to_string(), nullifier);
+        assert!(layout.assign_named(&values).is_err());
+        values.insert("root".to_string(), root);
+        assert_eq!(layout.assign_named(&values).unwrap(), expected);
+        values.insert("extra".to_string(), root);
+        assert!(layout.assign_named(&values).is_err());
+
+        let mut bytes = Vec::new();
+        layout.write(&mut bytes);
+        let (read, rest) = PublicInputLayout::read(&bytes).unwrap();
+        assert_eq!(read, layout);
+        assert!(rest.is_empty());
+
+        // Should fail as the name of the first input is not valid UTF-8
+        let mut invalid = bytes.clone();
+        invalid[16] = 0xff;
+        let err = PublicInputLayout::read(&invalid).unwrap_err();
+        assert!(matches!(
+            err.downcast_ref::<SerialisationErrors>(),
+            Some(SerialisationErrors::StringMalformed)
+        ));
+
+        // Should fail as the first input is declared twice
+        let mut duplicate = Vec::new();
+        write_u64(2, &mut duplicate);
+        for position in 4..6 {
+            write_u64(4, &mut duplicate);
+            duplicate.extend_from_slice(b"root");
+            write_u64(position, &mut duplicate);
+        }
+        let err = PublicInputLayout::read(&duplicate).unwrap_err();
+        assert!(matches!(
+            err.downcast_ref::<ProofErrors>(),
+            Some(ProofErrors::DuplicatePublicInput(name)) if name == "root"
+        ));
+    }
+
+    #[test]
+    #[should_panic]
+    fn test_declare_public_input_twice() {
+        let mut layout = PublicInputLayout::default();
+        layout.push("root", 4);
+        layout.push("root", 5);
+    }
+}
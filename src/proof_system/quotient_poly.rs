@@ -4,21 +4,29 @@
 //
 // Copyright (c) DUSK NETWORK. All rights reserved.
 
-use crate::fft::{EvaluationDomain, Polynomial};
-use crate::proof_system::widget::ProverKey;
+use crate::fft::{EvaluationDomain, Evaluations, Polynomial};
+use crate::proof_system::widget::{custom::GateWires, ProverKey};
 use anyhow::{Error, Result};
-use dusk_bls12_381::BlsScalar;
+use dusk_bls12_381::{BlsScalar, GENERATOR};
 use rayon::prelude::*;
 
 /// This quotient polynomial can only be used for the standard composer
 /// Each composer will need to implement their own method for computing the quotient polynomial
 
+#[allow(clippy::too_many_arguments)]
 /// Computes the quotient polynomial
+///
+/// If `low_memory` is set, or if the `prover_key` does not hold the
+/// evaluations of its polynomials over the 8n coset, the evaluations of the
+/// witness and selector polynomials are computed from their coefficients for
+/// one eighth of the rows of the coset at a time, so only `n` evaluations of
+/// each polynomial are kept in memory instead of `8n`.
 pub(crate) fn compute(
     domain: &EvaluationDomain,
     prover_key: &ProverKey,
     z_poly: &Polynomial,
     (w_l_poly, w_r_poly, w_o_poly, w_4_poly): (&Polynomial, &Polynomial, &Polynomial, &Polynomial),
+    (f_poly, h_1_poly, h_2_poly, p_poly): (&Polynomial, &Polynomial, &Polynomial, &Polynomial),
     public_inputs_poly: &Polynomial,
     (
         alpha,
@@ -26,8 +34,9 @@ pub(crate) fn compute(
         gamma,
         range_challenge,
         logic_challenge,
-        fixed_base_challenge,
         var_base_challenge,
+        lookup_challenge,
+        sbox_challenge,
     ): &(
         BlsScalar,
         BlsScalar,
@@ -36,112 +45,204 @@ pub(crate) fn compute(
         BlsScalar,
         BlsScalar,
         BlsScalar,
+        BlsScalar,
     ),
+    (zeta, delta, epsilon): &(BlsScalar, BlsScalar, BlsScalar),
+    custom_challenges: &[BlsScalar],
+    low_memory: bool,
 ) -> Result<Polynomial, Error> {
-    // Compute 4n eval of z(X)
-    let domain_4n = EvaluationDomain::new(4 * domain.size())?;
-    let mut z_eval_4n = domain_4n.coset_fft(&z_poly);
-    z_eval_4n.push(z_eval_4n[0]);
-    z_eval_4n.push(z_eval_4n[1]);
-    z_eval_4n.push(z_eval_4n[2]);
-    z_eval_4n.push(z_eval_4n[3]);
-
-    // Compute 4n evaluations of the wire polynomials
-    let mut wl_eval_4n = domain_4n.coset_fft(&w_l_poly);
-    wl_eval_4n.push(wl_eval_4n[0]);
-    wl_eval_4n.push(wl_eval_4n[1]);
-    wl_eval_4n.push(wl_eval_4n[2]);
-    wl_eval_4n.push(wl_eval_4n[3]);
-    let mut wr_eval_4n = domain_4n.coset_fft(&w_r_poly);
-    wr_eval_4n.push(wr_eval_4n[0]);
-    wr_eval_4n.push(wr_eval_4n[1]);
-    wr_eval_4n.push(wr_eval_4n[2]);
-    wr_eval_4n.push(wr_eval_4n[3]);
-    let wo_eval_4n = domain_4n.coset_fft(&w_o_poly);
-
-    let mut w4_eval_4n = domain_4n.coset_fft(&w_4_poly);
-    w4_eval_4n.push(w4_eval_4n[0]);
-    w4_eval_4n.push(w4_eval_4n[1]);
-    w4_eval_4n.push(w4_eval_4n[2]);
-    w4_eval_4n.push(w4_eval_4n[3]);
-
-    let t_1 = compute_circuit_satisfiability_equation(
-        &domain,
-        (
-            range_challenge,
-            logic_challenge,
-            fixed_base_challenge,
-            var_base_challenge,
-        ),
-        prover_key,
-        (&wl_eval_4n, &wr_eval_4n, &wo_eval_4n, &w4_eval_4n),
-        public_inputs_poly,
-    );
-
-    let t_2 = compute_permutation_checks(
-        domain,
-        prover_key,
-        (&wl_eval_4n, &wr_eval_4n, &wo_eval_4n, &w4_eval_4n),
-        &z_eval_4n,
-        (alpha, beta, gamma),
-    );
-
-    let quotient: Vec<_> = (0..domain_4n.size())
-        .into_par_iter()
-        .map(|i| {
-            let numerator = t_1[i] + t_2[i];
-            let denominator = prover_key.v_h_coset_4n()[i];
-            numerator * denominator.invert().unwrap()
-        })
-        .collect();
+    let domain_8n = EvaluationDomain::new(8 * domain.size())?;
+    let low_memory = low_memory || !prover_key.has_coset_evaluations();
+    let chunks = if low_memory {
+        (0..8)
+            .map(|offset| Rows::new(offset, 8, domain))
+            .collect::<Result<Vec<_>, Error>>()?
+    } else {
+        vec![Rows::new(0, 1, domain)?]
+    };
+
+    let table_poly = prover_key.lookup.compute_table_poly(zeta);
+
+    let mut quotient = vec![BlsScalar::zero(); domain_8n.size()];
+    for rows in chunks {
+        // The `k`-th row is the row `k` of the 8n coset when it is computed
+        // at once, and the row `k` of the key holding the evaluations over
+        // the rows otherwise
+        let rows_key;
+        let key = if low_memory {
+            rows_key = prover_key.evaluate_with(|poly| {
+                Evaluations::from_vec_and_domain(rows.evaluate(poly), rows.subgroup.clone())
+            });
+            &rows_key
+        } else {
+            prover_key
+        };
+
+        // Compute the evaluations of z(X) and of the wire polynomials
+        let z_evals = rows.evaluate_with_next_row(z_poly);
+        let wl_evals = rows.evaluate_with_next_row(w_l_poly);
+        let wr_evals = rows.evaluate_with_next_row(w_r_poly);
+        let wo_evals = rows.evaluate(w_o_poly);
+        let w4_evals = rows.evaluate_with_next_row(w_4_poly);
+
+        let t_1 = compute_circuit_satisfiability_equation(
+            &rows,
+            (
+                range_challenge,
+                logic_challenge,
+                var_base_challenge,
+                sbox_challenge,
+            ),
+            custom_challenges,
+            key,
+            (&wl_evals, &wr_evals, &wo_evals, &w4_evals),
+            public_inputs_poly,
+        )?;
+
+        let t_2 = compute_permutation_checks(
+            domain,
+            &rows,
+            key,
+            (&wl_evals, &wr_evals, &wo_evals, &w4_evals),
+            &z_evals,
+            (alpha, beta, gamma),
+        );
+
+        let t_3 = compute_lookup_checks(
+            domain,
+            &rows,
+            key,
+            (&wl_evals, &wr_evals, &wo_evals),
+            (f_poly, h_1_poly, h_2_poly, p_poly),
+            &table_poly,
+            lookup_challenge,
+            (zeta, delta, epsilon),
+        );
+
+        let chunk: Vec<_> = (0..rows.len)
+            .into_par_iter()
+            .map(|k| {
+                let numerator = t_1[k] + t_2[k] + t_3[k];
+                let denominator = key.v_h_coset_8n()[k];
+                numerator * denominator.invert().unwrap()
+            })
+            .collect();
+        for (k, eval) in chunk.into_iter().enumerate() {
+            quotient[rows.index(k)] = eval;
+        }
+    }
 
     Ok(Polynomial::from_coefficients_vec(
-        domain_4n.coset_ifft(&quotient),
+        domain_8n.coset_ifft(&quotient),
     ))
 }
 
+/// The rows `offset + k * stride` of the 8n coset, for `k` in `0..len`,
+/// whose quotient evaluations are computed at once.
+///
+/// The stride divides `8`, so the rows are the points `shift * w^k` of a
+/// coset of the subgroup generated by `w`, of size `len`, and the next row
+/// of the circuit of the `k`-th row is the row `k + 8 / stride`.
+#[derive(Clone)]
+struct Rows {
+    offset: usize,
+    stride: usize,
+    len: usize,
+    subgroup: EvaluationDomain,
+    shift: BlsScalar,
+}
+
+impl Rows {
+    fn new(offset: usize, stride: usize, domain: &EvaluationDomain) -> Result<Rows, Error> {
+        let domain_8n = EvaluationDomain::new(8 * domain.size())?;
+        let len = domain_8n.size() / stride;
+        Ok(Rows {
+            offset,
+            stride,
+            len,
+            subgroup: EvaluationDomain::new(len)?,
+            shift: GENERATOR * domain_8n.group_gen.pow(&[offset as u64, 0, 0, 0]),
+        })
+    }
+
+    /// Returns the index in the 8n coset of the `k`-th row.
+    fn index(&self, k: usize) -> usize {
+        self.offset + k * self.stride
+    }
+
+    /// Returns the distance between the evaluations of a row and the ones of
+    /// the next row of the circuit.
+    fn next(&self) -> usize {
+        8 / self.stride
+    }
+
+    /// Evaluates the polynomial with the given coefficients over the rows.
+    fn evaluate(&self, coeffs: &[BlsScalar]) -> Vec<BlsScalar> {
+        // The evaluations of `p(X)` over the rows are the ones of
+        // `p(shift * X)` reduced modulo `X^len - 1` over the subgroup
+        let mut reduced = vec![BlsScalar::zero(); self.len];
+        let mut pow = BlsScalar::one();
+        for (i, coeff) in coeffs.iter().enumerate() {
+            reduced[i % self.len] += coeff * pow;
+            pow *= self.shift;
+        }
+        self.subgroup.fft_in_place(&mut reduced);
+        reduced
+    }
+
+    /// Evaluates the polynomial over the rows, followed by the evaluations
+    /// of the first rows, so that `evals[k + self.next()]` is the evaluation
+    /// at the next row of the circuit for every `k`.
+    fn evaluate_with_next_row(&self, coeffs: &[BlsScalar]) -> Vec<BlsScalar> {
+        let mut evals = self.evaluate(coeffs);
+        let next: Vec<_> = evals[..self.next()].to_vec();
+        evals.extend(next);
+        evals
+    }
+}
+
 // Ensures that the circuit is satisfied
 fn compute_circuit_satisfiability_equation(
-    domain: &EvaluationDomain,
-    (range_challenge, logic_challenge, fixed_base_challenge, var_base_challenge): (
+    rows: &Rows,
+    (range_challenge, logic_challenge, var_base_challenge, sbox_challenge): (
         &BlsScalar,
         &BlsScalar,
         &BlsScalar,
         &BlsScalar,
     ),
+    custom_challenges: &[BlsScalar],
     prover_key: &ProverKey,
-    (wl_eval_4n, wr_eval_4n, wo_eval_4n, w4_eval_4n): (
+    (wl_evals, wr_evals, wo_evals, w4_evals): (
         &[BlsScalar],
         &[BlsScalar],
         &[BlsScalar],
         &[BlsScalar],
     ),
     pi_poly: &Polynomial,
-) -> Vec<BlsScalar> {
-    let domain_4n = EvaluationDomain::new(4 * domain.size()).unwrap();
-    let pi_eval_4n = domain_4n.coset_fft(pi_poly);
+) -> Result<Vec<BlsScalar>, Error> {
+    let pi_evals = rows.evaluate(pi_poly);
 
-    let t: Vec<_> = (0..domain_4n.size())
+    let t: Result<Vec<_>, Error> = (0..rows.len)
         .into_par_iter()
-        .map(|i| {
-            let wl = &wl_eval_4n[i];
-            let wr = &wr_eval_4n[i];
-            let wo = &wo_eval_4n[i];
-            let w4 = &w4_eval_4n[i];
-            let wl_next = &wl_eval_4n[i + 4];
-            let wr_next = &wr_eval_4n[i + 4];
-            let w4_next = &w4_eval_4n[i + 4];
-            let pi = &pi_eval_4n[i];
-
-            let a = prover_key.arithmetic.compute_quotient_i(i, wl, wr, wo, w4);
+        .map(|k| {
+            let wl = &wl_evals[k];
+            let wr = &wr_evals[k];
+            let wo = &wo_evals[k];
+            let w4 = &w4_evals[k];
+            let wl_next = &wl_evals[k + rows.next()];
+            let wr_next = &wr_evals[k + rows.next()];
+            let w4_next = &w4_evals[k + rows.next()];
+            let pi = &pi_evals[k];
+
+            let a = prover_key.arithmetic.compute_quotient_i(k, wl, wr, wo, w4);
 
             let b =
                 prover_key
                     .range
-                    .compute_quotient_i(i, range_challenge, wl, wr, wo, w4, w4_next);
+                    .compute_quotient_i(k, range_challenge, wl, wr, wo, w4, w4_next);
 
             let c = prover_key.logic.compute_quotient_i(
-                i,
+                k,
                 logic_challenge,
                 &wl,
                 &wl_next,
@@ -152,20 +253,8 @@ fn compute_circuit_satisfiability_equation(
                 &w4_next,
             );
 
-            let d = prover_key.fixed_base.compute_quotient_i(
-                i,
-                fixed_base_challenge,
-                &wl,
-                &wl_next,
-                &wr,
-                &wr_next,
-                &wo,
-                &w4,
-                &w4_next,
-            );
-
-            let e = prover_key.variable_base.compute_quotient_i(
-                i,
+            let d = prover_key.variable_base.compute_quotient_i(
+                k,
                 var_base_challenge,
                 &wl,
                 &wl_next,
@@ -176,7 +265,27 @@ fn compute_circuit_satisfiability_equation(
                 &w4_next,
             );
 
-            (a + pi) + b + c + d + e
+            let e = prover_key
+                .sbox
+                .compute_quotient_i(k, sbox_challenge, wl, wo, w4);
+
+            let wires = GateWires {
+                a: *wl,
+                b: *wr,
+                c: *wo,
+                d: *w4,
+                a_next: *wl_next,
+                b_next: *wr_next,
+                d_next: *w4_next,
+            };
+            let f = prover_key
+                .custom
+                .iter()
+                .zip(custom_challenges.iter())
+                .map(|(custom, challenge)| custom.compute_quotient_i(k, challenge, &wires))
+                .sum::<Result<BlsScalar, Error>>()?;
+
+            Ok((a + pi) + b + c + d + e + f)
         })
         .collect();
     t
@@ -184,33 +293,33 @@ fn compute_circuit_satisfiability_equation(
 
 fn compute_permutation_checks(
     domain: &EvaluationDomain,
+    rows: &Rows,
     prover_key: &ProverKey,
-    (wl_eval_4n, wr_eval_4n, wo_eval_4n, w4_eval_4n): (
+    (wl_evals, wr_evals, wo_evals, w4_evals): (
         &[BlsScalar],
         &[BlsScalar],
         &[BlsScalar],
         &[BlsScalar],
     ),
-    z_eval_4n: &[BlsScalar],
+    z_evals: &[BlsScalar],
     (alpha, beta, gamma): (&BlsScalar, &BlsScalar, &BlsScalar),
 ) -> Vec<BlsScalar> {
-    let domain_4n = EvaluationDomain::new(4 * domain.size()).unwrap();
     let l1_poly_alpha = compute_first_lagrange_poly_scaled(domain, alpha.square());
-    let l1_alpha_sq_evals = domain_4n.coset_fft(&l1_poly_alpha.coeffs);
+    let l1_alpha_sq_evals = rows.evaluate(&l1_poly_alpha.coeffs);
 
-    let t: Vec<_> = (0..domain_4n.size())
+    let t: Vec<_> = (0..rows.len)
         .into_par_iter()
-        .map(|i| {
+        .map(|k| {
             prover_key.permutation.compute_quotient_i(
-                i,
-                &wl_eval_4n[i],
-                &wr_eval_4n[i],
-                &wo_eval_4n[i],
-                &w4_eval_4n[i],
-                &z_eval_4n[i],
-                &z_eval_4n[i + 4],
+                k,
+                &wl_evals[k],
+                &wr_evals[k],
+                &wo_evals[k],
+                &w4_evals[k],
+                &z_evals[k],
+                &z_evals[k + rows.next()],
                 &alpha,
-                &l1_alpha_sq_evals[i],
+                &l1_alpha_sq_evals[k],
                 &beta,
                 &gamma,
             )
@@ -218,9 +327,80 @@ fn compute_permutation_checks(
         .collect();
     t
 }
+
+#[allow(clippy::too_many_arguments)]
+fn compute_lookup_checks(
+    domain: &EvaluationDomain,
+    rows: &Rows,
+    prover_key: &ProverKey,
+    (wl_evals, wr_evals, wo_evals): (&[BlsScalar], &[BlsScalar], &[BlsScalar]),
+    (f_poly, h_1_poly, h_2_poly, p_poly): (&Polynomial, &Polynomial, &Polynomial, &Polynomial),
+    table_poly: &Polynomial,
+    lookup_challenge: &BlsScalar,
+    (zeta, delta, epsilon): (&BlsScalar, &BlsScalar, &BlsScalar),
+) -> Vec<BlsScalar> {
+    let l1_poly = compute_first_lagrange_poly_scaled(domain, BlsScalar::one());
+    let l1_evals = rows.evaluate(&l1_poly.coeffs);
+
+    let table_evals = rows.evaluate_with_next_row(table_poly);
+    let f_evals = rows.evaluate(f_poly);
+    let h_1_evals = rows.evaluate_with_next_row(h_1_poly);
+    let h_2_evals = rows.evaluate(h_2_poly);
+    let p_evals = rows.evaluate_with_next_row(p_poly);
+
+    let t: Vec<_> = (0..rows.len)
+        .into_par_iter()
+        .map(|k| {
+            let next = k + rows.next();
+            prover_key.lookup.compute_quotient_i(
+                k,
+                lookup_challenge,
+                (zeta, delta, epsilon),
+                (&wl_evals[k], &wr_evals[k], &wo_evals[k]),
+                &f_evals[k],
+                (&h_1_evals[k], &h_1_evals[next]),
+                &h_2_evals[k],
+                (&table_evals[k], &table_evals[next]),
+                (&p_evals[k], &p_evals[next]),
+                &l1_evals[k],
+            )
+        })
+        .collect();
+    t
+}
+
 fn compute_first_lagrange_poly_scaled(domain: &EvaluationDomain, scale: BlsScalar) -> Polynomial {
     let mut x_evals = vec![BlsScalar::zero(); domain.size()];
     x_evals[0] = scale;
     domain.ifft_in_place(&mut x_evals);
     Polynomial::from_coefficients_vec(x_evals)
 }
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_evaluate_rows_in_chunks() {
+        let domain = EvaluationDomain::new(16).unwrap();
+        let domain_8n = EvaluationDomain::new(8 * domain.size()).unwrap();
+        // A polynomial of degree larger than the subgroups of the chunks
+        let poly = Polynomial::rand(domain.size() + 3, &mut rand::thread_rng());
+        let evals_8n = domain_8n.coset_fft(&poly);
+
+        let rows = Rows::new(0, 1, &domain).unwrap();
+        assert_eq!(rows.evaluate(&poly), evals_8n);
+
+        for offset in 0..8 {
+            let rows = Rows::new(offset, 8, &domain).unwrap();
+            let evals = rows.evaluate_with_next_row(&poly);
+            for k in 0..rows.len {
+                assert_eq!(evals[k], evals_8n[rows.index(k)]);
+                assert_eq!(
+                    evals[k + rows.next()],
+                    evals_8n[(rows.index(k) + 8) % domain_8n.size()]
+                );
+            }
+        }
+    }
+}
@@ -4,16 +4,21 @@
 //
 // Copyright (c) DUSK NETWORK. All rights reserved.
 
+use super::proof_system_errors::ProofErrors;
 use crate::commitment_scheme::kzg10::{CommitKey, OpeningKey};
 use crate::constraint_system::StandardComposer;
 use crate::proof_system::widget::VerifierKey;
 use crate::proof_system::Proof;
+use crate::transcript::TranscriptProtocol;
 use anyhow::{Error, Result};
-use dusk_bls12_381::BlsScalar;
+use dusk_bls12_381::{BlsScalar, G1Projective};
 use merlin::Transcript;
 /// Verifier verifies a proof
+///
+/// The Verifier is generic over the transcript used to derive the challenges,
+/// which defaults to the Merlin `Transcript`. It must match the one of the `Prover`.
 #[allow(missing_debug_implementations)]
-pub struct Verifier {
+pub struct Verifier<T: TranscriptProtocol = Transcript> {
     /// VerificationKey which is used to verify a specific PLONK circuit
     pub verifier_key: Option<VerifierKey>,
 
@@ -22,7 +27,7 @@ pub struct Verifier {
     /// This is copied each time, we make a proof, so that we can use the same verifier to
     /// Verify multiple proofs from the same circuit. If this is not copied, then the verification procedure will modify
     /// the transcript, making it unusable for future proofs.
-    pub preprocessed_transcript: Transcript,
+    pub preprocessed_transcript: T,
 }
 
 impl Default for Verifier {
@@ -49,6 +54,17 @@ impl Verifier {
             preprocessed_transcript: Transcript::new(label),
         }
     }
+}
+
+impl<T: TranscriptProtocol> Verifier<T> {
+    /// Creates a new verifier object which uses the given transcript backend
+    pub fn with_transcript(transcript: T) -> Verifier<T> {
+        Verifier {
+            verifier_key: None,
+            cs: StandardComposer::new(),
+            preprocessed_transcript: transcript,
+        }
+    }
 
     /// Returns the number of gates in the circuit
     pub fn circuit_size(&self) -> usize {
@@ -62,9 +78,7 @@ impl Verifier {
 
     /// Preprocess a proof
     pub fn preprocess(&mut self, commit_key: &CommitKey) -> Result<(), Error> {
-        let vk = self
-            .cs
-            .preprocess_verifier(commit_key, &mut self.preprocessed_transcript)?;
+        let vk = self.cs.preprocess_verifier(commit_key)?;
 
         self.verifier_key = Some(vk);
         Ok(())
@@ -76,15 +90,42 @@ impl Verifier {
         self.preprocessed_transcript.append_message(label, message);
     }
 
-    /// Verifies a proof
+    /// Verifies a proof, given the values of the public inputs in the order
+    /// they were declared with `StandardComposer::public_input`.
+    ///
+    /// The values are placed at the gates recorded in the
+    /// `PublicInputLayout` of the `VerifierKey`.
     pub fn verify(
         &self,
         proof: &Proof,
         opening_key: &OpeningKey,
         public_inputs: &[BlsScalar],
+    ) -> Result<(), Error> {
+        let verifier_key = self
+            .verifier_key
+            .as_ref()
+            .ok_or(ProofErrors::CircuitNotPreprocessed)?;
+        let public_inputs = verifier_key.public_inputs.assign(public_inputs)?;
+        self.verify_sparse(proof, opening_key, &public_inputs)
+    }
+
+    /// Verifies a proof, given its non-zero public inputs along with the
+    /// index of their gate, such as the ones returned by
+    /// `StandardComposer::sparse_public_inputs`.
+    ///
+    /// Only needed for circuits whose gates set their public input directly,
+    /// instead of declaring it with `StandardComposer::public_input`.
+    pub fn verify_sparse(
+        &self,
+        proof: &Proof,
+        opening_key: &OpeningKey,
+        public_inputs: &[(usize, BlsScalar)],
     ) -> Result<(), Error> {
         let mut cloned_transcript = self.preprocessed_transcript.clone();
-        let verifier_key = self.verifier_key.as_ref().unwrap();
+        let verifier_key = self
+            .verifier_key
+            .as_ref()
+            .ok_or(ProofErrors::CircuitNotPreprocessed)?;
 
         proof.verify(
             verifier_key,
@@ -93,4 +134,218 @@ impl Verifier {
             public_inputs,
         )
     }
+
+    /// Verifies a batch of proofs of the circuit, each one along with the values of
+    /// its public inputs, in the order they were declared.
+    ///
+    /// The openings of every proof are folded together using random factors chosen by
+    /// the verifier, so the whole batch is checked with a single pairing.
+    /// If the batch is rejected, the error holds the index of the first proof that
+    /// fails verification, or whose public inputs do not match the circuit.
+    pub fn verify_batch(
+        &self,
+        proofs: &[(Proof, Vec<BlsScalar>)],
+        opening_key: &OpeningKey,
+    ) -> Result<(), Error> {
+        let verifier_key = self
+            .verifier_key
+            .as_ref()
+            .ok_or(ProofErrors::CircuitNotPreprocessed)?;
+
+        let folded_openings = proofs
+            .iter()
+            .enumerate()
+            .map(|(index, (proof, public_inputs))| {
+                let mut cloned_transcript = self.preprocessed_transcript.clone();
+                verifier_key
+                    .public_inputs
+                    .assign(public_inputs)
+                    .and_then(|public_inputs| {
+                        proof.fold_openings(
+                            verifier_key,
+                            &mut cloned_transcript,
+                            opening_key,
+                            &public_inputs,
+                        )
+                    })
+                    .map_err(|_| ProofErrors::BatchVerificationError(index).into())
+            })
+            .collect::<Result<Vec<_>, Error>>()?;
+
+        // Scale each proof by a random factor, so that invalid proofs can not
+        // cancel each other out
+        let mut rng = rand::thread_rng();
+        let mut total_w = G1Projective::identity();
+        let mut total_c = G1Projective::identity();
+        for (w, c) in folded_openings.iter() {
+            let factor = BlsScalar::random(&mut rng);
+            total_w += w * factor;
+            total_c += c * factor;
+        }
+
+        if opening_key.check_folded(total_w, total_c).is_ok() {
+            return Ok(());
+        }
+
+        // Find out which proof made the batch fail
+        match folded_openings
+            .iter()
+            .position(|(w, c)| opening_key.check_folded(*w, *c).is_err())
+        {
+            Some(index) => Err(ProofErrors::BatchVerificationError(index).into()),
+            None => Err(ProofErrors::ProofVerificationError.into()),
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::commitment_scheme::kzg10::PublicParameters;
+    use crate::constraint_system::helper::dummy_gadget;
+    use crate::proof_system::{Prover, PublicInputLayout};
+
+    #[test]
+    fn test_verify_batch() {
+        let public_parameters = PublicParameters::setup(2 * 30, &mut rand::thread_rng()).unwrap();
+        let (ck, vk) = public_parameters.trim(2 * 20).unwrap();
+        let gadget = |composer: &mut StandardComposer, value: u64| {
+            dummy_gadget(10, composer);
+            composer.public_input("value", BlsScalar::from(value));
+        };
+
+        let mut prover = Prover::new(b"demo");
+        gadget(prover.mut_cs(), 0);
+        prover.preprocess(&ck).unwrap();
+
+        let mut proofs = vec![(prover.prove(&ck).unwrap(), vec![BlsScalar::zero()])];
+        for value in 1..3 {
+            gadget(prover.mut_cs(), value);
+            proofs.push((prover.prove(&ck).unwrap(), vec![BlsScalar::from(value)]));
+        }
+
+        let mut verifier = Verifier::new(b"demo");
+        gadget(verifier.mut_cs(), 0);
+        verifier.preprocess(&ck).unwrap();
+
+        assert!(verifier.verify_batch(&proofs, &vk).is_ok());
+
+        // Should fail as the second proof is checked against the wrong public inputs
+        proofs[1].1[0] = BlsScalar::from(2);
+        let err = verifier.verify_batch(&proofs, &vk).unwrap_err();
+        match err.downcast_ref::<ProofErrors>() {
+            Some(ProofErrors::BatchVerificationError(index)) => assert_eq!(*index, 1),
+            _ => panic!("unexpected error: {}", err),
+        }
+
+        // Should fail as the public input of the third proof is missing
+        proofs[1].1[0] = BlsScalar::one();
+        proofs[2].1.clear();
+        let err = verifier.verify_batch(&proofs, &vk).unwrap_err();
+        match err.downcast_ref::<ProofErrors>() {
+            Some(ProofErrors::BatchVerificationError(index)) => assert_eq!(*index, 2),
+            _ => panic!("unexpected error: {}", err),
+        }
+
+        // Should fail as the circuit of the verifier is not preprocessed
+        let err = Verifier::new(b"demo")
+            .verify_batch(&proofs, &vk)
+            .unwrap_err();
+        match err.downcast_ref::<ProofErrors>() {
+            Some(ProofErrors::CircuitNotPreprocessed) => (),
+            _ => panic!("unexpected error: {}", err),
+        }
+    }
+
+    #[test]
+    fn test_verify_sparse() {
+        let public_parameters = PublicParameters::setup(2 * 30, &mut rand::thread_rng()).unwrap();
+        let (ck, vk) = public_parameters.trim(2 * 20).unwrap();
+        let gadget = |composer: &mut StandardComposer| {
+            dummy_gadget(10, composer);
+            let var_one = composer.add_input(BlsScalar::one());
+            composer.constrain_to_constant(var_one, BlsScalar::zero(), -BlsScalar::one());
+        };
+
+        let mut prover = Prover::new(b"demo");
+        gadget(prover.mut_cs());
+        prover.preprocess(&ck).unwrap();
+        let public_inputs = prover.cs.sparse_public_inputs();
+        let proof = prover.prove(&ck).unwrap();
+        assert_eq!(public_inputs.len(), 1);
+
+        let mut verifier = Verifier::new(b"demo");
+        gadget(verifier.mut_cs());
+        verifier.preprocess(&ck).unwrap();
+        assert!(verifier.verify_sparse(&proof, &vk, &public_inputs).is_ok());
+
+        // Should fail as the public input is not at the index of its gate
+        let (index, pi) = public_inputs[0];
+        let err = verifier
+            .verify_sparse(&proof, &vk, &[(index + 1, pi)])
+            .unwrap_err();
+        match err.downcast_ref::<ProofErrors>() {
+            Some(ProofErrors::InvalidPublicInputGate(invalid)) => assert_eq!(*invalid, index + 1),
+            _ => panic!("unexpected error: {}", err),
+        }
+
+        // Should fail as the index is out of the circuit
+        let out_of_range = verifier.circuit_size() + 1;
+        let err = verifier
+            .verify_sparse(&proof, &vk, &[(out_of_range, pi)])
+            .unwrap_err();
+        match err.downcast_ref::<ProofErrors>() {
+            Some(ProofErrors::InvalidPublicInputGate(invalid)) => {
+                assert_eq!(*invalid, out_of_range)
+            }
+            _ => panic!("unexpected error: {}", err),
+        }
+
+        // Should fail as the public input is split between two entries for
+        // its gate
+        let half = pi * BlsScalar::from(2).invert().unwrap();
+        let err = verifier
+            .verify_sparse(&proof, &vk, &[(index, half), (index, half)])
+            .unwrap_err();
+        match err.downcast_ref::<ProofErrors>() {
+            Some(ProofErrors::DuplicatePublicInputGate(duplicate)) => assert_eq!(*duplicate, index),
+            _ => panic!("unexpected error: {}", err),
+        }
+
+        // Should fail as the circuit of the verifier is not preprocessed
+        let err = Verifier::new(b"demo").verify(&proof, &vk, &[]).unwrap_err();
+        match err.downcast_ref::<ProofErrors>() {
+            Some(ProofErrors::CircuitNotPreprocessed) => (),
+            _ => panic!("unexpected error: {}", err),
+        }
+    }
+
+    #[test]
+    fn test_proof_binds_verifier_key() {
+        let public_parameters = PublicParameters::setup(2 * 30, &mut rand::thread_rng()).unwrap();
+        let (ck, vk) = public_parameters.trim(2 * 20).unwrap();
+        let gadget = |composer: &mut StandardComposer| {
+            dummy_gadget(10, composer);
+            composer.public_input("value", BlsScalar::from(7));
+        };
+
+        let mut prover = Prover::new(b"demo");
+        gadget(prover.mut_cs());
+        prover.preprocess(&ck).unwrap();
+        let proof = prover.prove(&ck).unwrap();
+
+        let mut verifier = Verifier::new(b"demo");
+        gadget(verifier.mut_cs());
+        verifier.preprocess(&ck).unwrap();
+        assert!(verifier.verify(&proof, &vk, &[BlsScalar::from(7)]).is_ok());
+
+        // Should fail as the public input is renamed, although the gates and
+        // the public input polynomial are unchanged
+        let verifier_key = verifier.verifier_key.as_mut().unwrap();
+        let position = verifier_key.public_inputs.position("value").unwrap();
+        let mut renamed = PublicInputLayout::default();
+        renamed.push("other", position);
+        verifier_key.public_inputs = renamed;
+        assert!(verifier.verify(&proof, &vk, &[BlsScalar::from(7)]).is_err());
+    }
 }
@@ -0,0 +1,150 @@
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this
+// file, You can obtain one at http://mozilla.org/MPL/2.0/.
+//
+// Copyright (c) DUSK NETWORK. All rights reserved.
+
+//! Custom gates which are defined outside of the crate.
+//!
+//! A custom gate owns a set of selector polynomials `q_j(X)` and constrains
+//! every row of the circuit with an identity which is linear in them:
+//!
+//! `\sum_j q_j(X) * f_j(wires) = 0`
+//!
+//! The gate only has to provide the coefficients `f_j`, from which the
+//! quotient and linearisation polynomials and the linearisation commitment
+//! are derived in the same way as for the built-in widgets.
+//!
+//! The fixed base curve addition gate of the crate is itself a custom gate,
+//! which is resolved without being provided when deserialising a key.
+
+mod proverkey;
+mod verifierkey;
+
+pub use proverkey::ProverKey;
+pub use verifierkey::VerifierKey;
+
+use crate::proof_system::linearisation_poly::ProofEvaluations;
+use crate::proof_system::proof_system_errors::ProofErrors;
+use crate::proof_system::widget::ecc::scalar_mul::fixed_base::FixedBaseGate;
+use crate::serialisation::{read_n, read_u64, write_u64};
+use anyhow::{Error, Result};
+use dusk_bls12_381::BlsScalar;
+use std::fmt::Debug;
+use std::sync::Arc;
+
+/// Values of the wires a custom gate can constrain, at the current row and,
+/// for the shifted wires, at the next row.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub struct GateWires {
+    /// Left wire
+    pub a: BlsScalar,
+    /// Right wire
+    pub b: BlsScalar,
+    /// Output wire
+    pub c: BlsScalar,
+    /// Fourth wire
+    pub d: BlsScalar,
+    /// Left wire at the next row
+    pub a_next: BlsScalar,
+    /// Right wire at the next row
+    pub b_next: BlsScalar,
+    /// Fourth wire at the next row
+    pub d_next: BlsScalar,
+}
+
+impl GateWires {
+    /// Gathers the wire evaluations of a proof.
+    pub(crate) fn from_evaluations(evaluations: &ProofEvaluations) -> GateWires {
+        GateWires {
+            a: evaluations.a_eval,
+            b: evaluations.b_eval,
+            c: evaluations.c_eval,
+            d: evaluations.d_eval,
+            a_next: evaluations.a_next_eval,
+            b_next: evaluations.b_next_eval,
+            d_next: evaluations.d_next_eval,
+        }
+    }
+}
+
+/// A gate which can be registered in a `StandardComposer` and is picked up by
+/// the preprocessing, the prover, the verifier and the key serialisation.
+///
+/// The coefficients may be of degree at most 4 in the wires, as for the
+/// built-in range and logic gates, so that the quotient polynomial can still
+/// be computed over the 8n coset.
+pub trait CustomGate: Debug + Send + Sync {
+    /// Unique name of the gate. It identifies the gate in the transcript and
+    /// in the serialised keys.
+    fn name(&self) -> &'static str;
+
+    /// Number of selector polynomials of the gate.
+    fn num_selectors(&self) -> usize;
+
+    /// Returns the coefficient `f_j` of each of the `num_selectors` selectors
+    /// for the given `wires`. Proving and verifying fail with
+    /// `ProofErrors::CustomGateCoefficientCount` if there are more or fewer
+    /// coefficients than selectors.
+    ///
+    /// The gate's `separation_challenge` can be used to combine several
+    /// identities, as the built-in gates do with `kappa`. The whole identity
+    /// is multiplied by the separation challenge afterwards.
+    fn coefficients(&self, wires: &GateWires, separation_challenge: &BlsScalar) -> Vec<BlsScalar>;
+}
+
+// Returns the coefficients of the gate, checking that there is one per selector
+pub(crate) fn checked_coefficients(
+    gate: &dyn CustomGate,
+    wires: &GateWires,
+    separation_challenge: &BlsScalar,
+) -> Result<Vec<BlsScalar>, Error> {
+    let coefficients = gate.coefficients(wires, separation_challenge);
+    if coefficients.len() != gate.num_selectors() {
+        return Err(ProofErrors::CustomGateCoefficientCount {
+            name: gate.name(),
+            expected: gate.num_selectors(),
+            found: coefficients.len(),
+        }
+        .into());
+    }
+    Ok(coefficients)
+}
+
+// Two gates are considered the same if they share their name and number of selectors
+pub(crate) fn same_gate(gate: &dyn CustomGate, other: &dyn CustomGate) -> bool {
+    gate.name() == other.name() && gate.num_selectors() == other.num_selectors()
+}
+
+// Writes the name of the gate followed by its number of selectors
+pub(crate) fn write_gate(gate: &dyn CustomGate, bytes: &mut Vec<u8>) {
+    write_u64(gate.name().len() as u64, bytes);
+    bytes.extend_from_slice(gate.name().as_bytes());
+    write_u64(gate.num_selectors() as u64, bytes);
+}
+
+// The custom gates defined in the crate
+pub(crate) fn builtin_gates() -> Vec<Arc<dyn CustomGate>> {
+    vec![Arc::new(FixedBaseGate)]
+}
+
+// Reads the name and number of selectors of a gate and looks it up in `gates`
+// and in the built-in gates
+pub(crate) fn read_gate<'a>(
+    bytes: &'a [u8],
+    gates: &[Arc<dyn CustomGate>],
+) -> Result<(Arc<dyn CustomGate>, &'a [u8]), Error> {
+    let (name_len, rest) = read_u64(bytes)?;
+    let (name, rest) = read_n(name_len as usize, rest)?;
+    let (num_selectors, rest) = read_u64(rest)?;
+
+    let name = String::from_utf8_lossy(name);
+    let builtin = builtin_gates();
+    let gate = gates
+        .iter()
+        .chain(builtin.iter())
+        .find(|gate| gate.name() == name && gate.num_selectors() as u64 == num_selectors)
+        .ok_or_else(|| ProofErrors::UnknownCustomGate(name.into_owned()))?;
+
+    Ok((gate.clone(), rest))
+}
@@ -0,0 +1,89 @@
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this
+// file, You can obtain one at http://mozilla.org/MPL/2.0/.
+//
+// Copyright (c) DUSK NETWORK. All rights reserved.
+
+use super::{checked_coefficients, read_gate, same_gate, write_gate, CustomGate, GateWires};
+use crate::fft::{EvaluationDomain, Evaluations, Polynomial};
+use anyhow::{Error, Result};
+use dusk_bls12_381::BlsScalar;
+use std::sync::Arc;
+
+#[derive(Debug, Clone)]
+pub struct ProverKey {
+    pub gate: Arc<dyn CustomGate>,
+    pub selectors: Vec<(Polynomial, Evaluations)>,
+}
+
+impl PartialEq for ProverKey {
+    fn eq(&self, other: &Self) -> bool {
+        same_gate(self.gate.as_ref(), other.gate.as_ref()) && self.selectors == other.selectors
+    }
+}
+
+impl Eq for ProverKey {}
+
+impl ProverKey {
+    pub(crate) fn compute_quotient_i(
+        &self,
+        index: usize,
+        separation_challenge: &BlsScalar,
+        wires: &GateWires,
+    ) -> Result<BlsScalar, Error> {
+        let coefficients = checked_coefficients(self.gate.as_ref(), wires, separation_challenge)?;
+
+        // (\sum_j q_j(X) * f_j(wires)) * separation_challenge
+        let identity: BlsScalar = self
+            .selectors
+            .iter()
+            .zip(coefficients.iter())
+            .map(|(selector, coefficient)| selector.1[index] * coefficient)
+            .sum();
+        Ok(identity * separation_challenge)
+    }
+
+    pub(crate) fn compute_linearisation(
+        &self,
+        separation_challenge: &BlsScalar,
+        wires: &GateWires,
+    ) -> Result<Polynomial, Error> {
+        let coefficients = checked_coefficients(self.gate.as_ref(), wires, separation_challenge)?;
+
+        let mut linearisation_poly = Polynomial::zero();
+        for (selector, coefficient) in self.selectors.iter().zip(coefficients.iter()) {
+            linearisation_poly += &(&selector.0 * &(coefficient * separation_challenge));
+        }
+        Ok(linearisation_poly)
+    }
+
+    pub(crate) fn write(&self, bytes: &mut Vec<u8>) {
+        use crate::serialisation::{write_evaluations, write_polynomial};
+
+        write_gate(self.gate.as_ref(), bytes);
+        for (poly, evals) in self.selectors.iter() {
+            write_polynomial(poly, bytes);
+            write_evaluations(evals, bytes);
+        }
+    }
+
+    pub(crate) fn read<'a>(
+        bytes: &'a [u8],
+        domain: &EvaluationDomain,
+        gates: &[Arc<dyn CustomGate>],
+    ) -> Result<(ProverKey, &'a [u8]), Error> {
+        use crate::serialisation::{read_evaluations, read_polynomial};
+
+        let (gate, mut rest) = read_gate(bytes, gates)?;
+
+        let mut selectors = Vec::with_capacity(gate.num_selectors());
+        for _ in 0..gate.num_selectors() {
+            let (poly, next) = read_polynomial(rest)?;
+            let (evals, next) = read_evaluations(domain.clone(), next)?;
+            selectors.push((poly, evals));
+            rest = next;
+        }
+
+        Ok((ProverKey { gate, selectors }, rest))
+    }
+}
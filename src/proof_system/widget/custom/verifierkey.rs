@@ -0,0 +1,72 @@
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this
+// file, You can obtain one at http://mozilla.org/MPL/2.0/.
+//
+// Copyright (c) DUSK NETWORK. All rights reserved.
+
+use super::{checked_coefficients, read_gate, same_gate, write_gate, CustomGate, GateWires};
+use crate::commitment_scheme::kzg10::Commitment;
+use crate::proof_system::linearisation_poly::ProofEvaluations;
+use anyhow::{Error, Result};
+use dusk_bls12_381::{BlsScalar, G1Affine};
+use std::sync::Arc;
+
+#[derive(Debug, Clone)]
+pub struct VerifierKey {
+    pub gate: Arc<dyn CustomGate>,
+    pub selectors: Vec<Commitment>,
+}
+
+impl PartialEq for VerifierKey {
+    fn eq(&self, other: &Self) -> bool {
+        same_gate(self.gate.as_ref(), other.gate.as_ref()) && self.selectors == other.selectors
+    }
+}
+
+impl Eq for VerifierKey {}
+
+impl VerifierKey {
+    pub(crate) fn compute_linearisation_commitment(
+        &self,
+        separation_challenge: &BlsScalar,
+        scalars: &mut Vec<BlsScalar>,
+        points: &mut Vec<G1Affine>,
+        evaluations: &ProofEvaluations,
+    ) -> Result<(), Error> {
+        let wires = GateWires::from_evaluations(evaluations);
+        let coefficients = checked_coefficients(self.gate.as_ref(), &wires, separation_challenge)?;
+
+        for (selector, coefficient) in self.selectors.iter().zip(coefficients.iter()) {
+            scalars.push(coefficient * separation_challenge);
+            points.push(selector.0);
+        }
+        Ok(())
+    }
+
+    pub(crate) fn write(&self, bytes: &mut Vec<u8>) {
+        use crate::serialisation::write_commitment;
+
+        write_gate(self.gate.as_ref(), bytes);
+        for selector in self.selectors.iter() {
+            write_commitment(selector, bytes);
+        }
+    }
+
+    pub(crate) fn read<'a>(
+        bytes: &'a [u8],
+        gates: &[Arc<dyn CustomGate>],
+    ) -> Result<(VerifierKey, &'a [u8]), Error> {
+        use crate::serialisation::read_commitment;
+
+        let (gate, mut rest) = read_gate(bytes, gates)?;
+
+        let mut selectors = Vec::with_capacity(gate.num_selectors());
+        for _ in 0..gate.num_selectors() {
+            let (selector, next) = read_commitment(rest)?;
+            selectors.push(selector);
+            rest = next;
+        }
+
+        Ok((VerifierKey { gate, selectors }, rest))
+    }
+}
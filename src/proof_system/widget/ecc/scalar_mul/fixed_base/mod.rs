@@ -4,27 +4,97 @@
 //
 // Copyright (c) DUSK NETWORK. All rights reserved.
 
-mod proverkey;
-mod verifierkey;
-
-pub use proverkey::ProverKey;
-pub use verifierkey::VerifierKey;
-
 // Note: The ECC gadget does not check that the initial point is on the curve for two reasons:
-// - We constrain the accumulator to start from the identity point, which the verifier knows is on the curve
+// - We constrain the accumulator to start from a multiple of the generator fixed in the circuit description, which the verifier knows is on the curve
 // - We are adding multiples of the generator to the accumulator which the verifier also knows is on the curve and is prime order
-// - We do allow arbitrary BlsScalar multiplication, and possibly XXX: may add constraints to ensure the generator is correct (prime order)
+// - The generator must have prime order, which `GeneratorTable::new` checks, since the gadget adds multiples of its half
 
-// Bits are accumulated in base2. So we use d(Xw) - 2d(X) to extract the base2 bit
+// Bits are odd digits in {-3, -1, 1, 3}, accumulated in base4. So we use d(Xw) - 4d(X) to extract
+// the base4 digit. Negating a point on the Edwards curve negates its x coordinate, so the x
+// coordinate of the point a gate adds is an odd polynomial of the digit, the y coordinate is an
+// even one, and two coefficients interpolate each of them over the four digits.
+//
+// The gate is a built-in `CustomGate`, so only the circuits doing fixed base scalar
+// multiplications hold its selectors. Each interpolation coefficient has its own selector, which
+// keeps the identity linear in the selectors and within degree 4 in the wires.
 
+use crate::proof_system::widget::custom::{CustomGate, GateWires};
 use dusk_bls12_381::BlsScalar;
-fn extract_bit(curr_acc: &BlsScalar, next_acc: &BlsScalar) -> BlsScalar {
-    // Next - 2 * current
-    next_acc - (curr_acc + curr_acc)
+use dusk_jubjub::EDWARDS_D;
+
+pub(crate) fn extract_bit(curr_acc: &BlsScalar, next_acc: &BlsScalar) -> BlsScalar {
+    // Next - 4 * current
+    next_acc - curr_acc.double().double()
 }
 
-// Ensures that the bit is either +1, -1 or 0
-fn check_bit_consistency(bit: BlsScalar) -> BlsScalar {
-    let one = BlsScalar::one();
-    bit * (bit - one) * (bit + one)
+// Ensures that the bit is either +/-1 or +/-3
+pub(crate) fn check_bit_consistency(bit: BlsScalar) -> BlsScalar {
+    let bit_sq = bit.square();
+    (bit_sq - BlsScalar::one()) * (bit_sq - BlsScalar::from(9))
+}
+
+/// Fixed base curve addition gate, adding to the accumulator the multiple of
+/// the generator matching one odd base 4 digit of the scalar.
+///
+/// Its selectors are `q_fixed_group_add`, followed by the coefficients
+/// `x_beta`, `y_beta`, `xy_beta`, `x_gamma`, `y_gamma` and `xy_gamma`
+/// interpolating the added point from the digit.
+#[derive(Debug)]
+pub(crate) struct FixedBaseGate;
+
+impl CustomGate for FixedBaseGate {
+    fn name(&self) -> &'static str {
+        "fixed_base_add"
+    }
+
+    fn num_selectors(&self) -> usize {
+        7
+    }
+
+    fn coefficients(
+        &self,
+        wires: &GateWires,
+        ecc_separation_challenge: &BlsScalar,
+    ) -> Vec<BlsScalar> {
+        let kappa = ecc_separation_challenge.square();
+        let kappa_sq = kappa.square();
+        let kappa_cu = kappa_sq * kappa;
+
+        let acc_x = wires.a;
+        let acc_x_next = wires.a_next;
+        let acc_y = wires.b;
+        let acc_y_next = wires.b_next;
+
+        let xy_alpha = wires.c;
+
+        let bit = extract_bit(&wires.d, &wires.d_next);
+        let bit_sq = bit.square();
+        let bit_cu = bit_sq * bit;
+
+        // Check bit consistency
+        let bit_consistency = check_bit_consistency(bit);
+
+        // Parts of the accumulator consistency checks which do not depend on
+        // the added point, whose coordinates are
+        // x_alpha = bit * x_beta + bit^3 * x_gamma and
+        // y_alpha = y_beta + bit^2 * y_gamma
+        let xy_acc = xy_alpha * acc_x * acc_y * EDWARDS_D;
+        let x_acc_consistency = (acc_x_next + acc_x_next * xy_acc) * kappa_sq;
+        let y_acc_consistency = (acc_y_next - acc_y_next * xy_acc) * kappa_cu;
+
+        // x_alpha is multiplied by acc_y in the x accumulator check and by
+        // acc_x in the y accumulator check, and conversely for y_alpha
+        let x_alpha_factor = -(kappa_sq * acc_y + kappa_cu * acc_x);
+        let y_alpha_factor = -(kappa_sq * acc_x + kappa_cu * acc_y);
+
+        vec![
+            bit_consistency - xy_alpha * kappa + x_acc_consistency + y_acc_consistency,
+            bit * x_alpha_factor,
+            y_alpha_factor,
+            bit * kappa,
+            bit_cu * x_alpha_factor,
+            bit_sq * y_alpha_factor,
+            bit_cu * kappa,
+        ]
+    }
 }
@@ -17,7 +17,7 @@ pub use verifierkey::VerifierKey;
 ///
 use dusk_bls12_381::BlsScalar;
 // Computes f(f-1)(f-2)(f-3)
-fn delta(f: BlsScalar) -> BlsScalar {
+pub(crate) fn delta(f: BlsScalar) -> BlsScalar {
     let f_1 = f - BlsScalar::one();
     let f_2 = f - BlsScalar::from(2);
     let f_3 = f - BlsScalar::from(3);
@@ -30,7 +30,7 @@ fn delta(f: BlsScalar) -> BlsScalar {
 // E = 3(a+b+c) - 2F
 // F = w[w(4w - 18(a+b) + 81) + 18(a^2 + b^2) - 81(a+b) + 83]
 #[allow(non_snake_case)]
-fn delta_xor_and(
+pub(crate) fn delta_xor_and(
     a: &BlsScalar,
     b: &BlsScalar,
     w: &BlsScalar,
@@ -0,0 +1,56 @@
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this
+// file, You can obtain one at http://mozilla.org/MPL/2.0/.
+//
+// Copyright (c) DUSK NETWORK. All rights reserved.
+
+mod proverkey;
+mod verifierkey;
+
+pub use proverkey::ProverKey;
+pub use verifierkey::VerifierKey;
+
+/// Common functionality across both the ProverKey and VerifierKey are listed below
+///
+///
+///
+///
+use dusk_bls12_381::BlsScalar;
+
+// Compresses a lookup row into a single scalar:
+// a + zeta * b + zeta^2 * c + zeta^3 * d
+pub(crate) fn compress(
+    a: &BlsScalar,
+    b: &BlsScalar,
+    c: &BlsScalar,
+    d: &BlsScalar,
+    zeta: &BlsScalar,
+) -> BlsScalar {
+    a + zeta * (b + zeta * (c + zeta * d))
+}
+
+// Computes the numerator of the lookup grand product:
+// (1 + delta)(epsilon + f)(epsilon(1 + delta) + t + delta * t_next)
+pub(crate) fn lookup_numerator(
+    (delta, epsilon): (&BlsScalar, &BlsScalar),
+    f: &BlsScalar,
+    t: &BlsScalar,
+    t_next: &BlsScalar,
+) -> BlsScalar {
+    let one_plus_delta = delta + BlsScalar::one();
+    let epsilon_one_plus_delta = epsilon * one_plus_delta;
+    one_plus_delta * (epsilon + f) * (epsilon_one_plus_delta + t + (delta * t_next))
+}
+
+// Computes the denominator of the lookup grand product:
+// (epsilon(1 + delta) + h_1 + delta * h_2)(epsilon(1 + delta) + h_2 + delta * h_1_next)
+pub(crate) fn lookup_denominator(
+    (delta, epsilon): (&BlsScalar, &BlsScalar),
+    h_1: &BlsScalar,
+    h_2: &BlsScalar,
+    h_1_next: &BlsScalar,
+) -> BlsScalar {
+    let epsilon_one_plus_delta = epsilon * (delta + BlsScalar::one());
+    (epsilon_one_plus_delta + h_1 + (delta * h_2))
+        * (epsilon_one_plus_delta + h_2 + (delta * h_1_next))
+}
@@ -0,0 +1,100 @@
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this
+// file, You can obtain one at http://mozilla.org/MPL/2.0/.
+//
+// Copyright (c) DUSK NETWORK. All rights reserved.
+
+#![allow(clippy::too_many_arguments)]
+use super::{compress, lookup_denominator, lookup_numerator};
+use crate::fft::{EvaluationDomain, Evaluations, Polynomial};
+use dusk_bls12_381::BlsScalar;
+
+#[derive(Debug, Eq, PartialEq, Clone)]
+pub struct ProverKey {
+    pub q_lookup: (Polynomial, Evaluations),
+    pub q_c: (Polynomial, Evaluations),
+    // Columns of the lookup table, the fourth one holds the table ids
+    pub table_1: Polynomial,
+    pub table_2: Polynomial,
+    pub table_3: Polynomial,
+    pub table_4: Polynomial,
+}
+
+impl ProverKey {
+    /// Compresses the columns of the lookup table into a single polynomial
+    /// using the `zeta` challenge.
+    pub(crate) fn compute_table_poly(&self, zeta: &BlsScalar) -> Polynomial {
+        let mut table_poly = &self.table_4 * zeta;
+        table_poly += &self.table_3;
+        let mut table_poly = &table_poly * zeta;
+        table_poly += &self.table_2;
+        let mut table_poly = &table_poly * zeta;
+        table_poly += &self.table_1;
+        table_poly
+    }
+
+    pub(crate) fn compute_quotient_i(
+        &self,
+        index: usize,
+        lookup_separation_challenge: &BlsScalar,
+        (zeta, delta, epsilon): (&BlsScalar, &BlsScalar, &BlsScalar),
+        (w_l_i, w_r_i, w_o_i): (&BlsScalar, &BlsScalar, &BlsScalar),
+        f_i: &BlsScalar,
+        (h_1_i, h_1_i_next): (&BlsScalar, &BlsScalar),
+        h_2_i: &BlsScalar,
+        (t_i, t_i_next): (&BlsScalar, &BlsScalar),
+        (p_i, p_i_next): (&BlsScalar, &BlsScalar),
+        l1_i: &BlsScalar,
+    ) -> BlsScalar {
+        let q_lookup_i = &self.q_lookup.1[index];
+        let q_c_i = &self.q_c.1[index];
+
+        let kappa = lookup_separation_challenge.square();
+        let kappa_sq = kappa.square();
+
+        // q_lookup(X) * (a(X) + zeta * b(X) + zeta^2 * c(X) + zeta^3 * q_c(X) - f(X))
+        let a = q_lookup_i * (compress(w_l_i, w_r_i, w_o_i, q_c_i, zeta) - f_i);
+
+        // p(X) * (1 + delta)(epsilon + f(X))(epsilon(1 + delta) + t(X) + delta * t(Xw))
+        // - p(Xw) * (epsilon(1 + delta) + h_1(X) + delta * h_2(X))(epsilon(1 + delta) + h_2(X) + delta * h_1(Xw))
+        let b = (p_i * lookup_numerator((delta, epsilon), f_i, t_i, t_i_next)
+            - p_i_next * lookup_denominator((delta, epsilon), h_1_i, h_2_i, h_1_i_next))
+            * kappa;
+
+        // L_1(X) * (p(X) - 1)
+        let c = l1_i * (p_i - BlsScalar::one()) * kappa_sq;
+
+        (a + b + c) * lookup_separation_challenge
+    }
+
+    pub(crate) fn compute_linearisation(
+        &self,
+        domain: &EvaluationDomain,
+        lookup_separation_challenge: &BlsScalar,
+        (zeta, delta, epsilon): (&BlsScalar, &BlsScalar, &BlsScalar),
+        z_challenge: &BlsScalar,
+        (a_eval, b_eval, c_eval): (&BlsScalar, &BlsScalar, &BlsScalar),
+        q_c_eval: &BlsScalar,
+        f_eval: &BlsScalar,
+        (table_eval, table_next_eval): (&BlsScalar, &BlsScalar),
+        p_poly: &Polynomial,
+    ) -> Polynomial {
+        let q_lookup_poly = &self.q_lookup.0;
+
+        let kappa = lookup_separation_challenge.square();
+        let kappa_sq = kappa.square();
+
+        // q_lookup(X) * (a_eval + zeta * b_eval + zeta^2 * c_eval + zeta^3 * q_c_eval - f_eval)
+        let a = compress(a_eval, b_eval, c_eval, q_c_eval, zeta) - f_eval;
+        let a = q_lookup_poly * &(a * lookup_separation_challenge);
+
+        // p(X) * [(1 + delta)(epsilon + f_eval)(epsilon(1 + delta) + t_eval + delta * t_next_eval) * kappa
+        // + L_1(z) * kappa^2]
+        let l1_eval = domain.evaluate_all_lagrange_coefficients(*z_challenge)[0];
+        let b = lookup_numerator((delta, epsilon), f_eval, table_eval, table_next_eval) * kappa
+            + l1_eval * kappa_sq;
+        let b = p_poly * &(b * lookup_separation_challenge);
+
+        &a + &b
+    }
+}
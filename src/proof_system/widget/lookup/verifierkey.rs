@@ -0,0 +1,68 @@
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this
+// file, You can obtain one at http://mozilla.org/MPL/2.0/.
+//
+// Copyright (c) DUSK NETWORK. All rights reserved.
+
+use super::{compress, lookup_numerator};
+use crate::commitment_scheme::kzg10::Commitment;
+use crate::proof_system::linearisation_poly::ProofEvaluations;
+use dusk_bls12_381::{BlsScalar, G1Affine};
+
+#[derive(Debug, PartialEq, Eq, Copy, Clone)]
+pub struct VerifierKey {
+    pub q_lookup: Commitment,
+    // Commitments to the columns of the lookup table
+    pub table_1: Commitment,
+    pub table_2: Commitment,
+    pub table_3: Commitment,
+    pub table_4: Commitment,
+}
+
+impl VerifierKey {
+    /// Computes the commitment to the lookup table compressed with the `zeta` challenge.
+    pub(crate) fn compute_table_commitment(&self, zeta: &BlsScalar) -> Commitment {
+        let zeta_sq = zeta.square();
+        let zeta_cu = zeta_sq * zeta;
+        let table_comm = self.table_1.0
+            + self.table_2.0 * zeta
+            + self.table_3.0 * zeta_sq
+            + self.table_4.0 * zeta_cu;
+        Commitment::from_projective(table_comm)
+    }
+
+    #[allow(clippy::too_many_arguments)]
+    pub(crate) fn compute_linearisation_commitment(
+        &self,
+        lookup_separation_challenge: &BlsScalar,
+        (zeta, delta, epsilon): (&BlsScalar, &BlsScalar, &BlsScalar),
+        scalars: &mut Vec<BlsScalar>,
+        points: &mut Vec<G1Affine>,
+        evaluations: &ProofEvaluations,
+        l1_eval: &BlsScalar,
+        p_comm: G1Affine,
+    ) {
+        let kappa = lookup_separation_challenge.square();
+        let kappa_sq = kappa.square();
+
+        let a = compress(
+            &evaluations.a_eval,
+            &evaluations.b_eval,
+            &evaluations.c_eval,
+            &evaluations.q_c_eval,
+            zeta,
+        ) - evaluations.f_eval;
+        scalars.push(a * lookup_separation_challenge);
+        points.push(self.q_lookup.0);
+
+        let b = lookup_numerator(
+            (delta, epsilon),
+            &evaluations.f_eval,
+            &evaluations.table_eval,
+            &evaluations.table_next_eval,
+        ) * kappa
+            + l1_eval * kappa_sq;
+        scalars.push(b * lookup_separation_challenge);
+        points.push(p_comm);
+    }
+}
@@ -5,44 +5,63 @@
 // Copyright (c) DUSK NETWORK. All rights reserved.
 
 pub mod arithmetic;
+pub mod custom;
 pub mod ecc;
 pub mod logic;
+pub mod lookup;
 pub mod permutation;
 pub mod range;
+pub mod sbox;
 
-use crate::fft::Evaluations;
-use crate::transcript::TranscriptProtocol;
+use super::proof_system_errors::ProofErrors;
+use super::PublicInputLayout;
+use crate::fft::{Evaluations, Polynomial};
+use crate::serialisation::SerialisationErrors;
+use crate::transcript::{PoseidonTranscript, TranscriptProtocol};
 use anyhow::{Error, Result};
-use merlin::Transcript;
+use custom::CustomGate;
+use dusk_bls12_381::BlsScalar;
 use serde::de::Visitor;
 use serde::{self, Deserialize, Deserializer, Serialize, Serializer};
+use std::collections::BTreeSet;
+use std::sync::Arc;
 
 /// PLONK circuit proving key
 #[derive(Debug, PartialEq, Eq, Clone)]
 pub struct ProverKey {
     /// Circuit size
     pub n: usize,
+    /// Digest of the `VerifierKey` of the circuit, appended to the transcript
+    /// of every proof
+    pub verifier_key_digest: BlsScalar,
+    /// Digest of the circuit, as returned by
+    /// `StandardComposer::circuit_digest`
+    pub circuit_digest: BlsScalar,
     /// ProverKey for arithmetic gate
     pub arithmetic: arithmetic::ProverKey,
     /// ProverKey for logic gate
     pub logic: logic::ProverKey,
     /// ProverKey for range gate
     pub range: range::ProverKey,
-    /// ProverKey for fixed base curve addition gates
-    pub fixed_base: ecc::scalar_mul::fixed_base::ProverKey,
     /// ProverKey for permutation checks
     pub permutation: permutation::ProverKey,
     /// ProverKey for variable base curve addition gates
     pub variable_base: ecc::curve_addition::ProverKey,
-    // Pre-processes the 4n Evaluations for the vanishing polynomial, so they do not
+    /// ProverKey for lookup gates
+    pub lookup: lookup::ProverKey,
+    /// ProverKey for Poseidon S-box gates
+    pub sbox: sbox::ProverKey,
+    /// ProverKeys for the custom gates, in the order they were registered
+    pub custom: Vec<custom::ProverKey>,
+    // Pre-processes the 8n Evaluations for the vanishing polynomial, so they do not
     // need to be computed at the proving stage.
     // Note: With this, we can combine all parts of the quotient polynomial in their evaluation phase and
     // divide by the quotient polynomial without having to perform IFFT
-    pub(crate) v_h_coset_4n: Evaluations,
+    pub(crate) v_h_coset_8n: Evaluations,
 }
 
 /// PLONK circuit verification key
-#[derive(Debug, PartialEq, Eq, Copy, Clone)]
+#[derive(Debug, PartialEq, Eq, Clone)]
 pub struct VerifierKey {
     /// Circuit size
     pub n: usize,
@@ -52,12 +71,20 @@ pub struct VerifierKey {
     pub logic: logic::VerifierKey,
     /// VerifierKey for range gates
     pub range: range::VerifierKey,
-    /// VerifierKey for fixed base curve addition gates
-    pub fixed_base: ecc::scalar_mul::fixed_base::VerifierKey,
     /// VerifierKey for variable base curve addition gates
     pub variable_base: ecc::curve_addition::VerifierKey,
     /// VerifierKey for permutation checks
     pub permutation: permutation::VerifierKey,
+    /// VerifierKey for lookup gates
+    pub lookup: lookup::VerifierKey,
+    /// VerifierKey for Poseidon S-box gates
+    pub sbox: sbox::VerifierKey,
+    /// VerifierKeys for the custom gates, in the order they were registered
+    pub custom: Vec<custom::VerifierKey>,
+    /// Names and positions of the public inputs declared in the circuit
+    pub public_inputs: PublicInputLayout,
+    /// Indices of the gates holding a public input, in increasing order
+    pub public_input_gates: Vec<usize>,
 }
 
 impl_serde!(ProverKey);
@@ -90,9 +117,6 @@ impl VerifierKey {
         // Range
         write_commitment(&self.range.q_range, &mut bytes);
 
-        // Fixed base scalar mul
-        write_commitment(&self.fixed_base.q_fixed_group_add, &mut bytes);
-
         // Curve addition
         write_commitment(&self.variable_base.q_variable_group_add, &mut bytes);
 
@@ -102,13 +126,49 @@ impl VerifierKey {
         write_commitment(&self.permutation.out_sigma, &mut bytes);
         write_commitment(&self.permutation.fourth_sigma, &mut bytes);
 
+        // Lookup
+        write_commitment(&self.lookup.q_lookup, &mut bytes);
+        write_commitment(&self.lookup.table_1, &mut bytes);
+        write_commitment(&self.lookup.table_2, &mut bytes);
+        write_commitment(&self.lookup.table_3, &mut bytes);
+        write_commitment(&self.lookup.table_4, &mut bytes);
+
+        // Poseidon S-box
+        write_commitment(&self.sbox.q_sbox, &mut bytes);
+
+        // Custom gates
+        write_u64(self.custom.len() as u64, &mut bytes);
+        for custom in self.custom.iter() {
+            custom.write(&mut bytes);
+        }
+
+        // Public inputs
+        self.public_inputs.write(&mut bytes);
+        write_u64(self.public_input_gates.len() as u64, &mut bytes);
+        for position in self.public_input_gates.iter() {
+            write_u64(*position as u64, &mut bytes);
+        }
+
         bytes
     }
     /// Deserialise a slice of bytes into a VerifierKey
+    ///
+    /// Fails if the key holds custom gates other than the built-in ones, which
+    /// can only be deserialised with [`VerifierKey::from_bytes_with_gates`].
     pub fn from_bytes(bytes: &[u8]) -> Result<VerifierKey, Error> {
+        VerifierKey::from_bytes_with_gates(bytes, &[])
+    }
+    /// Deserialise a slice of bytes into a VerifierKey, resolving its custom
+    /// gates by name among `gates`
+    pub fn from_bytes_with_gates(
+        bytes: &[u8],
+        gates: &[Arc<dyn CustomGate>],
+    ) -> Result<VerifierKey, Error> {
         use crate::serialisation::{read_commitment, read_u64};
 
-        assert_eq!(bytes.len(), VerifierKey::serialised_size());
+        if bytes.len() < VerifierKey::serialised_size() {
+            return Err(SerialisationErrors::NotEnoughBytes.into());
+        }
 
         let (n, rest) = read_u64(bytes)?;
 
@@ -124,14 +184,41 @@ impl VerifierKey {
 
         let (q_range, rest) = read_commitment(rest)?;
 
-        let (q_fixed_group_add, rest) = read_commitment(rest)?;
-
         let (q_variable_group_add, rest) = read_commitment(rest)?;
 
         let (left_sigma, rest) = read_commitment(rest)?;
         let (right_sigma, rest) = read_commitment(rest)?;
         let (out_sigma, rest) = read_commitment(rest)?;
-        let (fourth_sigma, _) = read_commitment(rest)?;
+        let (fourth_sigma, rest) = read_commitment(rest)?;
+
+        let (q_lookup, rest) = read_commitment(rest)?;
+        let (table_1, rest) = read_commitment(rest)?;
+        let (table_2, rest) = read_commitment(rest)?;
+        let (table_3, rest) = read_commitment(rest)?;
+        let (table_4, rest) = read_commitment(rest)?;
+
+        let (q_sbox, rest) = read_commitment(rest)?;
+
+        let (num_custom, mut rest) = read_u64(rest)?;
+        let mut custom = Vec::new();
+        for _ in 0..num_custom {
+            let (custom_key, next) = custom::VerifierKey::read(rest, gates)?;
+            custom.push(custom_key);
+            rest = next;
+        }
+
+        let (public_inputs, rest) = PublicInputLayout::read(rest)?;
+        let (num_public_input_gates, mut rest) = read_u64(rest)?;
+        let mut public_input_gates: Vec<usize> = Vec::new();
+        for _ in 0..num_public_input_gates {
+            let (position, next) = read_u64(rest)?;
+            let position = position as usize;
+            if position >= n as usize || public_input_gates.last() >= Some(&position) {
+                return Err(ProofErrors::InvalidPublicInputGate(position).into());
+            }
+            public_input_gates.push(position);
+            rest = next;
+        }
 
         let arithmetic = arithmetic::VerifierKey {
             q_m,
@@ -144,12 +231,6 @@ impl VerifierKey {
         };
         let logic = logic::VerifierKey { q_c, q_logic };
         let range = range::VerifierKey { q_range };
-        let fixed_base = ecc::scalar_mul::fixed_base::VerifierKey {
-            q_fixed_group_add,
-            q_l,
-            q_r,
-        };
-
         let variable_base = ecc::curve_addition::VerifierKey {
             q_variable_group_add,
         };
@@ -161,28 +242,56 @@ impl VerifierKey {
             fourth_sigma,
         };
 
+        let lookup = lookup::VerifierKey {
+            q_lookup,
+            table_1,
+            table_2,
+            table_3,
+            table_4,
+        };
+
+        let sbox = sbox::VerifierKey { q_sbox };
+
         let verifier_key = VerifierKey {
             n: n as usize,
             arithmetic,
             logic,
             range,
             variable_base,
-            fixed_base,
             permutation,
+            lookup,
+            sbox,
+            custom,
+            public_inputs,
+            public_input_gates,
         };
         Ok(verifier_key)
     }
 
-    /// Return the serialized size of a [`VerifierKey`]
+    /// Return the serialized size of a [`VerifierKey`] without custom gates
+    /// and public inputs
     pub const fn serialised_size() -> usize {
         const N_SIZE: usize = 8;
-        const NUM_COMMITMENTS: usize = 15;
+        const NUM_COMMITMENTS: usize = 20;
         const COMMITMENT_SIZE: usize = 48;
-        N_SIZE + NUM_COMMITMENTS * COMMITMENT_SIZE
+        const NUM_CUSTOM_SIZE: usize = 8;
+        const NUM_PUBLIC_INPUTS_SIZE: usize = 8;
+        const NUM_PUBLIC_INPUT_GATES_SIZE: usize = 8;
+        N_SIZE
+            + NUM_COMMITMENTS * COMMITMENT_SIZE
+            + NUM_CUSTOM_SIZE
+            + NUM_PUBLIC_INPUTS_SIZE
+            + NUM_PUBLIC_INPUT_GATES_SIZE
     }
 
-    /// Adds the circuit description to the transcript
-    pub(crate) fn seed_transcript(&self, transcript: &mut Transcript) {
+    /// Returns the digest of the circuit description, which is appended to
+    /// the transcript of every proof of the circuit.
+    ///
+    /// The digest is computed with a `PoseidonTranscript` whatever the
+    /// transcript of the proofs, so that it can be stored in the `ProverKey`
+    /// and recomputed inside a circuit.
+    pub fn digest(&self) -> BlsScalar {
+        let mut transcript = PoseidonTranscript::new(b"dusk-plonk-verifier-key");
         transcript.append_commitment(b"q_m", &self.arithmetic.q_m);
         transcript.append_commitment(b"q_l", &self.arithmetic.q_l);
         transcript.append_commitment(b"q_r", &self.arithmetic.q_r);
@@ -196,26 +305,70 @@ impl VerifierKey {
             b"q_variable_group_add",
             &self.variable_base.q_variable_group_add,
         );
-        transcript.append_commitment(b"q_fixed_group_add", &self.fixed_base.q_fixed_group_add);
 
         transcript.append_commitment(b"left_sigma", &self.permutation.left_sigma);
         transcript.append_commitment(b"right_sigma", &self.permutation.right_sigma);
         transcript.append_commitment(b"out_sigma", &self.permutation.out_sigma);
         transcript.append_commitment(b"fourth_sigma", &self.permutation.fourth_sigma);
 
+        transcript.append_commitment(b"q_lookup", &self.lookup.q_lookup);
+        transcript.append_commitment(b"table_1", &self.lookup.table_1);
+        transcript.append_commitment(b"table_2", &self.lookup.table_2);
+        transcript.append_commitment(b"table_3", &self.lookup.table_3);
+        transcript.append_commitment(b"table_4", &self.lookup.table_4);
+
+        transcript.append_commitment(b"q_sbox", &self.sbox.q_sbox);
+
+        for custom in self.custom.iter() {
+            transcript.append_message(b"custom_gate", custom.gate.name().as_bytes());
+            for selector in custom.selectors.iter() {
+                transcript.append_commitment(b"q_custom", selector);
+            }
+        }
+
         // Append circuit size to transcript
         transcript.circuit_domain_sep(self.n as u64);
+
+        for (name, position) in self.public_inputs.iter() {
+            transcript.append_message(b"public_input", name.as_bytes());
+            transcript.append_scalar(b"position", &BlsScalar::from(position as u64));
+        }
+        for position in self.public_input_gates.iter() {
+            transcript.append_scalar(b"pi_gate", &BlsScalar::from(*position as u64));
+        }
+
+        transcript.challenge_scalar(b"digest")
+    }
+
+    /// Checks that the public inputs given along with the index of their
+    /// gate are held by distinct gates of the circuit holding a public input.
+    pub(crate) fn check_public_input_gates(
+        &self,
+        pub_inputs: &[(usize, BlsScalar)],
+    ) -> Result<(), Error> {
+        let mut seen = BTreeSet::new();
+        for (index, _) in pub_inputs.iter() {
+            if self.public_input_gates.binary_search(index).is_err() {
+                return Err(ProofErrors::InvalidPublicInputGate(*index).into());
+            }
+            if !seen.insert(*index) {
+                return Err(ProofErrors::DuplicatePublicInputGate(*index).into());
+            }
+        }
+        Ok(())
     }
 }
 
 impl ProverKey {
     /// Serialises a ProverKey struct into bytes
     pub fn to_bytes(&self) -> Vec<u8> {
-        use crate::serialisation::{write_evaluations, write_polynomial, write_u64};
+        use crate::serialisation::{write_evaluations, write_polynomial, write_scalar, write_u64};
 
         let mut bytes = Vec::with_capacity(ProverKey::serialised_size(self.n));
 
         write_u64(self.n as u64, &mut bytes);
+        write_scalar(&self.verifier_key_digest, &mut bytes);
+        write_scalar(&self.circuit_digest, &mut bytes);
 
         // Arithmetic
         write_polynomial(&self.arithmetic.q_m.0, &mut bytes);
@@ -247,10 +400,6 @@ impl ProverKey {
         write_polynomial(&self.range.q_range.0, &mut bytes);
         write_evaluations(&self.range.q_range.1, &mut bytes);
 
-        // BlsScalar multiplication
-        write_polynomial(&self.fixed_base.q_fixed_group_add.0, &mut bytes);
-        write_evaluations(&self.fixed_base.q_fixed_group_add.1, &mut bytes);
-
         // Curve addition
         write_polynomial(&self.variable_base.q_variable_group_add.0, &mut bytes);
         write_evaluations(&self.variable_base.q_variable_group_add.1, &mut bytes);
@@ -269,79 +418,128 @@ impl ProverKey {
         write_evaluations(&self.permutation.fourth_sigma.1, &mut bytes);
         write_evaluations(&self.permutation.linear_evaluations, &mut bytes);
 
-        write_evaluations(&self.v_h_coset_4n, &mut bytes);
+        // Lookup
+        write_polynomial(&self.lookup.q_lookup.0, &mut bytes);
+        write_evaluations(&self.lookup.q_lookup.1, &mut bytes);
+
+        write_polynomial(&self.lookup.table_1, &mut bytes);
+        write_polynomial(&self.lookup.table_2, &mut bytes);
+        write_polynomial(&self.lookup.table_3, &mut bytes);
+        write_polynomial(&self.lookup.table_4, &mut bytes);
+
+        // Poseidon S-box
+        write_polynomial(&self.sbox.q_sbox.0, &mut bytes);
+        write_evaluations(&self.sbox.q_sbox.1, &mut bytes);
+
+        write_evaluations(&self.v_h_coset_8n, &mut bytes);
+
+        // Custom gates
+        write_u64(self.custom.len() as u64, &mut bytes);
+        for custom in self.custom.iter() {
+            custom.write(&mut bytes);
+        }
 
         bytes
     }
     /// Deserialises a slice of bytes into a ProverKey
+    ///
+    /// Fails if the key holds custom gates other than the built-in ones, which
+    /// can only be deserialised with [`ProverKey::from_bytes_with_gates`].
     pub fn from_bytes(bytes: &[u8]) -> Result<ProverKey, Error> {
-        use crate::serialisation::{read_evaluations, read_polynomial, read_u64};
+        ProverKey::from_bytes_with_gates(bytes, &[])
+    }
+    /// Deserialises a slice of bytes into a ProverKey, resolving its custom
+    /// gates by name among `gates`
+    pub fn from_bytes_with_gates(
+        bytes: &[u8],
+        gates: &[Arc<dyn CustomGate>],
+    ) -> Result<ProverKey, Error> {
+        use crate::serialisation::{read_evaluations, read_polynomial, read_scalar, read_u64};
 
         let (n, rest) = read_u64(bytes)?;
-        let domain = crate::fft::EvaluationDomain::new((4 * n) as usize).unwrap();
+        let (verifier_key_digest, rest) = read_scalar(rest)?;
+        let (circuit_digest, rest) = read_scalar(rest)?;
+        let domain = crate::fft::EvaluationDomain::new((8 * n) as usize).unwrap();
 
-        let (q_m_poly, rest) = read_polynomial(&rest)?;
-        let (q_m_evals, rest) = read_evaluations(domain, &rest)?;
+        let (q_m_poly, rest) = read_polynomial(rest)?;
+        let (q_m_evals, rest) = read_evaluations(domain.clone(), rest)?;
         let q_m = (q_m_poly, q_m_evals);
 
-        let (q_l_poly, rest) = read_polynomial(&rest)?;
-        let (q_l_evals, rest) = read_evaluations(domain, &rest)?;
+        let (q_l_poly, rest) = read_polynomial(rest)?;
+        let (q_l_evals, rest) = read_evaluations(domain.clone(), rest)?;
         let q_l = (q_l_poly, q_l_evals);
 
-        let (q_r_poly, rest) = read_polynomial(&rest)?;
-        let (q_r_evals, rest) = read_evaluations(domain, &rest)?;
+        let (q_r_poly, rest) = read_polynomial(rest)?;
+        let (q_r_evals, rest) = read_evaluations(domain.clone(), rest)?;
         let q_r = (q_r_poly, q_r_evals);
 
-        let (q_o_poly, rest) = read_polynomial(&rest)?;
-        let (q_o_evals, rest) = read_evaluations(domain, &rest)?;
+        let (q_o_poly, rest) = read_polynomial(rest)?;
+        let (q_o_evals, rest) = read_evaluations(domain.clone(), rest)?;
         let q_o = (q_o_poly, q_o_evals);
 
-        let (q_4_poly, rest) = read_polynomial(&rest)?;
-        let (q_4_evals, rest) = read_evaluations(domain, &rest)?;
+        let (q_4_poly, rest) = read_polynomial(rest)?;
+        let (q_4_evals, rest) = read_evaluations(domain.clone(), rest)?;
         let q_4 = (q_4_poly, q_4_evals);
 
-        let (q_c_poly, rest) = read_polynomial(&rest)?;
-        let (q_c_evals, rest) = read_evaluations(domain, &rest)?;
+        let (q_c_poly, rest) = read_polynomial(rest)?;
+        let (q_c_evals, rest) = read_evaluations(domain.clone(), rest)?;
         let q_c = (q_c_poly, q_c_evals);
 
-        let (q_arith_poly, rest) = read_polynomial(&rest)?;
-        let (q_arith_evals, rest) = read_evaluations(domain, &rest)?;
+        let (q_arith_poly, rest) = read_polynomial(rest)?;
+        let (q_arith_evals, rest) = read_evaluations(domain.clone(), rest)?;
         let q_arith = (q_arith_poly, q_arith_evals);
 
-        let (q_logic_poly, rest) = read_polynomial(&rest)?;
-        let (q_logic_evals, rest) = read_evaluations(domain, &rest)?;
+        let (q_logic_poly, rest) = read_polynomial(rest)?;
+        let (q_logic_evals, rest) = read_evaluations(domain.clone(), rest)?;
         let q_logic = (q_logic_poly, q_logic_evals);
 
-        let (q_range_poly, rest) = read_polynomial(&rest)?;
-        let (q_range_evals, rest) = read_evaluations(domain, &rest)?;
+        let (q_range_poly, rest) = read_polynomial(rest)?;
+        let (q_range_evals, rest) = read_evaluations(domain.clone(), rest)?;
         let q_range = (q_range_poly, q_range_evals);
 
-        let (q_fixed_group_add_poly, rest) = read_polynomial(&rest)?;
-        let (q_fixed_group_add_evals, rest) = read_evaluations(domain, &rest)?;
-        let q_fixed_group_add = (q_fixed_group_add_poly, q_fixed_group_add_evals);
-
-        let (q_variable_group_add_poly, rest) = read_polynomial(&rest)?;
-        let (q_variable_group_add_evals, rest) = read_evaluations(domain, &rest)?;
+        let (q_variable_group_add_poly, rest) = read_polynomial(rest)?;
+        let (q_variable_group_add_evals, rest) = read_evaluations(domain.clone(), rest)?;
         let q_variable_group_add = (q_variable_group_add_poly, q_variable_group_add_evals);
 
-        let (left_sigma_poly, rest) = read_polynomial(&rest)?;
-        let (left_sigma_evals, rest) = read_evaluations(domain, &rest)?;
+        let (left_sigma_poly, rest) = read_polynomial(rest)?;
+        let (left_sigma_evals, rest) = read_evaluations(domain.clone(), rest)?;
         let left_sigma = (left_sigma_poly, left_sigma_evals);
 
-        let (right_sigma_poly, rest) = read_polynomial(&rest)?;
-        let (right_sigma_evals, rest) = read_evaluations(domain, &rest)?;
+        let (right_sigma_poly, rest) = read_polynomial(rest)?;
+        let (right_sigma_evals, rest) = read_evaluations(domain.clone(), rest)?;
         let right_sigma = (right_sigma_poly, right_sigma_evals);
 
-        let (out_sigma_poly, rest) = read_polynomial(&rest)?;
-        let (out_sigma_evals, rest) = read_evaluations(domain, &rest)?;
+        let (out_sigma_poly, rest) = read_polynomial(rest)?;
+        let (out_sigma_evals, rest) = read_evaluations(domain.clone(), rest)?;
         let out_sigma = (out_sigma_poly, out_sigma_evals);
 
-        let (fourth_sigma_poly, rest) = read_polynomial(&rest)?;
-        let (fourth_sigma_evals, rest) = read_evaluations(domain, &rest)?;
+        let (fourth_sigma_poly, rest) = read_polynomial(rest)?;
+        let (fourth_sigma_evals, rest) = read_evaluations(domain.clone(), rest)?;
         let fourth_sigma = (fourth_sigma_poly, fourth_sigma_evals);
-        let (linear_evaluations, rest) = read_evaluations(domain, rest)?;
+        let (linear_evaluations, rest) = read_evaluations(domain.clone(), rest)?;
+
+        let (q_lookup_poly, rest) = read_polynomial(rest)?;
+        let (q_lookup_evals, rest) = read_evaluations(domain.clone(), rest)?;
+        let q_lookup = (q_lookup_poly, q_lookup_evals);
+
+        let (table_1, rest) = read_polynomial(rest)?;
+        let (table_2, rest) = read_polynomial(rest)?;
+        let (table_3, rest) = read_polynomial(rest)?;
+        let (table_4, rest) = read_polynomial(rest)?;
+
+        let (q_sbox_poly, rest) = read_polynomial(rest)?;
+        let (q_sbox_evals, rest) = read_evaluations(domain.clone(), rest)?;
+        let q_sbox = (q_sbox_poly, q_sbox_evals);
 
-        let (v_h_coset_4n, _) = read_evaluations(domain, rest)?;
+        let (v_h_coset_8n, rest) = read_evaluations(domain.clone(), rest)?;
+
+        let (num_custom, mut rest) = read_u64(rest)?;
+        let mut custom = Vec::new();
+        for _ in 0..num_custom {
+            let (custom_key, next) = custom::ProverKey::read(rest, &domain, gates)?;
+            custom.push(custom_key);
+            rest = next;
+        }
 
         let arithmetic = arithmetic::ProverKey {
             q_m,
@@ -360,13 +558,6 @@ impl ProverKey {
 
         let range = range::ProverKey { q_range };
 
-        let fixed_base = ecc::scalar_mul::fixed_base::ProverKey {
-            q_fixed_group_add,
-            q_l,
-            q_r,
-            q_c,
-        };
-
         let permutation = permutation::ProverKey {
             left_sigma,
             right_sigma,
@@ -379,35 +570,133 @@ impl ProverKey {
             q_variable_group_add,
         };
 
+        let lookup = lookup::ProverKey {
+            q_lookup,
+            q_c: q_c.clone(),
+            table_1,
+            table_2,
+            table_3,
+            table_4,
+        };
+
+        let sbox = sbox::ProverKey { q_sbox, q_c };
+
         let prover_key = ProverKey {
             n: n as usize,
+            verifier_key_digest,
+            circuit_digest,
             arithmetic,
             logic,
             range,
-            fixed_base,
             variable_base,
             permutation,
-            v_h_coset_4n,
+            lookup,
+            sbox,
+            custom,
+            v_h_coset_8n,
         };
 
         Ok(prover_key)
     }
 
     fn serialised_size(n: usize) -> usize {
+        const N_SIZE: usize = 8;
         const SIZE_SCALAR: usize = 32;
 
-        const NUM_POLYNOMIALS: usize = 15;
+        const NUM_POLYNOMIALS: usize = 20;
         let num_poly_scalars = n;
 
-        const NUM_EVALUATIONS: usize = 16;
-        let num_eval_scalars = 4 * n;
+        const NUM_EVALUATIONS: usize = 17;
+        let num_eval_scalars = 8 * n;
 
-        (NUM_POLYNOMIALS * num_poly_scalars * SIZE_SCALAR)
+        N_SIZE
+            + 2 * SIZE_SCALAR
+            + (NUM_POLYNOMIALS * num_poly_scalars * SIZE_SCALAR)
             + (NUM_EVALUATIONS * num_eval_scalars * SIZE_SCALAR)
     }
 
-    pub(crate) fn v_h_coset_4n(&self) -> &Evaluations {
-        &self.v_h_coset_4n
+    pub(crate) fn v_h_coset_8n(&self) -> &Evaluations {
+        &self.v_h_coset_8n
+    }
+
+    /// Returns whether the key holds the evaluations of its polynomials over
+    /// the 8n coset, which the keys of a low-memory `Prover` do not.
+    pub(crate) fn has_coset_evaluations(&self) -> bool {
+        !self.v_h_coset_8n.evals.is_empty()
+    }
+
+    /// Returns a key holding the evaluations of the selector, permutation
+    /// and vanishing polynomials computed by `evaluate` from their
+    /// coefficients, but none of the coefficients.
+    ///
+    /// The low-memory `Prover` computes the quotient polynomial over one part
+    /// of the 8n coset at a time with such a key.
+    pub(crate) fn evaluate_with<F>(&self, evaluate: F) -> ProverKey
+    where
+        F: Fn(&Polynomial) -> Evaluations,
+    {
+        let eval = |(poly, _): &(Polynomial, Evaluations)| (Polynomial::zero(), evaluate(poly));
+
+        // X^n - 1
+        let mut v_h_coeffs = vec![BlsScalar::zero(); self.n + 1];
+        v_h_coeffs[0] = -BlsScalar::one();
+        v_h_coeffs[self.n] = BlsScalar::one();
+
+        ProverKey {
+            n: self.n,
+            verifier_key_digest: self.verifier_key_digest,
+            circuit_digest: self.circuit_digest,
+            arithmetic: arithmetic::ProverKey {
+                q_m: eval(&self.arithmetic.q_m),
+                q_l: eval(&self.arithmetic.q_l),
+                q_r: eval(&self.arithmetic.q_r),
+                q_o: eval(&self.arithmetic.q_o),
+                q_c: eval(&self.arithmetic.q_c),
+                q_4: eval(&self.arithmetic.q_4),
+                q_arith: eval(&self.arithmetic.q_arith),
+            },
+            logic: logic::ProverKey {
+                q_c: eval(&self.logic.q_c),
+                q_logic: eval(&self.logic.q_logic),
+            },
+            range: range::ProverKey {
+                q_range: eval(&self.range.q_range),
+            },
+            permutation: permutation::ProverKey {
+                left_sigma: eval(&self.permutation.left_sigma),
+                right_sigma: eval(&self.permutation.right_sigma),
+                out_sigma: eval(&self.permutation.out_sigma),
+                fourth_sigma: eval(&self.permutation.fourth_sigma),
+                linear_evaluations: evaluate(&Polynomial::from_coefficients_vec(vec![
+                    BlsScalar::zero(),
+                    BlsScalar::one(),
+                ])),
+            },
+            variable_base: ecc::curve_addition::ProverKey {
+                q_variable_group_add: eval(&self.variable_base.q_variable_group_add),
+            },
+            lookup: lookup::ProverKey {
+                q_lookup: eval(&self.lookup.q_lookup),
+                q_c: eval(&self.lookup.q_c),
+                table_1: Polynomial::zero(),
+                table_2: Polynomial::zero(),
+                table_3: Polynomial::zero(),
+                table_4: Polynomial::zero(),
+            },
+            sbox: sbox::ProverKey {
+                q_c: eval(&self.sbox.q_c),
+                q_sbox: eval(&self.sbox.q_sbox),
+            },
+            custom: self
+                .custom
+                .iter()
+                .map(|custom| custom::ProverKey {
+                    gate: custom.gate.clone(),
+                    selectors: custom.selectors.iter().map(eval).collect(),
+                })
+                .collect(),
+            v_h_coset_8n: evaluate(&Polynomial::from_coefficients_vec(v_h_coeffs)),
+        }
     }
 }
 
@@ -423,8 +712,8 @@ mod test {
     }
 
     fn rand_evaluations(n: usize) -> Evaluations {
-        let domain = EvaluationDomain::new(4 * n).unwrap();
-        let values: Vec<_> = (0..4 * n)
+        let domain = EvaluationDomain::new(8 * n).unwrap();
+        let values: Vec<_> = (0..8 * n)
             .map(|_| BlsScalar::random(&mut rand::thread_rng()))
             .collect();
         let evaluations = Evaluations::from_vec_and_domain(values, domain);
@@ -447,8 +736,6 @@ mod test {
 
         let q_range = rand_poly_eval(n);
 
-        let q_fixed_group_add = rand_poly_eval(n);
-
         let q_variable_group_add = rand_poly_eval(n);
 
         let left_sigma = rand_poly_eval(n);
@@ -457,7 +744,15 @@ mod test {
         let fourth_sigma = rand_poly_eval(n);
         let linear_evaluations = rand_evaluations(n);
 
-        let v_h_coset_4n = rand_evaluations(n);
+        let q_lookup = rand_poly_eval(n);
+        let table_1 = Polynomial::rand(n, &mut rand::thread_rng());
+        let table_2 = Polynomial::rand(n, &mut rand::thread_rng());
+        let table_3 = Polynomial::rand(n, &mut rand::thread_rng());
+        let table_4 = Polynomial::rand(n, &mut rand::thread_rng());
+
+        let q_sbox = rand_poly_eval(n);
+
+        let v_h_coset_8n = rand_evaluations(n);
 
         let arithmetic = arithmetic::ProverKey {
             q_m,
@@ -476,13 +771,6 @@ mod test {
 
         let range = range::ProverKey { q_range };
 
-        let fixed_base = ecc::scalar_mul::fixed_base::ProverKey {
-            q_fixed_group_add,
-            q_l,
-            q_r,
-            q_c,
-        };
-
         let permutation = permutation::ProverKey {
             left_sigma,
             right_sigma,
@@ -495,15 +783,30 @@ mod test {
             q_variable_group_add,
         };
 
+        let lookup = lookup::ProverKey {
+            q_lookup,
+            q_c: q_c.clone(),
+            table_1,
+            table_2,
+            table_3,
+            table_4,
+        };
+
+        let sbox = sbox::ProverKey { q_sbox, q_c };
+
         let prover_key = ProverKey {
             arithmetic,
             logic,
-            fixed_base,
             range,
             variable_base,
             permutation,
-            v_h_coset_4n,
+            lookup,
+            sbox,
+            custom: vec![],
+            v_h_coset_8n,
             n,
+            verifier_key_digest: BlsScalar::random(&mut rand::thread_rng()),
+            circuit_digest: BlsScalar::random(&mut rand::thread_rng()),
         };
 
         let prover_key_bytes = prover_key.to_bytes();
@@ -529,7 +832,6 @@ mod test {
 
         let q_range = Commitment::from_affine(G1Affine::generator());
 
-        let q_fixed_group_add = Commitment::from_affine(G1Affine::generator());
         let q_variable_group_add = Commitment::from_affine(G1Affine::generator());
 
         let q_logic = Commitment::from_affine(G1Affine::generator());
@@ -539,6 +841,14 @@ mod test {
         let out_sigma = Commitment::from_affine(G1Affine::generator());
         let fourth_sigma = Commitment::from_affine(G1Affine::generator());
 
+        let q_lookup = Commitment::from_affine(G1Affine::generator());
+        let table_1 = Commitment::from_affine(G1Affine::generator());
+        let table_2 = Commitment::from_affine(G1Affine::generator());
+        let table_3 = Commitment::from_affine(G1Affine::generator());
+        let table_4 = Commitment::from_affine(G1Affine::generator());
+
+        let q_sbox = Commitment::from_affine(G1Affine::generator());
+
         let arithmetic = arithmetic::VerifierKey {
             q_m,
             q_l,
@@ -553,11 +863,6 @@ mod test {
 
         let range = range::VerifierKey { q_range };
 
-        let fixed_base = ecc::scalar_mul::fixed_base::VerifierKey {
-            q_fixed_group_add,
-            q_l,
-            q_r,
-        };
         let variable_base = ecc::curve_addition::VerifierKey {
             q_variable_group_add,
         };
@@ -569,19 +874,47 @@ mod test {
             fourth_sigma,
         };
 
+        let lookup = lookup::VerifierKey {
+            q_lookup,
+            table_1,
+            table_2,
+            table_3,
+            table_4,
+        };
+
+        let sbox = sbox::VerifierKey { q_sbox };
+
         let verifier_key = VerifierKey {
             n,
             arithmetic,
             logic,
             range,
-            fixed_base,
             variable_base,
             permutation,
+            lookup,
+            sbox,
+            custom: vec![],
+            public_inputs: PublicInputLayout::default(),
+            public_input_gates: vec![0, 3, n - 1],
         };
 
         let verifier_key_bytes = verifier_key.to_bytes();
         let got = VerifierKey::from_bytes(&verifier_key_bytes).unwrap();
 
         assert_eq!(got, verifier_key);
+
+        // Should fail as the gates holding a public input are out of order
+        let mut unordered = verifier_key.clone();
+        unordered.public_input_gates = vec![3, 0];
+        let err = VerifierKey::from_bytes(&unordered.to_bytes()).unwrap_err();
+        assert!(matches!(
+            err.downcast_ref::<ProofErrors>(),
+            Some(ProofErrors::InvalidPublicInputGate(0))
+        ));
+
+        // Should fail as a gate holding a public input is out of the circuit
+        let mut out_of_range = verifier_key;
+        out_of_range.public_input_gates = vec![n];
+        assert!(VerifierKey::from_bytes(&out_of_range.to_bytes()).is_err());
     }
 }
@@ -102,6 +102,7 @@ impl ProverKey {
 
     pub(crate) fn compute_linearisation(
         &self,
+        domain: &EvaluationDomain,
         z_challenge: &BlsScalar,
         (alpha, beta, gamma): (&BlsScalar, &BlsScalar, &BlsScalar),
         (a_eval, b_eval, c_eval, d_eval): (&BlsScalar, &BlsScalar, &BlsScalar, &BlsScalar),
@@ -125,8 +126,7 @@ impl ProverKey {
             &self.fourth_sigma.0,
         );
 
-        let domain = EvaluationDomain::new(z_poly.degree()).unwrap();
-        let c = self.compute_lineariser_check_is_one(&domain, z_challenge, &alpha.square(), z_poly);
+        let c = self.compute_lineariser_check_is_one(domain, z_challenge, &alpha.square(), z_poly);
         &(&a + &b) + &c
     }
     // (a_eval + beta * z_challenge + gamma)(b_eval + beta * K1 * z_challenge + gamma)(c_eval + beta * K2 * z_challenge + gamma) * alpha z(X)
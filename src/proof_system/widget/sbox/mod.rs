@@ -0,0 +1,34 @@
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this
+// file, You can obtain one at http://mozilla.org/MPL/2.0/.
+//
+// Copyright (c) DUSK NETWORK. All rights reserved.
+
+mod proverkey;
+mod verifierkey;
+
+pub use proverkey::ProverKey;
+pub use verifierkey::VerifierKey;
+
+/// Common functionality across both the ProverKey and VerifierKey are listed below
+///
+///
+///
+///
+use dusk_bls12_381::BlsScalar;
+
+// The identity we want to check is q_sbox * A = 0
+// where the fourth wire holds the square of the shifted input:
+// A = (d - (a + q_c)^2) + kappa * (c - d^2 * (a + q_c))
+fn delta_sbox(
+    a: &BlsScalar,
+    c: &BlsScalar,
+    d: &BlsScalar,
+    q_c: &BlsScalar,
+    kappa: &BlsScalar,
+) -> BlsScalar {
+    let x = a + q_c;
+    let square_consistency = d - x.square();
+    let quint_consistency = c - d.square() * x;
+    square_consistency + quint_consistency * kappa
+}
@@ -0,0 +1,51 @@
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this
+// file, You can obtain one at http://mozilla.org/MPL/2.0/.
+//
+// Copyright (c) DUSK NETWORK. All rights reserved.
+
+use super::delta_sbox;
+use crate::fft::{Evaluations, Polynomial};
+use dusk_bls12_381::BlsScalar;
+
+#[derive(Debug, Eq, PartialEq, Clone)]
+pub struct ProverKey {
+    pub q_c: (Polynomial, Evaluations),
+    pub q_sbox: (Polynomial, Evaluations),
+}
+
+impl ProverKey {
+    pub(crate) fn compute_quotient_i(
+        &self,
+        index: usize,
+        sbox_separation_challenge: &BlsScalar,
+        w_l_i: &BlsScalar,
+        w_o_i: &BlsScalar,
+        w_4_i: &BlsScalar,
+    ) -> BlsScalar {
+        let q_sbox_i = &self.q_sbox.1[index];
+        let q_c_i = &self.q_c.1[index];
+
+        let kappa = sbox_separation_challenge.square();
+
+        // ((d(X) - (a(X) + q_c(X))^2) + (c(X) - d(X)^2 * (a(X) + q_c(X))) * kappa) * Q_Sbox(X)
+        delta_sbox(w_l_i, w_o_i, w_4_i, q_c_i, &kappa) * q_sbox_i * sbox_separation_challenge
+    }
+
+    pub(crate) fn compute_linearisation(
+        &self,
+        sbox_separation_challenge: &BlsScalar,
+        a_eval: &BlsScalar,
+        c_eval: &BlsScalar,
+        d_eval: &BlsScalar,
+        q_c_eval: &BlsScalar,
+    ) -> Polynomial {
+        let q_sbox_poly = &self.q_sbox.0;
+
+        let kappa = sbox_separation_challenge.square();
+
+        let t = delta_sbox(a_eval, c_eval, d_eval, q_c_eval, &kappa) * sbox_separation_challenge;
+
+        q_sbox_poly * &t
+    }
+}
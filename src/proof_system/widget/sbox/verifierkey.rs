@@ -0,0 +1,38 @@
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this
+// file, You can obtain one at http://mozilla.org/MPL/2.0/.
+//
+// Copyright (c) DUSK NETWORK. All rights reserved.
+
+use super::delta_sbox;
+use crate::commitment_scheme::kzg10::Commitment;
+use crate::proof_system::linearisation_poly::ProofEvaluations;
+use dusk_bls12_381::{BlsScalar, G1Affine};
+
+#[derive(Debug, PartialEq, Eq, Copy, Clone)]
+pub struct VerifierKey {
+    pub q_sbox: Commitment,
+}
+
+impl VerifierKey {
+    pub(crate) fn compute_linearisation_commitment(
+        &self,
+        sbox_separation_challenge: &BlsScalar,
+        scalars: &mut Vec<BlsScalar>,
+        points: &mut Vec<G1Affine>,
+        evaluations: &ProofEvaluations,
+    ) {
+        let kappa = sbox_separation_challenge.square();
+
+        let a = delta_sbox(
+            &evaluations.a_eval,
+            &evaluations.c_eval,
+            &evaluations.d_eval,
+            &evaluations.q_c_eval,
+            &kappa,
+        );
+
+        scalars.push(a * sbox_separation_challenge);
+        points.push(self.q_sbox.0);
+    }
+}
@@ -19,6 +19,8 @@ pub enum SerialisationErrors {
     PointMalformed,
     #[error("Cannot deserialise scalar, as it is not in a canonical format")]
     BlsScalarMalformed,
+    #[error("Cannot deserialise string, as it is not valid UTF-8")]
+    StringMalformed,
 }
 
 /// Reads n bytes from slice and returns the n bytes along with the rest of the slice
@@ -177,7 +179,7 @@ mod test {
         let polynomial = Polynomial::from_coefficients_slice(&scalars);
 
         let domain = EvaluationDomain::new(scalars.len()).unwrap();
-        let evaluations = Evaluations::from_vec_and_domain(scalars.clone(), domain);
+        let evaluations = Evaluations::from_vec_and_domain(scalars.clone(), domain.clone());
 
         write_scalars(&scalars, &mut bytes);
         write_polynomial(&polynomial, &mut bytes);
@@ -6,13 +6,32 @@
 
 //! This is an extension over the [Merlin Transcript](https://docs.rs/merlin/1.0.3/merlin/struct.Transcript.html)
 //! which adds a few extra functionalities.
+//!
+//! The `Prover` and `Verifier` are generic over the `TranscriptProtocol`, so other
+//! backends such as the `PoseidonTranscript` can be used instead of Merlin.
 use crate::commitment_scheme::kzg10::Commitment;
 use dusk_bls12_381::BlsScalar;
 use merlin::Transcript;
 
+mod poseidon;
+
+pub use poseidon::PoseidonTranscript;
+
+/// Version of the protocol, appended as a domain separator to the transcript
+/// of every proof before its statement. It changes whenever the messages of
+/// the transcript do, so that a proof is only accepted by verifiers of the
+/// version it was made with.
+pub const PROTOCOL_VERSION: &[u8] = b"dusk-plonk-v1";
+
 /// Transcript adds an abstraction over the Merlin transcript
 /// For convenience
-pub trait TranscriptProtocol {
+pub trait TranscriptProtocol: Clone {
+    /// Creates a new transcript with the given `label`.
+    fn new(label: &'static [u8]) -> Self;
+
+    /// Append a `message` with the given `label`.
+    fn append_message(&mut self, label: &'static [u8], message: &[u8]);
+
     /// Append a `commitment` with the given `label`.
     fn append_commitment(&mut self, label: &'static [u8], comm: &Commitment);
 
@@ -27,12 +46,20 @@ pub trait TranscriptProtocol {
 }
 
 impl TranscriptProtocol for Transcript {
+    fn new(label: &'static [u8]) -> Self {
+        Transcript::new(label)
+    }
+
+    fn append_message(&mut self, label: &'static [u8], message: &[u8]) {
+        Transcript::append_message(self, label, message)
+    }
+
     fn append_commitment(&mut self, label: &'static [u8], comm: &Commitment) {
-        self.append_message(label, &comm.0.to_compressed());
+        Transcript::append_message(self, label, &comm.0.to_compressed());
     }
 
     fn append_scalar(&mut self, label: &'static [u8], s: &BlsScalar) {
-        self.append_message(label, &s.to_bytes())
+        Transcript::append_message(self, label, &s.to_bytes())
     }
 
     fn challenge_scalar(&mut self, label: &'static [u8]) -> BlsScalar {
@@ -43,7 +70,7 @@ impl TranscriptProtocol for Transcript {
     }
 
     fn circuit_domain_sep(&mut self, n: u64) {
-        self.append_message(b"dom-sep", b"circuit_size");
+        Transcript::append_message(self, b"dom-sep", b"circuit_size");
         self.append_u64(b"n", n);
     }
 }
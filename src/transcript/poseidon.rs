@@ -0,0 +1,149 @@
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this
+// file, You can obtain one at http://mozilla.org/MPL/2.0/.
+//
+// Copyright (c) DUSK NETWORK. All rights reserved.
+
+//! A transcript built as a duplex sponge over the Poseidon permutation.
+//!
+//! Scalars are absorbed as they are, which makes this transcript cheap to
+//! replay inside a circuit or a contract working over `BlsScalar`.
+//! Byte messages are length-prefixed and absorbed in chunks of 31 bytes, so
+//! that every chunk fits in a scalar.
+
+use super::TranscriptProtocol;
+use crate::commitment_scheme::kzg10::Commitment;
+use crate::poseidon::{Poseidon, RATE, WIDTH};
+use dusk_bls12_381::BlsScalar;
+
+/// Bytes of a message absorbed in each scalar.
+const CHUNK_SIZE: usize = 31;
+
+/// Transcript backend based on the Poseidon permutation.
+#[derive(Debug, Clone)]
+pub struct PoseidonTranscript {
+    poseidon: Poseidon,
+    state: [BlsScalar; WIDTH],
+    // Number of elements absorbed since the last permutation
+    absorbed: usize,
+}
+
+impl PoseidonTranscript {
+    /// Absorbs a scalar into the rate part of the state.
+    fn absorb(&mut self, scalar: BlsScalar) {
+        if self.absorbed == RATE {
+            self.poseidon.permute(&mut self.state);
+            self.absorbed = 0;
+        }
+        self.state[1 + self.absorbed] += scalar;
+        self.absorbed += 1;
+    }
+
+    /// Absorbs the length of `bytes` followed by its chunks.
+    fn absorb_bytes(&mut self, bytes: &[u8]) {
+        self.absorb(BlsScalar::from(bytes.len() as u64));
+        for chunk in bytes.chunks(CHUNK_SIZE) {
+            let mut buf = [0u8; 32];
+            buf[..chunk.len()].copy_from_slice(chunk);
+            // 31 bytes are always below the modulus
+            self.absorb(BlsScalar::from_bytes(&buf).unwrap());
+        }
+    }
+
+    /// Permutes the state and returns the first element of its rate part.
+    fn squeeze(&mut self) -> BlsScalar {
+        self.poseidon.permute(&mut self.state);
+        self.absorbed = 0;
+        self.state[1]
+    }
+}
+
+impl TranscriptProtocol for PoseidonTranscript {
+    fn new(label: &'static [u8]) -> Self {
+        let mut transcript = PoseidonTranscript {
+            poseidon: Poseidon::new(),
+            state: [BlsScalar::zero(); WIDTH],
+            absorbed: 0,
+        };
+        transcript.append_message(b"dom-sep", label);
+        transcript
+    }
+
+    fn append_message(&mut self, label: &'static [u8], message: &[u8]) {
+        self.absorb_bytes(label);
+        self.absorb_bytes(message);
+    }
+
+    fn append_commitment(&mut self, label: &'static [u8], comm: &Commitment) {
+        self.append_message(label, &comm.0.to_compressed());
+    }
+
+    fn append_scalar(&mut self, label: &'static [u8], s: &BlsScalar) {
+        self.absorb_bytes(label);
+        self.absorb(*s);
+    }
+
+    fn challenge_scalar(&mut self, label: &'static [u8]) -> BlsScalar {
+        self.absorb_bytes(label);
+        self.squeeze()
+    }
+
+    fn circuit_domain_sep(&mut self, n: u64) {
+        self.append_message(b"dom-sep", b"circuit_size");
+        self.append_scalar(b"n", &BlsScalar::from(n));
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::commitment_scheme::kzg10::PublicParameters;
+    use crate::constraint_system::helper::dummy_gadget;
+    use crate::proof_system::{Prover, Verifier};
+
+    #[test]
+    fn test_challenges_depend_on_transcript() {
+        let mut transcript_a = PoseidonTranscript::new(b"test");
+        let mut transcript_b = PoseidonTranscript::new(b"test");
+        let mut transcript_c = PoseidonTranscript::new(b"other test");
+
+        transcript_a.append_scalar(b"s", &BlsScalar::one());
+        transcript_b.append_scalar(b"s", &BlsScalar::one());
+        transcript_c.append_scalar(b"s", &BlsScalar::one());
+
+        let challenge_a = transcript_a.challenge_scalar(b"challenge");
+        assert_eq!(challenge_a, transcript_b.challenge_scalar(b"challenge"));
+        assert_ne!(challenge_a, transcript_c.challenge_scalar(b"challenge"));
+
+        // Later challenges depend on everything appended before them
+        transcript_a.append_scalar(b"s", &BlsScalar::from(2));
+        transcript_b.append_scalar(b"s", &BlsScalar::from(3));
+        assert_ne!(
+            transcript_a.challenge_scalar(b"challenge"),
+            transcript_b.challenge_scalar(b"challenge")
+        );
+    }
+
+    #[test]
+    fn test_prove_verify_with_poseidon_transcript() {
+        let public_parameters = PublicParameters::setup(2 * 30, &mut rand::thread_rng()).unwrap();
+        let (ck, vk) = public_parameters.trim(2 * 20).unwrap();
+
+        let mut prover = Prover::with_transcript(PoseidonTranscript::new(b"demo"));
+        dummy_gadget(10, prover.mut_cs());
+        prover.preprocess(&ck).unwrap();
+        let public_inputs = prover.cs.sparse_public_inputs();
+        let proof = prover.prove(&ck).unwrap();
+
+        let mut verifier = Verifier::with_transcript(PoseidonTranscript::new(b"demo"));
+        dummy_gadget(10, verifier.mut_cs());
+        verifier.preprocess(&ck).unwrap();
+        assert!(verifier.verify_sparse(&proof, &vk, &public_inputs).is_ok());
+
+        // Should fail as the Verifier derives the challenges with Merlin
+        let mut verifier = Verifier::new(b"demo");
+        dummy_gadget(10, verifier.mut_cs());
+        verifier.preprocess(&ck).unwrap();
+        assert!(verifier.verify_sparse(&proof, &vk, &public_inputs).is_err());
+    }
+}
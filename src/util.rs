@@ -6,7 +6,7 @@
 
 use dusk_bls12_381::{BlsScalar, G1Affine, G1Projective, G2Affine, G2Projective};
 use rand_core::RngCore;
-use rayon::iter::{IntoParallelRefIterator, ParallelIterator};
+use rayon::iter::{IndexedParallelIterator, IntoParallelRefIterator, ParallelIterator};
 
 /// Returns a vector of BlsScalars of increasing powers of x from x^0 to x^d.
 pub(crate) fn powers_of(scalar: &BlsScalar, max_degree: usize) -> Vec<BlsScalar> {
@@ -37,14 +37,18 @@ pub(crate) fn random_g2_point<R: RngCore>(rng: &mut R) -> G2Projective {
     G2Affine::generator() * random_scalar(rng)
 }
 
-/// This function is only used to generate the SRS.
+/// This function is only used to update the SRS.
 /// The intention is just to compute the resulting points
-/// of the operation `a*P, b*P, c*P ... (n-1)*P` into a `Vec`.
-pub(crate) fn slow_multiscalar_mul_single_base(
+/// of the operation `a*P, b*Q, c*R ...` into a `Vec`.
+pub(crate) fn slow_multiscalar_mul_pairwise(
     scalars: &[BlsScalar],
-    base: G1Projective,
+    bases: &[G1Affine],
 ) -> Vec<G1Projective> {
-    scalars.par_iter().map(|s| base * *s).collect()
+    scalars
+        .par_iter()
+        .zip(bases.par_iter())
+        .map(|(s, base)| base * s)
+        .collect()
 }
 
 // while we do not have batch inversion for scalars